@@ -1,5 +1,6 @@
 use project_generators::rust::RustMicroserviceGeneratorImpl;
 use crate::project_generators::rust::RustMicroserviceGenerator;
+use crate::models::ddr_format::DdrFormat;
 
 
 pub mod models;
@@ -16,18 +17,52 @@ fn main() {
     .arg(Arg::new("input")
                 .short('i')
                 .long("input")
-                .help("The input file, containing the JSON representation of the DDD"))
+                .help("The input file, containing the JSON, YAML or TOML representation of the DDD"))
+    .arg(Arg::new("format")
+                .short('f')
+                .long("format")
+                .value_parser(["json", "yaml", "yml", "toml"])
+                .help("The format of the input file. Detected from the file extension when omitted"))
     .arg(Arg::new("output")
                 .short('o')
                 .long("output")
                 .help("The output directory, where the generated service will be placed"))
+    .subcommand(Command::new("schema")
+                .about("Print the JSON Schema of the domain driven request"))
+    .args_conflicts_with_subcommands(true)
     .get_matches();
+
+    if let Some(("schema", _)) = matches.subcommand() {
+        let schema = models::ddr_schema::generate_ddr_schema();
+        println!("{}", serde_json::to_string_pretty(&schema).expect("Could not serialize the schema"));
+        return;
+    }
+
     let input = matches.get_one::<String>("input").expect("You must provide an input file");
     let output = matches.get_one::<String>("output").expect("You must provide an output directory");
 
-    let json = std::fs::read_to_string(input).expect("Could not read the input file");
-    let raw_ddr: models::ddr_req::RawDomainDrivenRequest = serde_json::from_str(&json).expect("Could not parse the input file");
-    let ddr = models::ddr_req::DomainDrivenRequest::from(raw_ddr);
+    let format = matches.get_one::<String>("format")
+        .map(|format| format.parse::<DdrFormat>().expect("Unsupported input format"))
+        .unwrap_or(DdrFormat::from_path(input));
+
+    let content = std::fs::read_to_string(input).expect("Could not read the input file");
+    let raw_ddr = match format.parse(&content) {
+        Ok(raw_ddr) => raw_ddr,
+        Err(e) => {
+            eprintln!("{input}: {e}");
+            std::process::exit(1);
+        }
+    };
+    let ddr = match models::ddr_req::DomainDrivenRequest::try_from(raw_ddr) {
+        Ok(ddr) => ddr,
+        Err(errors) => {
+            eprintln!("{input}: found {} problem(s) in the domain driven request", errors.len());
+            for error in errors {
+                eprintln!("  {error}");
+            }
+            std::process::exit(1);
+        }
+    };
    // println!("{:#?}", ddr);
     let rust_microservice_generator = RustMicroserviceGeneratorImpl {};
     rust_microservice_generator.generate_rust_microservice(ddr, output).expect("Could not generate the service");
@@ -0,0 +1,67 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use super::{entity::AttributeType, enum_type::EnumType, validation::ValidationErrorKind};
+
+/**
+ * The default value of an attribute, declared in the `defaults` of an entity, for example:
+ * `"defaults": { "active": true, "stock": 0, "created_at": "now()" }`
+ * The value is translated once into SQL, for the column definition, and into a Rust expression,
+ * which fills in the attribute when it is missing from the create payload.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttributeDefault {
+    pub value: Value,
+    pub sql: String,
+    pub rust: String,
+}
+
+impl AttributeDefault {
+    pub fn new(attribute_type: &AttributeType, value: Value) -> Result<Self, ValidationErrorKind> {
+        let (sql, rust) = match (attribute_type, &value) {
+            (AttributeType::Option(_), Value::Null) => ("NULL".to_string(), "None".to_string()),
+            (AttributeType::Option(inner_type), _) => {
+                let (sql, rust) = AttributeDefault::to_sql_and_rust(inner_type, &value)?;
+                (sql, format!("Some({})", rust))
+            },
+            _ => AttributeDefault::to_sql_and_rust(attribute_type, &value)?,
+        };
+        Ok(AttributeDefault { value, sql, rust })
+    }
+
+    fn to_sql_and_rust(attribute_type: &AttributeType, value: &Value) -> Result<(String, String), ValidationErrorKind> {
+        let invalid = || ValidationErrorKind::InvalidDefault(value.to_string(), attribute_type.to_string());
+        let now = value.as_str() == Some("now()");
+        let default = match (attribute_type, value) {
+            (AttributeType::Boolean, Value::Bool(value)) => (value.to_string().to_uppercase(), value.to_string()),
+            (AttributeType::I32, Value::Number(number)) if number.as_i64().is_some_and(|n| i32::try_from(n).is_ok()) => (number.to_string(), number.to_string()),
+            (AttributeType::I64, Value::Number(number)) if number.is_i64() => (number.to_string(), number.to_string()),
+            (AttributeType::F32 | AttributeType::F64, Value::Number(number)) => {
+                let float = number.as_f64().ok_or_else(invalid)?;
+                (number.to_string(), format!("{:?}", float))
+            },
+            (AttributeType::Decimal(_), Value::Number(_) | Value::String(_)) => {
+                let decimal = value.as_str().map(str::to_string).unwrap_or(value.to_string());
+                decimal.parse::<f64>().ok().filter(|decimal| decimal.is_finite()).ok_or_else(invalid)?;
+                (decimal.clone(), format!("rust_decimal::Decimal::from_str_exact({:?}).unwrap()", decimal))
+            },
+            (AttributeType::String | AttributeType::Text, Value::String(value)) => (sql_string(value), format!("{:?}.to_string()", value)),
+            (AttributeType::VarChar(max_length), Value::String(value)) if value.chars().count() <= *max_length as usize => (sql_string(value), format!("{:?}.to_string()", value)),
+            (AttributeType::Enum(enum_name), Value::String(value)) => (sql_string(value), format!("{}::{}", enum_name, EnumType::variant_name(value))),
+            (AttributeType::Uuid, Value::String(value)) if value == "gen_random_uuid()" => ("gen_random_uuid()".to_string(), "Uuid::new_v4()".to_string()),
+            // Timestamps without time zone are stored in UTC, like chrono::Utc::now()
+            (AttributeType::NaiveDateTime, _) if now => ("(now() AT TIME ZONE 'utc')".to_string(), "chrono::Utc::now().naive_utc()".to_string()),
+            (AttributeType::DateTimeUtc, _) if now => ("now()".to_string(), "chrono::Utc::now()".to_string()),
+            (AttributeType::NaiveDate, _) if now => ("(now() AT TIME ZONE 'utc')::DATE".to_string(), "chrono::Utc::now().date_naive()".to_string()),
+            (AttributeType::NaiveTime, _) if now => ("(now() AT TIME ZONE 'utc')::TIME".to_string(), "chrono::Utc::now().time()".to_string()),
+            (AttributeType::Json(None), _) => (format!("{}::JSONB", sql_string(&value.to_string())), format!("serde_json::json!({})", value)),
+            (AttributeType::Vec(_), Value::Array(values)) if values.is_empty() => ("'{}'".to_string(), "Vec::new()".to_string()),
+            _ => return Err(invalid()),
+        };
+        Ok(default)
+    }
+}
+
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
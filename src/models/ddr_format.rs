@@ -0,0 +1,113 @@
+use std::{fmt::{Display, Formatter}, path::Path, str::FromStr};
+
+use super::ddr_req::RawDomainDrivenRequest;
+
+/**
+ * The serialization formats in which a domain driven request can be written.
+ * JSON is the original format. YAML and TOML are parsed into the same `RawDomainDrivenRequest`,
+ * and allow comments in the request file.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DdrFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl FromStr for DdrFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_ascii_lowercase().as_str() {
+            "json" => Ok(DdrFormat::Json),
+            "yaml" | "yml" => Ok(DdrFormat::Yaml),
+            "toml" => Ok(DdrFormat::Toml),
+            _ => Err(format!("Unsupported input format {format}. Expected one of: json, yaml, toml")),
+        }
+    }
+}
+
+impl DdrFormat {
+    /**
+     * Detect the format from the extension of the input file.
+     * Files without a known extension are assumed to be JSON.
+     */
+    pub fn from_path(path: &str) -> DdrFormat {
+        Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| extension.parse().ok())
+            .unwrap_or(DdrFormat::Json)
+    }
+
+    /**
+     * The errors of the underlying parsers already contain the line and column at which parsing failed.
+     */
+    pub fn parse(&self, content: &str) -> Result<RawDomainDrivenRequest, String> {
+        match self {
+            DdrFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            DdrFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            DdrFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| format!("Could not parse the input file as {self}: {e}"))
+    }
+}
+
+impl Display for DdrFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            DdrFormat::Json => "JSON",
+            DdrFormat::Yaml => "YAML",
+            DdrFormat::Toml => "TOML",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DdrFormat;
+
+    #[test]
+    fn detects_the_format_from_the_extension() {
+        assert_eq!(DdrFormat::from_path("ddr.json"), DdrFormat::Json);
+        assert_eq!(DdrFormat::from_path("ddr.yaml"), DdrFormat::Yaml);
+        assert_eq!(DdrFormat::from_path("ddr.YML"), DdrFormat::Yaml);
+        assert_eq!(DdrFormat::from_path("ddr.toml"), DdrFormat::Toml);
+        assert_eq!(DdrFormat::from_path("ddr"), DdrFormat::Json);
+        assert!("xml".parse::<DdrFormat>().is_err());
+    }
+
+    #[test]
+    fn parses_the_same_request_from_every_format() {
+        let json = r#"{"service_name": "shop", "entities": [{"Item": {"id": "Uuid", "primary_key": "id"}}]}"#;
+        let yaml = "
+# comments are allowed
+service_name: shop
+entities:
+  - Item:
+      id: Uuid
+      primary_key: id
+";
+        let toml = r#"
+# comments are allowed
+service_name = "shop"
+
+[[entities]]
+Item = { id = "Uuid", primary_key = "id" }
+"#;
+        let from_json = DdrFormat::Json.parse(json).unwrap();
+        for (format, content) in [(DdrFormat::Yaml, yaml), (DdrFormat::Toml, toml)] {
+            let parsed = format.parse(content).unwrap();
+            assert_eq!(parsed.service_name, from_json.service_name);
+            assert_eq!(parsed.entities, from_json.entities);
+        }
+    }
+
+    #[test]
+    fn reports_the_format_and_position_of_a_parse_error() {
+        let error = DdrFormat::Yaml.parse("service_name: [shop").err().unwrap();
+        assert!(error.starts_with("Could not parse the input file as YAML"));
+        assert!(error.contains("line 1"));
+    }
+}
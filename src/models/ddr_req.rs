@@ -1,11 +1,10 @@
-use chrono::format;
 use serde::{Serialize, Deserialize};
 use serde_json::{from_value, Value};
 
 
-use crate::{templates::{docker::docker_compose::DockerComposeGenerator, postgres::{crud_query_templates::CrudQueryGenerator, database_template::DatabaseGenerator, table_templates::PostgresTableGenerator}, rust::{axum_routes_templates::AxumRoutesGenerator, controller_templates::ControllerGenerator, error_templates::ErrorGenerator, import_templates::ImportGenerator, mod_template::ModGenerator, model_templates::ModelGenerator, project_config_templates::ProjectConfigGenerator, service_templates::ServiceGenerator, source_templates::SourceGenerator}}, utils::naming_convention::to_snake_case};
+use crate::{utils::naming_convention::{to_plural, to_snake_case}, templates::{docker::docker_compose::DockerComposeGenerator, postgres::{crud_query_templates::CrudQueryGenerator, database_template::DatabaseGenerator, table_templates::PostgresTableGenerator}, rust::{axum_routes_templates::AxumRoutesGenerator, controller_templates::ControllerGenerator, error_templates::ErrorGenerator, import_templates::ImportGenerator, mod_template::ModGenerator, model_templates::ModelGenerator, project_config_templates::ProjectConfigGenerator, service_templates::ServiceGenerator, source_templates::SourceGenerator}}};
 
-use super::entity::{Entity, EntityName, EntityPluralName};
+use super::{entity::{AttributeType, Entity, EntityName, EntityPath, EntityPluralName, JsonTypeName, PrimaryKey}, enum_type::{EnumName, EnumType}, relation::Relation, validation::{pointer, ValidationError, ValidationErrorKind, ValidationErrors}};
 
 #[derive(Serialize, Deserialize)]
 pub struct Semantics {
@@ -15,45 +14,226 @@ pub struct Semantics {
 pub struct RawDomainDrivenRequest {
     pub service_name: String,
     pub entities: Value,
+    #[serde(default)]
     pub semantics: Value,
+    #[serde(default)]
+    pub enums: Value,
+    #[serde(default)]
+    pub relations: Value,
 }
 
 impl RawDomainDrivenRequest {
-    pub fn generate_entities(&self) -> Vec<Entity> {
+    pub fn generate_entities(&self, enums: &[EnumType], errors: &mut ValidationErrors) -> Vec<Entity> {
         let mut entities = Vec::new();
+        let enum_names = self.get_enum_names_and_values().into_iter().map(|(_, enum_name, _)| enum_name).collect::<Vec<EnumName>>();
         // extract entities in key value pairs
-        for(entity_name, entity_plural_name, entity_description) in self.get_entity_names_and_values()  {
-            let entity = Entity::from((entity_name.to_string(), entity_plural_name.to_string(), entity_description.clone(), self.entities.clone()));
-            entities.push(entity);
+        for (entity_path, entity_name, entity_plural_name, entity_description) in self.get_entity_names_and_values(errors) {
+            match Entity::try_from((entity_path.clone(), entity_name, entity_plural_name, entity_description, self.entities.clone(), enum_names.clone())) {
+                Ok(entity) => {
+                    verify_enum_defaults(&entity, &entity_path, enums, errors);
+                    entities.push(entity)
+                },
+                Err(entity_errors) => errors.extend(entity_errors),
+            }
+        }
+        for entity in &entities {
+            verify_foreign_key_targets(entity, &entities, errors);
+        }
+        let primary_keys = entities.iter().map(|entity| (entity.name.clone(), entity.primary_key.clone())).collect::<Vec<(EntityName, PrimaryKey)>>();
+        for entity in &mut entities {
+            for (_, foreign_key) in &mut entity.foreign_keys {
+                foreign_key.references_primary_key = primary_keys.iter().any(|(entity_name, primary_key)| {
+                    entity_name == &foreign_key.entity_name && primary_key == &vec![foreign_key.attribute_name.clone()]
+                });
+            }
         }
         entities
     }
 
-    
-    pub fn get_entity_names_and_values(&self) -> Vec<(EntityName, EntityPluralName, Value)> {
-        self
-            .entities
+    pub fn generate_enums(&self, errors: &mut ValidationErrors) -> Vec<EnumType> {
+        if !self.enums.is_null() && !self.enums.is_array() {
+            errors.push(ValidationError::new("/enums".to_string(), ValidationErrorKind::EnumsNotAnArray));
+        }
+        let mut enums = Vec::new();
+        for (enum_path, enum_name, raw_enum) in self.get_enum_names_and_values() {
+            match EnumType::try_from((enum_path, enum_name, raw_enum)) {
+                Ok(enum_type) => enums.push(enum_type),
+                Err(enum_errors) => errors.extend(enum_errors),
+            }
+        }
+        enums
+    }
+
+    /**
+     * Relations can only be generated once the entities are, since the join tables reference their primary keys.
+     */
+    pub fn generate_relations(&self, entities: &[Entity], errors: &mut ValidationErrors) -> Vec<Relation> {
+        if !self.relations.is_null() && !self.relations.is_array() {
+            errors.push(ValidationError::new("/relations".to_string(), ValidationErrorKind::RelationsNotAnArray));
+        }
+        let mut relations: Vec<Relation> = Vec::new();
+        let raw_relations = self.relations
+            .as_array()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(relation_index, raw_relation)| raw_relation.as_object().map(|raw_relation| (relation_index, raw_relation)));
+        for (relation_index, raw_relation) in raw_relations {
+            for (relation_name, raw_entity_names) in raw_relation {
+                let relation_path = pointer(&pointer("/relations", relation_index), relation_name);
+                let relation = match Relation::try_from((relation_path.clone(), relation_name.to_string(), raw_entity_names.clone(), entities)) {
+                    Ok(relation) => relation,
+                    Err(relation_errors) => {
+                        errors.extend(relation_errors);
+                        continue;
+                    }
+                };
+                let [end, other_end] = &relation.ends;
+                let sc_plural_relation = to_snake_case(&relation.plural_name);
+                if relations.iter().any(|known| known.ends.iter().all(|known_end| known_end.entity_name == end.entity_name || known_end.entity_name == other_end.entity_name)) {
+                    errors.push(ValidationError::for_entity(relation_name, relation_path, ValidationErrorKind::DuplicateRelation(end.entity_name.clone(), other_end.entity_name.clone())));
+                } else if entities.iter().map(|entity| &entity.plural_name).chain(relations.iter().map(|known| &known.plural_name)).any(|plural_name| to_snake_case(plural_name) == sc_plural_relation) {
+                    errors.push(ValidationError::for_entity(relation_name, relation_path, ValidationErrorKind::RelationNameTaken(sc_plural_relation)));
+                } else if let Some(route) = self.nested_route_of_relation(&relation, entities) {
+                    errors.push(ValidationError::for_entity(relation_name, relation_path, ValidationErrorKind::RelationRouteTaken(route)));
+                } else {
+                    relations.push(relation);
+                }
+            }
+        }
+        relations
+    }
+
+    /**
+     * A foreign key that is the only one from an entity to another already lists it under the other entity,
+     * `/v1/users/:id/groups`, which is also the route of a relation between them.
+     */
+    fn nested_route_of_relation(&self, relation: &Relation, entities: &[Entity]) -> Option<String> {
+        relation.directions().into_iter().find_map(|(end, related_end)| {
+            let related_entity = entities.iter().find(|entity| entity.name == related_end.entity_name)?;
+            let nested_foreign_keys = related_entity.nested_foreign_keys().into_iter().filter(|(_, foreign_key)| foreign_key.entity_name == end.entity_name).count();
+            (nested_foreign_keys == 1).then(|| format!("/v1/{}/:id/{}", to_snake_case(&end.plural_name), to_snake_case(&related_end.plural_name)))
+        })
+    }
+
+    /**
+     * The `enums` section is optional, and has the same structure as the entities:
+     * an array in which every element maps the name of an enum to its values.
+     */
+    fn get_enum_names_and_values(&self) -> Vec<(String, EnumName, Value)> {
+        self.enums
             .as_array()
-            .expect("Entities must be an array")
-            .iter()
-            .flat_map(|x| x.as_object().expect("An entity must be correctly formatted")).map(|(entity_name, entity_value)| (entity_name.to_string(), entity_value.clone())).collect::<Vec<(String, Value)>>()
-            .iter()
-            .map(|(entity_name, entity_value)| {
-                let entity_plural_name = from_value::<Semantics>(
-                    self.semantics
-                    .as_array()
-                    .unwrap()
-                    .iter()
-                    .find(|entity_semantics|   
-                        entity_semantics.as_object().unwrap().contains_key(entity_name))
-                    .expect(&format!("Semantics of the entity {entity_name} are not correctly provided"))[entity_name].to_owned())
-                    .expect(&format!("Semantics of the entity {entity_name} are not correctly provided"))
-                    .plural;
-                let snake_case_single = to_snake_case(entity_name);
-                let snake_case_plural = to_snake_case(&entity_plural_name);
-                (entity_name.to_string(), entity_plural_name.to_string(), entity_value.clone())
-            }).collect::<Vec<(String, String, Value)>>()
-            
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(enum_index, raw_enum)| raw_enum.as_object().map(|raw_enum| (enum_index, raw_enum)))
+            .flat_map(|(enum_index, raw_enum)| raw_enum.iter().map(move |(enum_name, enum_values)| {
+                (pointer(&pointer("/enums", enum_index), enum_name), enum_name.to_string(), enum_values.clone())
+            }))
+            .collect()
+    }
+
+    /**
+     * Problems with the structure of the entities or their semantics are pushed to `errors`.
+     * An entity with malformed semantics is still returned, so that the rest of its definition gets validated as well.
+     */
+    pub fn get_entity_names_and_values(&self, errors: &mut ValidationErrors) -> Vec<(EntityPath, EntityName, EntityPluralName, Value)> {
+        let raw_entities = match self.entities.as_array() {
+            Some(raw_entities) => raw_entities,
+            None => {
+                errors.push(ValidationError::new("/entities".to_string(), ValidationErrorKind::EntitiesNotAnArray));
+                return vec![];
+            }
+        };
+        let mut entity_names_and_values = Vec::new();
+        for (entity_index, raw_entity) in raw_entities.iter().enumerate() {
+            let entity_index_path = pointer("/entities", entity_index);
+            let raw_entity = match raw_entity.as_object() {
+                Some(raw_entity) => raw_entity,
+                None => {
+                    errors.push(ValidationError::new(entity_index_path, ValidationErrorKind::MalformedEntity));
+                    continue;
+                }
+            };
+            for (entity_name, entity_value) in raw_entity {
+                let entity_path = pointer(&entity_index_path, entity_name);
+                let entity_plural_name = self.get_entity_plural_name(entity_name, &entity_path, entity_value, errors);
+                entity_names_and_values.push((entity_path, entity_name.to_string(), entity_plural_name, entity_value.clone()));
+            }
+        }
+        entity_names_and_values
+    }
+
+    /**
+     * The plural name of an entity is taken from its `semantics` entry, or else from the `plural` keyword of its definition.
+     * When neither is provided, the plural is inferred from the entity name.
+     * A plural spelled like the name, such as `Sheep`, would give the entity and its lists the same functions, so it is rejected.
+     */
+    fn get_entity_plural_name(&self, entity_name: &str, entity_path: &str, entity_value: &Value, errors: &mut ValidationErrors) -> EntityPluralName {
+        let entity_semantics = self.semantics
+            .as_array()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .find_map(|(semantics_index, entity_semantics)| entity_semantics.get(entity_name).map(|entity_semantics| (semantics_index, entity_semantics)));
+        let (plural, plural_path) = match entity_semantics {
+            Some((semantics_index, entity_semantics)) => {
+                let semantics_path = pointer(&pointer("/semantics", semantics_index), entity_name);
+                match from_value::<Semantics>(entity_semantics.to_owned()) {
+                    Ok(semantics) => (semantics.plural, pointer(&semantics_path, "plural")),
+                    Err(_) => {
+                        errors.push(ValidationError::for_entity(entity_name, semantics_path, ValidationErrorKind::MalformedSemantics));
+                        return entity_name.to_string();
+                    }
+                }
+            },
+            None => match entity_value.get("plural") {
+                Some(Value::String(plural)) if !plural.is_empty() => (plural.to_string(), pointer(entity_path, "plural")),
+                Some(_) => {
+                    errors.push(ValidationError::for_entity(entity_name, pointer(entity_path, "plural"), ValidationErrorKind::MalformedReservedKeyword("plural")));
+                    return entity_name.to_string();
+                },
+                None => (to_plural(entity_name), entity_path.to_string()),
+            }
+        };
+        if to_snake_case(&plural) == to_snake_case(entity_name) {
+            errors.push(ValidationError::for_entity(entity_name, plural_path, ValidationErrorKind::InvariantPlural(entity_name.to_string())));
+        }
+        plural
+    }
+}
+
+/**
+ * The defaults of enum attributes must be one of the values of the enum.
+ * They can only be verified once the enums are known, unlike the other defaults.
+ */
+fn verify_enum_defaults(entity: &Entity, entity_path: &str, enums: &[EnumType], errors: &mut ValidationErrors) {
+    for (attribute_name, default) in &entity.defaults {
+        let (Some(AttributeType::Enum(enum_name)), Some(value)) = (entity.attribute_type(attribute_name).map(AttributeType::filter_type), default.value.as_str()) else {
+            continue;
+        };
+        if let Some(enum_type) = enums.iter().find(|enum_type| &enum_type.name == enum_name) {
+            if !enum_type.values.iter().any(|enum_value| enum_value == value) {
+                let default_path = pointer(&pointer(entity_path, "defaults"), attribute_name);
+                errors.push(ValidationError::for_attribute(&entity.name, attribute_name, default_path, ValidationErrorKind::InvalidDefault(default.value.to_string(), enum_name.to_string())));
+            }
+        }
+    }
+}
+
+/**
+ * Foreign keys can only reference a single primary key or a unique attribute, otherwise Postgres cannot create the constraint.
+ * References to entities that could not be generated are skipped, their problems are already reported.
+ */
+fn verify_foreign_key_targets(entity: &Entity, entities: &[Entity], errors: &mut ValidationErrors) {
+    for (attribute_name, foreign_key) in &entity.foreign_keys {
+        let Some(referenced_entity) = entities.iter().find(|referenced_entity| referenced_entity.name == foreign_key.entity_name) else {
+            continue;
+        };
+        let referenced_attribute = vec![foreign_key.attribute_name.clone()];
+        if referenced_entity.primary_key != referenced_attribute && !referenced_entity.unique_attributes.contains(&referenced_attribute) {
+            errors.push(ValidationError::for_attribute(&entity.name, attribute_name, pointer(&entity.path, attribute_name), ValidationErrorKind::ForeignKeyTargetNotUnique(foreign_key.entity_name.clone(), foreign_key.attribute_name.clone())));
+        }
     }
 }
 
@@ -61,18 +241,28 @@ impl RawDomainDrivenRequest {
 pub struct DomainDrivenRequest {
     pub service_name: String,
     pub entities: Vec<Entity>,
+    pub enums: Vec<EnumType>,
+    pub relations: Vec<Relation>,
 }
 
-impl From<RawDomainDrivenRequest> for DomainDrivenRequest {
-    fn from(raw_ddr: RawDomainDrivenRequest) -> Self {
-        let entities: Vec<Entity> = raw_ddr.generate_entities();
-        for entity in &entities {
-            entity.verify_entity_constraints(&entities.iter().collect()).unwrap();
+impl TryFrom<RawDomainDrivenRequest> for DomainDrivenRequest {
+    type Error = ValidationErrors;
+
+    fn try_from(raw_ddr: RawDomainDrivenRequest) -> Result<Self, Self::Error> {
+        let mut errors = Vec::new();
+        let enums = raw_ddr.generate_enums(&mut errors);
+        // The constraints of every entity are verified while the entities are generated
+        let entities: Vec<Entity> = raw_ddr.generate_entities(&enums, &mut errors);
+        let relations = raw_ddr.generate_relations(&entities, &mut errors);
+        if !errors.is_empty() {
+            return Err(errors);
         }
-        DomainDrivenRequest {
+        Ok(DomainDrivenRequest {
             service_name: raw_ddr.service_name,
             entities,
-        }
+            enums,
+            relations,
+        })
     }
 }
 
@@ -92,7 +282,7 @@ impl DomainDrivenRequest {
     }
 
     pub fn generate_routes_file(&self) -> String {
-        AxumRoutesGenerator::generate_routes_file(self, self.entities.iter().collect())
+        AxumRoutesGenerator::generate_routes_file(self, self.entities.iter().collect(), &self.relations)
     }
 
     pub fn generate_controllers(&self) -> Vec<(&Entity, String)> {
@@ -105,6 +295,10 @@ impl DomainDrivenRequest {
         controller
     }
 
+    pub fn generate_relation_controllers(&self) -> Vec<(&Relation, String)> {
+        self.relations.iter().map(|relation| (relation, ControllerGenerator::generate_relation_controller(self, relation, &self.entities))).collect()
+    }
+
     pub fn generate_cargo_toml(&self) -> String {
         ProjectConfigGenerator::generate_cargo_toml(self, &self.service_name)
     }
@@ -119,6 +313,48 @@ impl DomainDrivenRequest {
         DatabaseGenerator::generate_database_create(self, self.service_name.as_str()).as_str()
     }
 
+    pub fn generate_postgres_enums(&self) -> String {
+        self.enums.iter().map(|enum_type| self.generate_enum_type_query(enum_type)).collect::<Vec<String>>().join("\n")
+    }
+
+    pub fn generate_enums_file(&self) -> String {
+        self.generate_database_enums(&self.enums)
+    }
+
+    /**
+     * The named JSON types of all entities, each listed once.
+     */
+    pub fn get_json_type_names(&self) -> Vec<JsonTypeName> {
+        let mut json_type_names: Vec<JsonTypeName> = Vec::new();
+        for (_, attribute_type) in self.entities.iter().flat_map(|entity| entity.attributes.iter()) {
+            if let Some(json_type_name) = attribute_type.json_type_name() {
+                if !json_type_names.contains(json_type_name) {
+                    json_type_names.push(json_type_name.clone());
+                }
+            }
+        }
+        json_type_names
+    }
+
+    /**
+     * Intervals are mapped to a static `Interval` type, which is only copied into the service when it is used.
+     */
+    pub fn uses_intervals(&self) -> bool {
+        self.entities.iter().flat_map(|entity| entity.attributes.iter()).any(|(_, attribute_type)| attribute_type.is_interval())
+    }
+
+    pub fn generate_json_types_file(&self) -> String {
+        self.generate_json_types(&self.get_json_type_names())
+    }
+
+    pub fn uses_foreign_keys(&self) -> bool {
+        self.entities.iter().any(|entity| !entity.foreign_keys.is_empty())
+    }
+
+    pub fn generate_postgres_foreign_keys(&self) -> String {
+        self.entities.iter().map(|entity| self.generate_foreign_key_queries(entity, &self.entities)).collect::<Vec<String>>().join("")
+    }
+
     pub fn generate_postgres_tables(&self) -> Vec<(&Entity, String)> {
         let mut tables = Vec::new();
         // extract entities in key value pairs
@@ -129,9 +365,16 @@ impl DomainDrivenRequest {
         tables
     }
 
+    /**
+     * The join tables reference the tables of both entities, which are created before them.
+     */
+    pub fn generate_postgres_relation_tables(&self) -> Vec<(&Relation, String)> {
+        self.relations.iter().map(|relation| (relation, self.generate_relation_table_query(relation))).collect()
+    }
+
 
     pub fn generate_http(&self) -> String {
-        self.generate_create_services_fn(self.entities.iter().collect())
+        self.generate_create_services_fn(self.entities.iter().collect(), &self.relations)
 
     }
 
@@ -139,28 +382,37 @@ impl DomainDrivenRequest {
         let mut service = Vec::new();
         // extract entities in key value pairs
         for entity in &self.entities  {
-            let service_fn = ServiceGenerator::generate_service(self, &entity);
+            let service_fn = ServiceGenerator::generate_service(self, entity, &self.entities);
             service.push((entity, service_fn));
         }
         service
     }
+
+    pub fn generate_relation_services(&self) -> Vec<(&Relation, String)> {
+        self.relations.iter().map(|relation| (relation, ServiceGenerator::generate_relation_service(self, relation, &self.entities))).collect()
+    }
     
     pub fn generate_sources(&self) -> Vec<(&Entity, String)> {
         let mut sources = Vec::new();
         // extract entities in key value pairs
         for entity in &self.entities {
-            let source_file = SourceGenerator::generate_source(self, &entity);
+            let source_file = SourceGenerator::generate_source(self, entity, &self.entities);
             sources.push((entity, source_file));
         }
         sources
     }
+
+    pub fn generate_relation_sources(&self) -> Vec<(&Relation, String)> {
+        self.relations.iter().map(|relation| (relation, SourceGenerator::generate_relation_source(self, relation, &self.entities))).collect()
+    }
     
     pub fn generate_docker_compose(&self) -> String {
         DockerComposeGenerator::generate_docker_compose(self, &self.service_name)
     }
 
     pub fn generate_error(&self) -> String {
-        ErrorGenerator::generate_error(self, self.get_entity_names())
+        let relation_names = self.relations.iter().map(|relation| relation.name.clone());
+        ErrorGenerator::generate_error(self, self.get_entity_names().into_iter().chain(relation_names).collect())
     }
 
     pub fn generate_model_mods(&self) -> String {
@@ -170,6 +422,15 @@ impl DomainDrivenRequest {
             let model_mod = ModGenerator::generate_model_mod(self, &entity);
             model_mods.push_str(model_mod.as_str());
         }
+        if !self.enums.is_empty() {
+            model_mods.push_str(ModGenerator::generate_enums_mod(self).as_str());
+        }
+        if !self.get_json_type_names().is_empty() {
+            model_mods.push_str(ModGenerator::generate_json_types_mod(self).as_str());
+        }
+        if self.uses_intervals() {
+            model_mods.push_str(ModGenerator::generate_interval_mod(self).as_str());
+        }
         model_mods
     }
 
@@ -180,7 +441,10 @@ impl DomainDrivenRequest {
             let service_mod = ModGenerator::generate_service_mod(self, &entity);
             service_mods.push_str(service_mod.as_str());
         }
-        let services_state = ServiceGenerator::generate_services_state(self, self.entities.iter().collect());
+        for relation in self.relations.iter() {
+            service_mods.push_str(ModGenerator::generate_relation_service_mod(self, relation).as_str());
+        }
+        let services_state = ServiceGenerator::generate_services_state(self, self.entities.iter().collect(), &self.relations);
         service_mods + services_state.as_str()
     }
 
@@ -191,6 +455,9 @@ impl DomainDrivenRequest {
             let source_mod = ModGenerator::generate_source_mod(self, &entity);
             source_mods.push_str(source_mod.as_str());
         }
+        for relation in self.relations.iter() {
+            source_mods.push_str(ModGenerator::generate_relation_source_mod(self, relation).as_str());
+        }
         source_mods
     }
 
@@ -201,6 +468,9 @@ impl DomainDrivenRequest {
             let controller_mod = ModGenerator::generate_controller_mod(self, &entity);
             controller_mods.push_str(controller_mod.as_str());
         }
+        for relation in self.relations.iter() {
+            controller_mods.push_str(ModGenerator::generate_relation_controller_mod(self, relation).as_str());
+        }
         controller_mods
     
     }
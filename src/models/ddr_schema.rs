@@ -0,0 +1,294 @@
+use serde_json::{json, Value};
+
+use super::entity::{JSON_TYPE_NAME_PATTERN, PARAMETERIZED_TYPES, PRIMITIVE_TYPES, RESERVED_KEYWORDS};
+
+pub static JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+pub static IDENTIFIER_PATTERN: &str = "[A-Za-z_][A-Za-z0-9_]*";
+
+/**
+ * A parameter of a parameterized type, which `AttributeType::from_str` trims and parses as a `u32`.
+ */
+pub static PARAMETER_PATTERN: &str = "\\s*\\+?\\d+\\s*";
+
+/**
+ * The regular expression of the primitive types understood by `AttributeType::from_str`,
+ * built from the same tables. It is combined with `Option<...>` and `Vec<...>` in the schema.
+ */
+fn primitive_types_pattern() -> String {
+    let type_names = PRIMITIVE_TYPES.iter().map(|(type_name, _)| escape(type_name));
+    let parameterized_types = PARAMETERIZED_TYPES.iter().map(|(type_name, parameter_count)| {
+        format!("{}\\({}\\)", escape(type_name), vec![PARAMETER_PATTERN; *parameter_count].join(","))
+    });
+    type_names.chain(parameterized_types).collect::<Vec<String>>().join("|")
+}
+
+/**
+ * Escapes the characters of a type name that have a meaning in regular expressions.
+ */
+fn escape(type_name: &str) -> String {
+    type_name.chars().map(|c| match c {
+        '.' | '(' | ')' | '[' | ']' | '{' | '}' | '*' | '+' | '?' | '|' | '^' | '$' | '\\' => format!("\\{c}"),
+        c => c.to_string(),
+    }).collect()
+}
+
+/**
+ * Generates the JSON Schema (draft 2020-12) of `RawDomainDrivenRequest`.
+ * The schema only describes the shape of a request: cross references between entities,
+ * such as foreign keys or attributes used in `filter_by`, are verified when the request is validated.
+ */
+pub fn generate_ddr_schema() -> Value {
+    let primitive_types = primitive_types_pattern();
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": "Lightspeed domain driven request",
+        "description": "The domain entities from which a service is generated.",
+        "type": "object",
+        "required": ["service_name", "entities"],
+        "properties": {
+            "service_name": {
+                "description": "The name of the generated service.",
+                "type": "string",
+                "minLength": 1
+            },
+            "entities": {
+                "description": "The entities of the domain. Every element maps the name of an entity to its definition.",
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "minProperties": 1,
+                    "propertyNames": { "pattern": format!("^{IDENTIFIER_PATTERN}$") },
+                    "additionalProperties": { "$ref": "#/$defs/entity" }
+                }
+            },
+            "enums": {
+                "description": "Enums that can be used as attribute types. Every element maps the name of an enum to its values.",
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "propertyNames": { "pattern": format!("^{IDENTIFIER_PATTERN}$") },
+                    "additionalProperties": {
+                        "type": "array",
+                        "minItems": 1,
+                        "uniqueItems": true,
+                        "items": { "type": "string", "pattern": "^[A-Za-z][A-Za-z0-9_-]*$" }
+                    }
+                }
+            },
+            "relations": {
+                "description": "Many-to-many relations between entities. Every element maps the name of a relation to the two entities it relates, which are linked through a join table.",
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "propertyNames": { "pattern": format!("^{IDENTIFIER_PATTERN}$") },
+                    "additionalProperties": {
+                        "type": "array",
+                        "minItems": 2,
+                        "maxItems": 2,
+                        "items": { "type": "string", "pattern": format!("^{IDENTIFIER_PATTERN}$") }
+                    }
+                }
+            },
+            "semantics": {
+                "description": "The semantics of the entities, such as their plural names. Every element maps the name of an entity to its semantics. Plurals that are not provided here or inline are inferred from the entity names.",
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "additionalProperties": { "$ref": "#/$defs/semantics" }
+                }
+            }
+        },
+        "$defs": {
+            "entity": {
+                "description": format!("The attributes of an entity, mapped to their types. {} are reserved keywords and cannot be used as attributes.", RESERVED_KEYWORDS.join(", ")),
+                "type": "object",
+                "properties": {
+                    "plural": {
+                        "description": "The plural name of the entity. Overridden by `semantics`, and inferred from the entity name when missing.",
+                        "type": "string",
+                        "minLength": 1
+                    },
+                    "primary_key": {
+                        "description": "The attribute used as primary key, or an array of attributes for a composite primary key. Defaults to `id`.",
+                        "oneOf": [
+                            { "$ref": "#/$defs/attribute_name" },
+                            {
+                                "type": "array",
+                                "minItems": 1,
+                                "uniqueItems": true,
+                                "items": { "$ref": "#/$defs/attribute_name" }
+                            }
+                        ]
+                    },
+                    "filter_by": {
+                        "description": "The attributes, or combinations of attributes, by which the entity can be filtered. Objects map string attributes to a case-insensitive match mode instead: `{\"name\": \"prefix\"}` is filtered with `?name_prefix=`.",
+                        "type": "array",
+                        "items": {
+                            "oneOf": [
+                                { "$ref": "#/$defs/attribute_name" },
+                                {
+                                    "type": "array",
+                                    "minItems": 1,
+                                    "items": { "$ref": "#/$defs/attribute_name" }
+                                },
+                                {
+                                    "type": "object",
+                                    "minProperties": 1,
+                                    "additionalProperties": { "enum": ["prefix", "contains", "icase"] }
+                                }
+                            ]
+                        }
+                    },
+                    "range_filter_by": {
+                        "description": "The numeric, date and time attributes that can be filtered on a range of values, with `min_` and `max_` query parameters.",
+                        "type": "array",
+                        "uniqueItems": true,
+                        "items": { "$ref": "#/$defs/attribute_name" }
+                    },
+                    "sortable": {
+                        "description": "The attributes by which the list routes can be sorted, one at a time, with `?sort=-created_at`. Arrays and JSON attributes cannot be sortable.",
+                        "type": "array",
+                        "uniqueItems": true,
+                        "items": { "$ref": "#/$defs/attribute_name" }
+                    },
+                    "cursor_by": {
+                        "description": "Lists the entity with cursors instead of page numbers, ordered by this required attribute and then by the primary key. Such an entity cannot be sortable or searchable.",
+                        "$ref": "#/$defs/attribute_name"
+                    },
+                    "searchable": {
+                        "description": "The string attributes that can be searched with `?q=`, the first ones weighing more in the ranking of the results.",
+                        "type": "array",
+                        "uniqueItems": true,
+                        "items": { "$ref": "#/$defs/attribute_name" }
+                    },
+                    "unique_attributes": {
+                        "description": "The attributes, or combinations of attributes, that are unique. They must also be present in `filter_by`.",
+                        "$ref": "#/$defs/attribute_groups"
+                    },
+                    "on_delete": {
+                        "description": "What happens to the entity when the entity referenced by a foreign key attribute is deleted.",
+                        "$ref": "#/$defs/referential_actions"
+                    },
+                    "on_update": {
+                        "description": "What happens to the entity when the key referenced by a foreign key attribute is updated.",
+                        "$ref": "#/$defs/referential_actions"
+                    },
+                    "defaults": {
+                        "description": "The default values of attributes, used when they are missing from the create payload. `now()` is the current time for date and time attributes, and `gen_random_uuid()` a random Uuid.",
+                        "type": "object",
+                        "propertyNames": { "pattern": format!("^{IDENTIFIER_PATTERN}$") }
+                    }
+                },
+                "propertyNames": { "pattern": format!("^{IDENTIFIER_PATTERN}$") },
+                "additionalProperties": { "$ref": "#/$defs/attribute_type" }
+            },
+            "referential_actions": {
+                "type": "object",
+                "propertyNames": { "pattern": format!("^{IDENTIFIER_PATTERN}$") },
+                "additionalProperties": { "enum": ["cascade", "restrict", "set null"] }
+            },
+            "attribute_name": {
+                "type": "string",
+                "pattern": format!("^{IDENTIFIER_PATTERN}$")
+            },
+            "attribute_groups": {
+                "type": "array",
+                "items": {
+                    "oneOf": [
+                        { "$ref": "#/$defs/attribute_name" },
+                        {
+                            "type": "array",
+                            "minItems": 1,
+                            "items": { "$ref": "#/$defs/attribute_name" }
+                        }
+                    ]
+                }
+            },
+            "attribute_type": {
+                "type": "string",
+                "anyOf": [
+                    {
+                        "description": "A primitive type, optionally wrapped in Option<...> to make it nullable.",
+                        "pattern": format!("^(Option<({primitive_types})>|{primitive_types})$")
+                    },
+                    {
+                        "description": "An array of primitives or enums, stored as a Postgres array, optionally wrapped in Option<...> to make it nullable.",
+                        "pattern": format!("^(Option<Vec<({primitive_types}|{IDENTIFIER_PATTERN})>>|Vec<({primitive_types}|{IDENTIFIER_PATTERN})>)$")
+                    },
+                    {
+                        "description": "A JSONB document: `Json` for free-form JSON, or `Json<Name>` for a named type, optionally wrapped in Option<...> to make it nullable.",
+                        "pattern": format!("^(Option<Json(<{JSON_TYPE_NAME_PATTERN}>)?>|Json(<{JSON_TYPE_NAME_PATTERN}>)?)$")
+                    },
+                    {
+                        "description": "An enum declared in `enums`, optionally wrapped in Option<...> to make it nullable.",
+                        "pattern": format!("^(Option<{IDENTIFIER_PATTERN}>|{IDENTIFIER_PATTERN})$")
+                    },
+                    {
+                        "description": "A foreign key, referencing the primary key or a unique attribute of another entity: `Entity.attribute`, optionally wrapped in Option<...> to make it nullable.",
+                        "pattern": format!("^(Option<{IDENTIFIER_PATTERN}\\.{IDENTIFIER_PATTERN}>|{IDENTIFIER_PATTERN}\\.{IDENTIFIER_PATTERN})$")
+                    }
+                ]
+            },
+            "semantics": {
+                "type": "object",
+                "required": ["plural"],
+                "properties": {
+                    "plural": {
+                        "description": "The plural name of the entity, used for tables, routes and services.",
+                        "type": "string",
+                        "minLength": 1
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonschema::JSONSchema;
+    use serde_json::Value;
+
+    use crate::models::entity::AttributeType;
+
+    use super::generate_ddr_schema;
+
+    fn ddr_schema() -> JSONSchema {
+        JSONSchema::compile(&generate_ddr_schema()).unwrap()
+    }
+
+    fn attribute_type_ddr(attribute_type: &str) -> Value {
+        serde_json::json!({ "service_name": "shop", "entities": [{ "Item": { "id": "Uuid", "value": attribute_type } }] })
+    }
+
+    #[test]
+    fn validates_the_example_requests() {
+        let ddr_schema = ddr_schema();
+        let example: Value = serde_json::from_str(include_str!("../../ddr_example.json")).unwrap();
+        let shop: Value = serde_yaml::from_str(include_str!("../../tests/fixtures/shop.yaml")).unwrap();
+        assert!(ddr_schema.is_valid(&example));
+        assert!(ddr_schema.is_valid(&shop));
+    }
+
+    #[test]
+    fn rejects_a_malformed_request() {
+        let ddr_schema = ddr_schema();
+        let malformed: Value = serde_json::json!({ "service_name": "shop", "entities": { "Item": { "id": "Uuid" } } });
+        assert!(!ddr_schema.is_valid(&malformed));
+        assert!(!ddr_schema.is_valid(&attribute_type_ddr("Vec<Vec<i32>>")));
+    }
+
+    #[test]
+    fn accepts_the_attribute_types_understood_by_the_parser() {
+        let ddr_schema = ddr_schema();
+        for attribute_type in ["Decimal( 10 , 2 )", "Option<String( 20 )>", "Vec<chrono::NaiveDate>", "Json<Metadata_v2>", "Option<chrono::DateTime<chrono::Utc>>"] {
+            assert!(AttributeType::from_str(attribute_type).is_known(), "{attribute_type}");
+            assert!(ddr_schema.is_valid(&attribute_type_ddr(attribute_type)), "{attribute_type}");
+        }
+        for attribute_type in ["Json<_Metadata>", "Decimal(10)", "String(-1)", "DateTime<chrono::Utc>"] {
+            assert!(!AttributeType::from_str(attribute_type).is_known(), "{attribute_type}");
+            assert!(!ddr_schema.is_valid(&attribute_type_ddr(attribute_type)), "{attribute_type}");
+        }
+    }
+}
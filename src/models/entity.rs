@@ -4,17 +4,196 @@ use serde::{Serialize, Deserialize};
 use serde_json::Value;
 use strum::EnumProperty;
 
+use crate::utils::naming_convention::{to_pascal_case, to_snake_case};
+
+use super::{attribute_default::AttributeDefault, enum_type::EnumName, validation::{pointer, ValidationError, ValidationErrorKind, ValidationErrors}};
+
 pub type RawEntities = Value;
 pub type RawEntity = Value;
+pub type EntityPath = String;
+
+/**
+ * Keys of an entity definition that configure the entity instead of declaring an attribute.
+ */
+pub static RESERVED_KEYWORDS: [&str; 11] = ["primary_key", "filter_by", "range_filter_by", "sortable", "cursor_by", "searchable", "unique_attributes", "defaults", "plural", "on_delete", "on_update"];
 
 pub type AttributeName = String;
 pub type EntityName = String;
 pub type EntityPluralName = String;
+pub type JsonTypeName = String;
+
+/**
+ * rust_decimal stores up to 28 significant digits, larger numerics cannot be decoded.
+ */
+pub static MAX_DECIMAL_PRECISION: u32 = 28;
+
+pub static MAX_VARCHAR_LENGTH: u32 = 10485760;
+
+/**
+ * The generated `tsvector` column of the searchable attributes of an entity.
+ */
+pub static SEARCH_COLUMN: &str = "search_vector";
+
+/**
+ * The text search configuration of the search column and queries, which is language agnostic.
+ */
+pub static SEARCH_CONFIGURATION: &str = "simple";
+
+/**
+ * The primitive types understood by `AttributeType::from_str`, by name. The JSON Schema of the request is built from them.
+ */
+pub static PRIMITIVE_TYPES: [(&str, AttributeType); 20] = [
+    ("String", AttributeType::String),
+    ("Text", AttributeType::Text),
+    ("Uuid", AttributeType::Uuid),
+    ("i32", AttributeType::I32),
+    ("i64", AttributeType::I64),
+    ("f32", AttributeType::F32),
+    ("f64", AttributeType::F64),
+    ("Decimal", AttributeType::Decimal(None)),
+    ("bool", AttributeType::Boolean),
+    ("NaiveDateTime", AttributeType::NaiveDateTime),
+    ("chrono::NaiveDateTime", AttributeType::NaiveDateTime),
+    ("DateTime<Utc>", AttributeType::DateTimeUtc),
+    ("chrono::DateTime<Utc>", AttributeType::DateTimeUtc),
+    ("chrono::DateTime<chrono::Utc>", AttributeType::DateTimeUtc),
+    ("NaiveDate", AttributeType::NaiveDate),
+    ("chrono::NaiveDate", AttributeType::NaiveDate),
+    ("NaiveTime", AttributeType::NaiveTime),
+    ("chrono::NaiveTime", AttributeType::NaiveTime),
+    ("Interval", AttributeType::Interval),
+    ("Duration", AttributeType::Interval),
+];
+
+/**
+ * The types that take numeric parameters, with their number of parameters: `String(255)` and `Decimal(10, 2)`.
+ * The parameters are trimmed, so `Decimal( 10 , 2 )` is a decimal as well.
+ */
+pub static PARAMETERIZED_TYPES: [(&str, usize); 2] = [("String", 1), ("Decimal", 2)];
+
+/**
+ * The name of the Rust struct of a `Json<Name>` attribute.
+ */
+pub static JSON_TYPE_NAME_PATTERN: &str = "[A-Za-z][A-Za-z0-9_]*";
+
+/**
+ * What happens to the referencing rows when the referenced row is deleted or its key is updated,
+ * declared per foreign key attribute in `on_delete` and `on_update`.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ReferentialAction {
+    Cascade,
+    Restrict,
+    SetNull,
+}
+
+impl ReferentialAction {
+    pub fn parse(action: &str) -> Option<Self> {
+        match action {
+            "cascade" => Some(ReferentialAction::Cascade),
+            "restrict" => Some(ReferentialAction::Restrict),
+            "set null" => Some(ReferentialAction::SetNull),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ReferentialAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReferentialAction::Cascade => write!(f, "CASCADE"),
+            ReferentialAction::Restrict => write!(f, "RESTRICT"),
+            ReferentialAction::SetNull => write!(f, "SET NULL"),
+        }
+    }
+}
+
+/**
+ * How a string attribute is matched on the value of a match filter. Every mode ignores the case.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MatchMode {
+    Prefix,
+    Contains,
+    ICase,
+}
+
+impl MatchMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "prefix" => Some(MatchMode::Prefix),
+            "contains" => Some(MatchMode::Contains),
+            "icase" => Some(MatchMode::ICase),
+            _ => None,
+        }
+    }
+}
+
+impl Display for MatchMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchMode::Prefix => write!(f, "prefix"),
+            MatchMode::Contains => write!(f, "contains"),
+            MatchMode::ICase => write!(f, "icase"),
+        }
+    }
+}
+
+/**
+ * A string attribute filtered on a pattern instead of equality, declared in `filter_by` as `{"name": "prefix"}`.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MatchFilter {
+    pub attribute_name: AttributeName,
+    pub mode: MatchMode,
+}
+
+impl MatchFilter {
+    /**
+     * The query parameter of the filter, such as `name_prefix`, which also names its functions.
+     */
+    pub fn param_name(&self) -> String {
+        format!("{}_{}", self.attribute_name, self.mode)
+    }
+}
+
+/**
+ * A sortable attribute and its direction, such as `?sort=-created_at` for the descending order of `created_at`.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortKey<'a> {
+    pub attribute_name: &'a AttributeName,
+    pub descending: bool,
+}
+
+impl SortKey<'_> {
+    /**
+     * The value of the `sort` query parameter.
+     */
+    pub fn param_value(&self) -> String {
+        if self.descending { format!("-{}", self.attribute_name) } else { self.attribute_name.clone() }
+    }
+
+    /**
+     * The variant of the sort of the entity, such as `CreatedAtDesc`.
+     */
+    pub fn variant_name(&self) -> String {
+        format!("{}{}", to_pascal_case(self.attribute_name), if self.descending { "Desc" } else { "Asc" })
+    }
+
+    pub fn order_by(&self) -> String {
+        format!("{} {}", self.attribute_name, if self.descending { "DESC" } else { "ASC" })
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ForeginKey {
     pub entity_name: String,
     pub attribute_name: String,
+    pub on_delete: Option<ReferentialAction>,
+    pub on_update: Option<ReferentialAction>,
+    /// Whether the single primary key of the referenced entity is referenced, known once all entities are generated.
+    pub references_primary_key: bool,
 }
 
 impl ForeginKey {
@@ -28,11 +207,50 @@ impl ForeginKey {
         Some(ForeginKey {
             entity_name,
             attribute_name,
+            on_delete: None,
+            on_update: None,
+            references_primary_key: false,
         })
     
     }
+
+    /**
+     * Looks up the type of the referenced attribute in the raw entities of the request.
+     */
+    pub fn resolve_type(&self, raw_entities: &RawEntities, enum_names: &[EnumName]) -> Result<AttributeType, ValidationErrorKind> {
+        let foreign_key_entity = raw_entities
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find_map(|entity| entity.get(&self.entity_name))
+            .ok_or(ValidationErrorKind::UnknownForeignKeyEntity(self.entity_name.clone()))?;
+        let foreign_key_attribute_type = foreign_key_entity
+            .get(&self.attribute_name)
+            .ok_or(ValidationErrorKind::UnknownForeignKeyAttribute(self.entity_name.clone(), self.attribute_name.clone()))?;
+        match foreign_key_attribute_type.as_str().map(|attribute_type| AttributeType::from_str(attribute_type).resolve_enums(enum_names)) {
+            Some(attribute_type) if attribute_type.is_known() => Ok(attribute_type),
+            _ => Err(ValidationErrorKind::InvalidForeignKeyTarget(self.entity_name.clone(), self.attribute_name.clone())),
+        }
+    }
 }
 
+/**
+ * The attributes that identify an entity. Join-style entities are identified by a combination of attributes.
+ */
+pub type PrimaryKey = Vec<AttributeName>;
+
+/**
+ * How the primary key of a new entity is obtained:
+ * - `Uuid`: a single Uuid key, generated by the service
+ * - `Identity`: a single i32 or i64 key, generated by the database (`GENERATED ALWAYS AS IDENTITY`)
+ * - `Client`: natural keys, such as a String code, and composite keys are provided in the create payload
+ */
+#[derive(Debug, PartialEq)]
+pub enum PrimaryKeyGeneration {
+    Uuid,
+    Identity,
+    Client,
+}
 pub type FilterBy = Vec<AttributeName>;
 pub type UniqueAttributes = Vec<AttributeName>;
 
@@ -40,11 +258,24 @@ pub type UniqueAttributes = Vec<AttributeName>;
 pub struct Entity {
     pub name: String,
     pub plural_name: String,
+    /// JSON pointer to the definition of the entity in the request.
+    pub path: EntityPath,
     pub attributes: Vec<(AttributeName, AttributeType)>,
-    pub primary_key: String,
-    pub foreign_keys: Vec<ForeginKey>,
+    pub primary_key: PrimaryKey,
+    pub foreign_keys: Vec<(AttributeName, ForeginKey)>,
     pub unique_attributes: Vec<UniqueAttributes>,
     pub filter_by: Vec<FilterBy>,
+    /// String attributes filtered on a pattern: `?name_prefix=`, declared in `filter_by`.
+    pub match_filters: Vec<MatchFilter>,
+    /// Attributes filtered on a range of values: `min_age` and `max_age`.
+    pub range_filter_by: Vec<AttributeName>,
+    /// Attributes by which the list routes can be sorted: `?sort=-created_at`.
+    pub sortable: Vec<AttributeName>,
+    /// The attribute that orders the pages of an entity listed with cursors instead of page numbers.
+    pub cursor_by: Option<AttributeName>,
+    /// String attributes searched with `?q=`, in order of importance.
+    pub searchable: Vec<AttributeName>,
+    pub defaults: Vec<(AttributeName, AttributeDefault)>,
 }
 
 impl Display for Entity {
@@ -58,70 +289,305 @@ impl Entity {
         attribute_name == self.attributes.last().expect(&format!("Attributes for {} are empty or malformed", self.name)).0.as_str()
     }
 
-    pub fn primary_key_type(&self) -> &AttributeType {
-        &self.attributes.iter().find(|(attribute_name, _)| attribute_name == &self.primary_key).expect(&format!("No primary key provided for {}", self.name)).1
+    pub fn is_primary_key(&self, attribute_name: &str) -> bool {
+        self.primary_key.iter().any(|primary_key| primary_key == attribute_name)
+    }
+
+    pub fn has_composite_primary_key(&self) -> bool {
+        self.primary_key.len() > 1
+    }
+
+    /**
+     * The attributes that are written by inserts and updates, in the order of their bind parameters.
+     */
+    pub fn inserted_attributes(&self) -> Vec<&AttributeName> {
+        self.attributes.iter().map(|(attribute_name, _)| attribute_name).filter(|attribute_name| !self.is_database_generated(attribute_name)).collect()
+    }
+
+    pub fn primary_key_generation(&self) -> PrimaryKeyGeneration {
+        if self.has_composite_primary_key() {
+            return PrimaryKeyGeneration::Client;
+        }
+        match self.primary_key_attributes().first().map(|(_, attribute_type)| attribute_type) {
+            Some(AttributeType::Uuid) => PrimaryKeyGeneration::Uuid,
+            Some(AttributeType::I32 | AttributeType::I64) => PrimaryKeyGeneration::Identity,
+            _ => PrimaryKeyGeneration::Client,
+        }
+    }
+
+    /**
+     * Generated primary keys are not part of the create payload.
+     */
+    pub fn generates_primary_key(&self) -> bool {
+        self.primary_key_generation() != PrimaryKeyGeneration::Client
+    }
+
+    /**
+     * Identity columns are assigned by the database, they are left out of inserts and updates.
+     */
+    pub fn is_database_generated(&self, attribute_name: &str) -> bool {
+        self.is_primary_key(attribute_name) && self.primary_key_generation() == PrimaryKeyGeneration::Identity
+    }
+
+    pub fn primary_key_attributes(&self) -> Vec<(&AttributeName, &AttributeType)> {
+        self.primary_key.iter().map(|primary_key| {
+            (primary_key, self.attribute_type(primary_key).unwrap_or_else(|| panic!("No primary key provided for {}", self.name)))
+        }).collect()
+    }
+
+    /**
+     * The Rust type of the primary key, a tuple for composite primary keys: `(Uuid, i32)`.
+     */
+    pub fn primary_key_type(&self) -> String {
+        let primary_key_types = self.primary_key_attributes().iter().map(|(_, attribute_type)| attribute_type.to_string()).collect::<Vec<String>>();
+        if self.has_composite_primary_key() {
+            format!("({})", primary_key_types.join(", "))
+        } else {
+            primary_key_types.join("")
+        }
+    }
+
+    pub fn uses_enums(&self) -> bool {
+        self.attributes.iter().any(|(_, attribute_type)| attribute_type.is_enum())
+    }
+
+    pub fn uses_json_types(&self) -> bool {
+        self.attributes.iter().any(|(_, attribute_type)| attribute_type.json_type_name().is_some())
+    }
+
+    /**
+     * Whether one of the values that the service takes to filter on, or to look up referenced entities, is an enum.
+     */
+    pub fn filters_on_enums(&self) -> bool {
+        self.filters().iter().flatten()
+            .chain(self.foreign_keys.iter().map(|(attribute_name, _)| attribute_name))
+            .filter_map(|attribute_name| self.attribute_type(attribute_name))
+            .any(|attribute_type| attribute_type.filter_type().is_enum())
+    }
+
+    pub fn uses_uuids(&self) -> bool {
+        self.attributes.iter().any(|(_, attribute_type)| attribute_type.filter_type() == &AttributeType::Uuid)
+    }
+
+    pub fn attribute_type(&self, attribute_name: &str) -> Option<&AttributeType> {
+        self.attributes.iter().find(|(name, _)| name == attribute_name).map(|(_, attribute_type)| attribute_type)
+    }
+
+    pub fn default_value(&self, attribute_name: &str) -> Option<&AttributeDefault> {
+        self.defaults.iter().find(|(name, _)| name == attribute_name).map(|(_, default)| default)
     }
 
     pub fn is_unique(&self, attribute_name: &str) -> bool {
         self.unique_attributes.iter().any(|unique_attributes| unique_attributes.contains(&attribute_name.to_string()))
-    
+
+    }
+
+    /**
+     * The attributes of this entity that are referenced by the foreign keys of the given entities, each listed once.
+     */
+    pub fn referenced_attributes<'a>(&self, entities: &'a [Entity]) -> Vec<&'a AttributeName> {
+        let mut referenced_attributes: Vec<&AttributeName> = Vec::new();
+        for (_, foreign_key) in entities.iter().flat_map(|entity| entity.foreign_keys.iter()) {
+            if foreign_key.entity_name == self.name && !referenced_attributes.contains(&&foreign_key.attribute_name) {
+                referenced_attributes.push(&foreign_key.attribute_name);
+            }
+        }
+        referenced_attributes
+    }
+
+    /**
+     * Foreign keys to a primary key that are not unique relate many entities to the referenced one, and are listed under it: `/v1/users/:id/cars`.
+     * Filters are named after their most specific attribute, so a foreign key is skipped when a longer filter_by ends with it.
+     */
+    pub fn nested_foreign_keys(&self) -> Vec<&(AttributeName, ForeginKey)> {
+        self.foreign_keys.iter().filter(|(attribute_name, foreign_key)| {
+            foreign_key.references_primary_key
+                && !self.is_unique(attribute_name)
+                && !self.filter_by.iter().any(|filter_by| filter_by.len() > 1 && filter_by.last() == Some(attribute_name))
+        }).collect()
+    }
+
+    /**
+     * The attributes that are filtered on a range, in the order of their bounds.
+     */
+    pub fn range_filter_attributes(&self) -> Vec<(&AttributeName, &AttributeType)> {
+        self.range_filter_by.iter().filter_map(|attribute_name| {
+            self.attribute_type(attribute_name).map(|attribute_type| (attribute_name, attribute_type))
+        }).collect()
+    }
+
+    /**
+     * The sortable attributes in both directions, each of which has its own query, so that the order is never built from user input.
+     */
+    pub fn sort_keys(&self) -> Vec<SortKey<'_>> {
+        self.sortable.iter().flat_map(|attribute_name| {
+            [false, true].map(|descending| SortKey { attribute_name, descending })
+        }).collect()
+    }
+
+    /**
+     * The attributes encoded in the cursor of an entity listed with cursors: the cursor attribute followed by the primary key,
+     * which breaks the ties between entities with the same cursor attribute.
+     */
+    pub fn cursor_attributes(&self) -> Vec<(&AttributeName, &AttributeType)> {
+        let Some(cursor_by) = &self.cursor_by else {
+            return vec![];
+        };
+        let mut cursor_attributes = vec![cursor_by];
+        cursor_attributes.extend(self.primary_key.iter().filter(|primary_key| *primary_key != cursor_by));
+        cursor_attributes.into_iter().filter_map(|attribute_name| {
+            self.attribute_type(attribute_name).map(|attribute_type| (attribute_name, attribute_type))
+        }).collect()
+    }
+
+    /**
+     * The filter_by, extended with the nested foreign keys that are not filtered on by themselves.
+     * The sources and services generate a filter for each of them.
+     */
+    pub fn filters(&self) -> Vec<FilterBy> {
+        let mut filters = self.filter_by.clone();
+        for (attribute_name, _) in self.nested_foreign_keys() {
+            let filter_by = vec![attribute_name.clone()];
+            if !filters.contains(&filter_by) {
+                filters.push(filter_by);
+            }
+        }
+        filters
     }
 
     /**
      * Possible constraints:
-     * - All attributes used as primary key, filter by, unique attributes, or foreign keys must be present
-     * - Primary key must be in the attributes
+     * - All attributes used as primary key, filter by or unique attributes must be present
+     * - All attributes of the primary key must be in the attributes, and cannot be nullable, arrays or JSON
+     * - Match filters can only be applied to string attributes
+     * - Range filters can only be applied to numeric, date and time attributes
+     * - Arrays and JSON attributes cannot be sortable
+     * - Only string attributes can be searchable, and no attribute can be named after the search column
+     * - The cursor attribute must be required, and cannot be an array, JSON or an interval. Entities listed with cursors cannot be sortable or searchable
      * - If there are unique attributes, they need to be present in filter_by
+     * - Arrays and JSON attributes cannot be unique, since filtering on them matches every row that contains the value
      * - If an attribute is unique, it cannot be used as a sub attribute in filter_by
      * For Example:
      * name is a unique attribute
      * It is not possible to have a filter_by with: [id, name], since name is already unique
+     *
+     * Foreign keys are already resolved against the other entities when the entity is created.
      */
-    pub fn verify_entity_constraints(&self, entities: &Vec<&Entity>) -> Result<(), String> {
+    pub fn verify_entity_constraints(&self) -> Result<(), ValidationErrors> {
         let attributes = self.attributes.iter().map(|(attribute_name, _)| attribute_name).collect::<Vec<&String>>();
+        let mut errors = Vec::new();
+
+        for primary_key in &self.primary_key {
+            match self.attribute_type(primary_key) {
+                None => errors.push(ValidationError::for_attribute(&self.name, primary_key, pointer(&self.path, "primary_key"), ValidationErrorKind::MissingPrimaryKey(primary_key.clone()))),
+                Some(attribute_type) if matches!(attribute_type, AttributeType::Option(_)) || attribute_type.is_array() || attribute_type.is_json() => {
+                    errors.push(ValidationError::for_attribute(&self.name, primary_key, pointer(&self.path, "primary_key"), ValidationErrorKind::InvalidPrimaryKeyType(primary_key.clone(), attribute_type.to_string())));
+                },
+                Some(_) => (),
+            }
+        }
+
+        for (group_index, unique_attributes) in self.unique_attributes.iter().enumerate() {
+            for unique_attribute in unique_attributes {
+                if !attributes.contains(&unique_attribute) {
+                    errors.push(ValidationError::for_attribute(&self.name, unique_attribute, pointer(&pointer(&self.path, "unique_attributes"), group_index), ValidationErrorKind::UnknownUniqueAttribute(unique_attribute.clone())));
+                } else if self.attribute_type(unique_attribute).is_some_and(|attribute_type| attribute_type.is_array() || attribute_type.is_json()) {
+                    errors.push(ValidationError::for_attribute(&self.name, unique_attribute, pointer(&pointer(&self.path, "unique_attributes"), group_index), ValidationErrorKind::UniqueContainerAttribute(unique_attribute.clone())));
+                }
+            }
+        }
 
-        if !attributes.contains(&&self.primary_key) {
-            return Err(format!("Primary key {} is not present in the attributes of {}", self.primary_key, self.name));
+        for (group_index, filter_by) in self.filter_by.iter().enumerate() {
+            for filter_by_attribute in filter_by {
+                if !attributes.contains(&filter_by_attribute) {
+                    errors.push(ValidationError::for_attribute(&self.name, filter_by_attribute, pointer(&pointer(&self.path, "filter_by"), group_index), ValidationErrorKind::UnknownFilterByAttribute(filter_by_attribute.clone())));
+                }
+            }
         }
 
-        for foreign_key in self.foreign_keys.iter() {
-            // search the foreign key in the other entities
-            let foreign_key_entity = 
-            entities.iter().find(|entity| entity.name == foreign_key.entity_name).expect(&format!("Foreign key entity {} is not present in the entities", foreign_key.entity_name));
-            if !foreign_key_entity.attributes.iter().map(|(attribute_name, _)| attribute_name).collect::<Vec<&String>>().contains(&&foreign_key.attribute_name) {
-                return Err(format!("Foreign key attribute {} is not present in the attributes of {}", foreign_key.attribute_name, foreign_key.entity_name));
+        for match_filter in &self.match_filters {
+            let attribute_name = &match_filter.attribute_name;
+            let match_filter_path = pointer(&self.path, "filter_by");
+            match self.attribute_type(attribute_name) {
+                None => errors.push(ValidationError::for_attribute(&self.name, attribute_name, match_filter_path, ValidationErrorKind::UnknownFilterByAttribute(attribute_name.clone()))),
+                Some(attribute_type) if !attribute_type.is_string() => {
+                    errors.push(ValidationError::for_attribute(&self.name, attribute_name, match_filter_path, ValidationErrorKind::MatchFilterNotString(attribute_name.clone(), attribute_type.to_string())));
+                },
+                Some(_) => (),
             }
         }
 
-        for unique_attribute in self.unique_attributes.iter().flatten().collect::<Vec<&String>>() {
-            if !attributes.contains(&unique_attribute) {
-                return Err(format!("Unique attribute {} is not present in the attributes of {}", unique_attribute, self.name));
+        for (attribute_index, range_filter_attribute) in self.range_filter_by.iter().enumerate() {
+            let range_filter_path = pointer(&pointer(&self.path, "range_filter_by"), attribute_index);
+            match self.attribute_type(range_filter_attribute) {
+                None => errors.push(ValidationError::for_attribute(&self.name, range_filter_attribute, range_filter_path, ValidationErrorKind::UnknownRangeFilterAttribute(range_filter_attribute.clone()))),
+                Some(attribute_type) if !attribute_type.is_orderable() => {
+                    errors.push(ValidationError::for_attribute(&self.name, range_filter_attribute, range_filter_path, ValidationErrorKind::RangeFilterNotOrderable(range_filter_attribute.clone(), attribute_type.to_string())));
+                },
+                Some(_) => (),
             }
         }
 
-        for filter_by_attribute in self.filter_by.iter().flatten().collect::<Vec<&String>>() {
-            if !attributes.contains(&filter_by_attribute) {
-                return Err(format!("Filter by attribute {} is not present in the attributes of {}", filter_by_attribute, self.name));
+        for (attribute_index, sortable_attribute) in self.sortable.iter().enumerate() {
+            let sortable_path = pointer(&pointer(&self.path, "sortable"), attribute_index);
+            match self.attribute_type(sortable_attribute) {
+                None => errors.push(ValidationError::for_attribute(&self.name, sortable_attribute, sortable_path, ValidationErrorKind::UnknownSortableAttribute(sortable_attribute.clone()))),
+                Some(attribute_type) if attribute_type.is_array() || attribute_type.is_json() => {
+                    errors.push(ValidationError::for_attribute(&self.name, sortable_attribute, sortable_path, ValidationErrorKind::SortableContainerAttribute(sortable_attribute.clone())));
+                },
+                Some(_) => (),
+            }
+        }
+
+        for (attribute_index, searchable_attribute) in self.searchable.iter().enumerate() {
+            let searchable_path = pointer(&pointer(&self.path, "searchable"), attribute_index);
+            match self.attribute_type(searchable_attribute) {
+                None => errors.push(ValidationError::for_attribute(&self.name, searchable_attribute, searchable_path, ValidationErrorKind::UnknownSearchableAttribute(searchable_attribute.clone()))),
+                Some(attribute_type) if !attribute_type.is_string() => {
+                    errors.push(ValidationError::for_attribute(&self.name, searchable_attribute, searchable_path, ValidationErrorKind::SearchableNotString(searchable_attribute.clone(), attribute_type.to_string())));
+                },
+                Some(_) => (),
+            }
+        }
+        if !self.searchable.is_empty() && self.attribute_type(SEARCH_COLUMN).is_some() {
+            errors.push(ValidationError::for_attribute(&self.name, SEARCH_COLUMN, pointer(&self.path, SEARCH_COLUMN), ValidationErrorKind::SearchColumnTaken));
+        }
+
+        if let Some(cursor_by) = &self.cursor_by {
+            let cursor_path = pointer(&self.path, "cursor_by");
+            match self.attribute_type(cursor_by) {
+                None => errors.push(ValidationError::for_attribute(&self.name, cursor_by, cursor_path.clone(), ValidationErrorKind::UnknownCursorAttribute(cursor_by.clone()))),
+                Some(attribute_type) if matches!(attribute_type, AttributeType::Option(_)) || attribute_type.is_array() || attribute_type.is_json() || attribute_type.is_interval() => {
+                    errors.push(ValidationError::for_attribute(&self.name, cursor_by, cursor_path.clone(), ValidationErrorKind::InvalidCursorAttribute(cursor_by.clone(), attribute_type.to_string())));
+                },
+                Some(_) => (),
+            }
+            if !self.sortable.is_empty() {
+                errors.push(ValidationError::for_entity(&self.name, cursor_path.clone(), ValidationErrorKind::SortableWithCursor));
+            }
+            if !self.searchable.is_empty() {
+                errors.push(ValidationError::for_entity(&self.name, cursor_path, ValidationErrorKind::SearchableWithCursor));
             }
         }
 
         // Verify that the unique attributes are present in the filter_by
-        for unique_attribute in self.unique_attributes.iter() {
+        for (group_index, unique_attribute) in self.unique_attributes.iter().enumerate() {
+            let unique_attribute_path = pointer(&pointer(&self.path, "unique_attributes"), group_index);
             if !self.filter_by.contains(unique_attribute) {
-                return Err(format!("Unique attribute {:?} is not present in the filter_by of {}", unique_attribute, self.name));
+                errors.push(ValidationError::for_entity(&self.name, unique_attribute_path.clone(), ValidationErrorKind::UniqueAttributesNotInFilterBy(unique_attribute.clone())));
             }
 
             let common = self.filter_by
             .iter()
             .filter(|filter_by| filter_by != &unique_attribute)
-            .any(|filter_by| {println!("{:?}", filter_by); is_sub(&filter_by, &unique_attribute)});
+            .any(|filter_by| is_sub(filter_by, unique_attribute));
 
             if common {
-                return Err(format!("Unique attribute {:?} is a sub attribute of another filter_by in {}. It does not make sense to have fine grained filters on unique attributes, since they're unique", unique_attribute, self.name));
+                errors.push(ValidationError::for_entity(&self.name, unique_attribute_path, ValidationErrorKind::UniqueAttributesInFineGrainedFilter(unique_attribute.clone())));
             }
         }
 
-        Ok(())
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
     }
 }
 
@@ -135,89 +601,332 @@ fn is_sub<T: PartialEq>(mut haystack: &[T], needle: &[T]) -> bool {
 }
 
 
-impl From<(EntityName, EntityPluralName, RawEntity, RawEntities)> for Entity {
-    fn from((entity_name, entity_plural_name, raw_entity, raw_entities): (EntityName, EntityPluralName, RawEntity, RawEntities)) -> Self {
-        let mut attributes = raw_entity.as_object().expect("Entity is not correctly formatted").iter().map(|(attribute_name, attribute_type)| {
-            let str_attribute_type = attribute_type.as_str().unwrap_or("Unknown");
-            let attribute_type = AttributeType::from_str(str_attribute_type);
-            (attribute_name.to_string(), attribute_type)
-        }).collect::<Vec<(AttributeName, AttributeType)>>();
-        
-        let mut unknown_attributes = Vec::new();
+impl TryFrom<(EntityPath, EntityName, EntityPluralName, RawEntity, RawEntities, Vec<EnumName>)> for Entity {
+    type Error = ValidationErrors;
 
-        let mut foreign_keys = Vec::new();
+    fn try_from((entity_path, entity_name, entity_plural_name, raw_entity, raw_entities, enum_names): (EntityPath, EntityName, EntityPluralName, RawEntity, RawEntities, Vec<EnumName>)) -> Result<Self, Self::Error> {
+        let raw_attributes = match raw_entity.as_object() {
+            Some(raw_attributes) => raw_attributes,
+            None => return Err(vec![ValidationError::for_entity(&entity_name, entity_path, ValidationErrorKind::MalformedEntity)]),
+        };
 
-        for attribute in attributes.iter_mut() {
-            match &attribute.1 {
-                AttributeType::Unknown(raw_attr_type) => {
-                    let foreign_key_ref = ForeginKey::from_str(&raw_attr_type);
-                    match foreign_key_ref {
-                        Some(foreign_key_ref) => {
-                            let foreign_key_entity = raw_entities.as_array().expect("Entities are not correctly formatted").iter().find(|entity| {
-                                entity.as_object().unwrap().contains_key(&foreign_key_ref.entity_name)
-                            }).unwrap().as_object().unwrap().get(&foreign_key_ref.entity_name).unwrap().as_object().unwrap();
-                            let foreign_key_attribute_type = foreign_key_entity.get(&foreign_key_ref.attribute_name).unwrap();
-                            let foreign_key_attribute_type = AttributeType::from_str(foreign_key_attribute_type.as_str().unwrap());
-                            attribute.1 = foreign_key_attribute_type;
-                            foreign_keys.push(foreign_key_ref);
-                        },
-                        None => {
-                            unknown_attributes.push(attribute.0.clone());
-                        }
-                    }
+        let mut errors = Vec::new();
+        let mut attributes = Vec::new();
+        let mut foreign_keys = Vec::new();
 
+        for (attribute_name, raw_attribute_type) in raw_attributes.iter().filter(|(key, _)| !RESERVED_KEYWORDS.contains(&key.as_str())) {
+            let attribute_path = pointer(&entity_path, attribute_name);
+            let str_attribute_type = match raw_attribute_type.as_str() {
+                Some(str_attribute_type) => str_attribute_type,
+                None => {
+                    errors.push(ValidationError::for_attribute(&entity_name, attribute_name, attribute_path, ValidationErrorKind::MalformedAttribute));
+                    continue;
+                }
+            };
+            let attribute_type = AttributeType::from_str(str_attribute_type).resolve_enums(&enum_names);
+            if attribute_type.is_known() {
+                match attribute_type.verify_parameters() {
+                    Ok(()) => attributes.push((attribute_name.to_string(), attribute_type)),
+                    Err(kind) => errors.push(ValidationError::for_attribute(&entity_name, attribute_name, attribute_path, kind)),
                 }
-                _ => {}
+                continue;
+            }
+            // Optional references are declared as `Option<Entity.attribute>`
+            let (nullable, foreign_reference) = match str_attribute_type.strip_prefix("Option<").and_then(|inner| inner.strip_suffix('>')) {
+                Some(foreign_reference) => (true, foreign_reference),
+                None => (false, str_attribute_type),
+            };
+            match ForeginKey::from_str(foreign_reference) {
+                Some(foreign_key_ref) => match foreign_key_ref.resolve_type(&raw_entities, &enum_names) {
+                    Ok(foreign_key_attribute_type) => {
+                        let foreign_key_attribute_type = match foreign_key_attribute_type {
+                            AttributeType::Option(_) => foreign_key_attribute_type,
+                            _ if nullable => AttributeType::Option(Box::new(foreign_key_attribute_type)),
+                            _ => foreign_key_attribute_type,
+                        };
+                        attributes.push((attribute_name.to_string(), foreign_key_attribute_type));
+                        foreign_keys.push((attribute_name.to_string(), foreign_key_ref));
+                    },
+                    Err(kind) => errors.push(ValidationError::for_attribute(&entity_name, attribute_name, attribute_path, kind)),
+                },
+                None => errors.push(ValidationError::for_attribute(&entity_name, attribute_name, attribute_path, ValidationErrorKind::UnknownAttributeType(str_attribute_type.to_string()))),
             }
         }
 
-        let attributes = attributes.into_iter().filter(|attribute| !unknown_attributes.contains(&attribute.0)).collect::<Vec<(AttributeName, AttributeType)>>();
-
-        let primary_key = raw_entity.get("primary_key").map(|primary_key| primary_key.as_str().unwrap().to_string()).unwrap_or("id".to_string());
-
-        let unique_attributes = raw_entity.get("unique_attributes").map(|unique_attributes| {
-            unique_attributes.as_array().unwrap().iter().map(|unique_attribute| {
-                match unique_attribute.as_array() {
-                    Some(unique_attribute) => unique_attribute.iter().map(|attribute| attribute.as_str().unwrap().to_string()).collect::<UniqueAttributes>(),
-                    None => vec![unique_attribute.as_str().unwrap().to_string()],
-                }
-            }).collect::<Vec<UniqueAttributes>>()
-        }).unwrap_or(vec![]);
-
-        let filter_by = raw_entity.get("filter_by").map(|filter_by| {
-            filter_by.as_array().unwrap().iter().map(|filter_by| {
-                match filter_by.as_array() {
-                    Some(filter_by) => filter_by.iter().map(|attribute| attribute.as_str().unwrap().to_string()).collect::<FilterBy>(),
-                    None => vec![filter_by.as_str().unwrap().to_string()],
-                }
-            }).collect::<Vec<FilterBy>>()
-        }).unwrap_or(vec![]);
+        let primary_key = parse_primary_key(&raw_entity, &entity_name, &entity_path, &mut errors);
 
+        let unique_attributes = parse_attribute_groups(&raw_entity, "unique_attributes", &entity_name, &entity_path, &mut errors);
+        let filter_by = parse_attribute_groups(&raw_entity, "filter_by", &entity_name, &entity_path, &mut errors);
+        let match_filters = parse_match_filters(&raw_entity, &entity_name, &entity_path, &mut errors);
+        let range_filter_by = parse_attribute_names(&raw_entity, "range_filter_by", &entity_name, &entity_path, &mut errors);
+        let sortable = parse_attribute_names(&raw_entity, "sortable", &entity_name, &entity_path, &mut errors);
+        let searchable = parse_attribute_names(&raw_entity, "searchable", &entity_name, &entity_path, &mut errors);
+        let cursor_by = match raw_entity.get("cursor_by") {
+            Some(Value::String(cursor_by)) => Some(cursor_by.to_string()),
+            Some(_) => {
+                errors.push(ValidationError::for_entity(&entity_name, pointer(&entity_path, "cursor_by"), ValidationErrorKind::MalformedReservedKeyword("cursor_by")));
+                None
+            },
+            None => None,
+        };
+        let defaults = parse_defaults(&raw_entity, &attributes, &entity_name, &entity_path, &mut errors);
+        for (attribute_name, action) in parse_referential_actions(&raw_entity, "on_delete", &attributes, &foreign_keys, &entity_name, &entity_path, &mut errors) {
+            if let Some((_, foreign_key)) = foreign_keys.iter_mut().find(|(name, _)| name == &attribute_name) {
+                foreign_key.on_delete = Some(action);
+            }
+        }
+        for (attribute_name, action) in parse_referential_actions(&raw_entity, "on_update", &attributes, &foreign_keys, &entity_name, &entity_path, &mut errors) {
+            if let Some((_, foreign_key)) = foreign_keys.iter_mut().find(|(name, _)| name == &attribute_name) {
+                foreign_key.on_update = Some(action);
+            }
+        }
 
-        Entity {
+        let entity = Entity {
             name: entity_name,
             plural_name: entity_plural_name,
+            path: entity_path,
             attributes,
             primary_key,
             foreign_keys,
             unique_attributes,
             filter_by,
+            match_filters,
+            range_filter_by,
+            sortable,
+            cursor_by,
+            searchable,
+            defaults,
+        };
+
+        // Attributes that could not be parsed are already reported, they should not be reported again as missing.
+        let invalid_attributes = errors.iter().filter_map(|error| error.attribute.clone()).collect::<Vec<AttributeName>>();
+        if let Err(constraint_errors) = entity.verify_entity_constraints() {
+            errors.extend(constraint_errors.into_iter().filter(|error| {
+                error.attribute.as_ref().is_none_or(|attribute| !invalid_attributes.contains(attribute))
+            }));
+        }
+
+        if errors.is_empty() { Ok(entity) } else { Err(errors) }
+    }
+}
+
+/**
+ * Parses the `primary_key` reserved keyword: a single attribute name, or an array of attribute names for a composite primary key.
+ * It defaults to `id`.
+ */
+fn parse_primary_key(raw_entity: &RawEntity, entity_name: &str, entity_path: &str, errors: &mut ValidationErrors) -> PrimaryKey {
+    let primary_key = match raw_entity.get("primary_key") {
+        Some(Value::String(primary_key)) => Some(vec![primary_key.to_string()]),
+        Some(Value::Array(primary_key)) => primary_key
+            .iter()
+            .map(|primary_key| primary_key.as_str().map(str::to_string))
+            .collect::<Option<PrimaryKey>>()
+            .filter(|primary_key| !primary_key.is_empty() && primary_key.iter().enumerate().all(|(index, attribute)| !primary_key[..index].contains(attribute))),
+        Some(_) => None,
+        None => return vec!["id".to_string()],
+    };
+    primary_key.unwrap_or_else(|| {
+        errors.push(ValidationError::for_entity(entity_name, pointer(entity_path, "primary_key"), ValidationErrorKind::MalformedReservedKeyword("primary_key")));
+        vec!["id".to_string()]
+    })
+}
+
+/**
+ * Parses reserved keywords such as `filter_by` and `unique_attributes`.
+ * They consist of an array in which every element is either a single attribute name or an array of attribute names.
+ */
+fn parse_attribute_groups(raw_entity: &RawEntity, keyword: &'static str, entity_name: &str, entity_path: &str, errors: &mut ValidationErrors) -> Vec<Vec<AttributeName>> {
+    let keyword_path = pointer(entity_path, keyword);
+    let raw_groups = match raw_entity.get(keyword) {
+        Some(raw_groups) => raw_groups,
+        None => return vec![],
+    };
+    let raw_groups = match raw_groups.as_array() {
+        Some(raw_groups) => raw_groups,
+        None => {
+            errors.push(ValidationError::for_entity(entity_name, keyword_path, ValidationErrorKind::MalformedReservedKeyword(keyword)));
+            return vec![];
+        }
+    };
+    let mut groups = Vec::new();
+    for (group_index, raw_group) in raw_groups.iter().enumerate() {
+        let group_path = pointer(&keyword_path, group_index);
+        match (raw_group.as_str(), raw_group.as_array()) {
+            (Some(attribute_name), _) => groups.push(vec![attribute_name.to_string()]),
+            // Match filters, see parse_match_filters
+            _ if keyword == "filter_by" && raw_group.is_object() => (),
+            (_, Some(raw_group)) => {
+                let mut group = Vec::new();
+                for (attribute_index, attribute_name) in raw_group.iter().enumerate() {
+                    match attribute_name.as_str() {
+                        Some(attribute_name) => group.push(attribute_name.to_string()),
+                        None => errors.push(ValidationError::for_entity(entity_name, pointer(&group_path, attribute_index), ValidationErrorKind::MalformedReservedKeyword(keyword))),
+                    }
+                }
+                groups.push(group);
+            },
+            _ => errors.push(ValidationError::for_entity(entity_name, group_path, ValidationErrorKind::MalformedReservedKeyword(keyword))),
+        }
+    }
+    groups
+}
+
+/**
+ * Parses the match filters of `filter_by`, the objects that map string attributes to their match mode:
+ * `"filter_by": ["email", {"name": "prefix", "city": "contains"}]`. A filter that is declared more than once is only kept once.
+ */
+fn parse_match_filters(raw_entity: &RawEntity, entity_name: &str, entity_path: &str, errors: &mut ValidationErrors) -> Vec<MatchFilter> {
+    let keyword_path = pointer(entity_path, "filter_by");
+    let raw_groups = match raw_entity.get("filter_by").and_then(Value::as_array) {
+        Some(raw_groups) => raw_groups,
+        None => return vec![],
+    };
+    let mut match_filters = Vec::new();
+    for (group_index, raw_group) in raw_groups.iter().enumerate() {
+        let raw_modes = match raw_group.as_object() {
+            Some(raw_modes) => raw_modes,
+            None => continue,
+        };
+        for (attribute_name, raw_mode) in raw_modes {
+            let mode_path = pointer(&pointer(&keyword_path, group_index), attribute_name);
+            match raw_mode.as_str().and_then(MatchMode::parse) {
+                Some(mode) => {
+                    let match_filter = MatchFilter { attribute_name: attribute_name.clone(), mode };
+                    if !match_filters.contains(&match_filter) {
+                        match_filters.push(match_filter);
+                    }
+                },
+                None => errors.push(ValidationError::for_attribute(entity_name, attribute_name, mode_path, ValidationErrorKind::InvalidMatchMode(raw_mode.to_string()))),
+            }
+        }
+    }
+    match_filters
+}
+
+/**
+ * Parses reserved keywords such as `range_filter_by`, which consist of an array of attribute names.
+ * An attribute that is listed more than once is only kept once.
+ */
+fn parse_attribute_names(raw_entity: &RawEntity, keyword: &'static str, entity_name: &str, entity_path: &str, errors: &mut ValidationErrors) -> Vec<AttributeName> {
+    let keyword_path = pointer(entity_path, keyword);
+    let raw_attribute_names = match raw_entity.get(keyword) {
+        Some(raw_attribute_names) => raw_attribute_names,
+        None => return vec![],
+    };
+    let raw_attribute_names = match raw_attribute_names.as_array() {
+        Some(raw_attribute_names) => raw_attribute_names,
+        None => {
+            errors.push(ValidationError::for_entity(entity_name, keyword_path, ValidationErrorKind::MalformedReservedKeyword(keyword)));
+            return vec![];
+        }
+    };
+    let mut attribute_names: Vec<AttributeName> = Vec::new();
+    for (attribute_index, attribute_name) in raw_attribute_names.iter().enumerate() {
+        match attribute_name.as_str() {
+            Some(attribute_name) if attribute_names.iter().any(|name| name == attribute_name) => (),
+            Some(attribute_name) => attribute_names.push(attribute_name.to_string()),
+            None => errors.push(ValidationError::for_entity(entity_name, pointer(&keyword_path, attribute_index), ValidationErrorKind::MalformedReservedKeyword(keyword))),
         }
     }
-        
+    attribute_names
 }
 
+/**
+ * Parses the `defaults` reserved keyword: an object mapping attribute names to their default values.
+ * Defaults of attributes that could not be parsed are skipped, the attribute is already reported.
+ */
+fn parse_defaults(raw_entity: &RawEntity, attributes: &[(AttributeName, AttributeType)], entity_name: &str, entity_path: &str, errors: &mut ValidationErrors) -> Vec<(AttributeName, AttributeDefault)> {
+    let defaults_path = pointer(entity_path, "defaults");
+    let raw_defaults = match raw_entity.get("defaults") {
+        Some(raw_defaults) => raw_defaults,
+        None => return vec![],
+    };
+    let raw_defaults = match raw_defaults.as_object() {
+        Some(raw_defaults) => raw_defaults,
+        None => {
+            errors.push(ValidationError::for_entity(entity_name, defaults_path, ValidationErrorKind::MalformedReservedKeyword("defaults")));
+            return vec![];
+        }
+    };
+    let mut defaults = Vec::new();
+    for (attribute_name, raw_default) in raw_defaults {
+        let default_path = pointer(&defaults_path, attribute_name);
+        if raw_entity.get(attribute_name).is_none() || RESERVED_KEYWORDS.contains(&attribute_name.as_str()) {
+            errors.push(ValidationError::for_attribute(entity_name, attribute_name, default_path, ValidationErrorKind::UnknownDefaultAttribute(attribute_name.clone())));
+            continue;
+        }
+        let Some((_, attribute_type)) = attributes.iter().find(|(name, _)| name == attribute_name) else {
+            continue;
+        };
+        match AttributeDefault::new(attribute_type, raw_default.clone()) {
+            Ok(default) => defaults.push((attribute_name.clone(), default)),
+            Err(kind) => errors.push(ValidationError::for_attribute(entity_name, attribute_name, default_path, kind)),
+        }
+    }
+    defaults
+}
+
+/**
+ * Parses the `on_delete` and `on_update` reserved keywords: an object mapping foreign key attributes to
+ * `cascade`, `restrict` or `set null`. Only nullable attributes can be set to null.
+ */
+fn parse_referential_actions(raw_entity: &RawEntity, keyword: &'static str, attributes: &[(AttributeName, AttributeType)], foreign_keys: &[(AttributeName, ForeginKey)], entity_name: &str, entity_path: &str, errors: &mut ValidationErrors) -> Vec<(AttributeName, ReferentialAction)> {
+    let keyword_path = pointer(entity_path, keyword);
+    let raw_actions = match raw_entity.get(keyword) {
+        Some(raw_actions) => raw_actions,
+        None => return vec![],
+    };
+    let raw_actions = match raw_actions.as_object() {
+        Some(raw_actions) => raw_actions,
+        None => {
+            errors.push(ValidationError::for_entity(entity_name, keyword_path, ValidationErrorKind::MalformedReservedKeyword(keyword)));
+            return vec![];
+        }
+    };
+    let mut actions = Vec::new();
+    for (attribute_name, raw_action) in raw_actions {
+        let action_path = pointer(&keyword_path, attribute_name);
+        if !foreign_keys.iter().any(|(name, _)| name == attribute_name) {
+            // Foreign keys that could not be resolved are already reported
+            if raw_entity.get(attribute_name).is_none() || attributes.iter().any(|(name, _)| name == attribute_name) {
+                errors.push(ValidationError::for_attribute(entity_name, attribute_name, action_path, ValidationErrorKind::NotAForeignKey(attribute_name.clone())));
+            }
+            continue;
+        }
+        match raw_action.as_str().and_then(ReferentialAction::parse) {
+            Some(ReferentialAction::SetNull) if !attributes.iter().any(|(name, attribute_type)| name == attribute_name && matches!(attribute_type, AttributeType::Option(_))) => {
+                errors.push(ValidationError::for_attribute(entity_name, attribute_name, action_path, ValidationErrorKind::SetNullOnRequiredAttribute(attribute_name.clone())));
+            },
+            Some(action) => actions.push((attribute_name.clone(), action)),
+            None => errors.push(ValidationError::for_attribute(entity_name, attribute_name, action_path, ValidationErrorKind::InvalidReferentialAction(raw_action.to_string()))),
+        }
+    }
+    actions
+}
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub enum AttributeType {
     String,
+    /// `String(n)`, a string of at most n characters.
+    VarChar(u32),
+    Text,
     Uuid,
     I32,
     I64,
     F32,
     F64,
+    /// Precision and scale, when they are given.
+    Decimal(Option<(u32, u32)>),
     Boolean,
     NaiveDateTime,
+    DateTimeUtc,
+    NaiveDate,
+    NaiveTime,
+    Interval,
+    Enum(EnumName),
+    /// JSONB column, mapped to `serde_json::Value` or to the named struct.
+    Json(Option<JsonTypeName>),
+    Vec(Box<AttributeType>),
     Option(Box<AttributeType>),
     Unknown(String),
 }
@@ -227,13 +936,23 @@ pub enum AttributeType {
 pub enum PostgresAttributeType {
 
     VARCHAR,
+    VARCHAR_N(u32),
+    TEXT,
     UUID,
     INT,
     BIGINT,
     REAL,
     DOUBLE_PRECISION,
+    NUMERIC(Option<(u32, u32)>),
     BOOLEAN,
     TIMESTAMP,
+    TIMESTAMPTZ,
+    DATE,
+    TIME,
+    INTERVAL,
+    ENUM(EnumName),
+    JSONB,
+    ARRAY(Box<PostgresAttributeType>),
     #[strum(props(is_nullable = "true"))]
     OPTION(Box<PostgresAttributeType>),
     UNKNOWN,
@@ -241,20 +960,59 @@ pub enum PostgresAttributeType {
 
 impl PostgresAttributeType {
     pub fn is_nullable(&self) -> bool {
-        self.get_bool("is_nullable").unwrap_or(false)
+        self.get_str("is_nullable") == Some("true")
+    }
+
+    /**
+     * The type of the column, without its nullability.
+     */
+    pub fn column_type(&self) -> String {
+        match self {
+            PostgresAttributeType::VARCHAR => "VARCHAR(255)".to_string(),
+            PostgresAttributeType::VARCHAR_N(max_length) => format!("VARCHAR({max_length})"),
+            PostgresAttributeType::TEXT => "TEXT".to_string(),
+            PostgresAttributeType::UUID => "UUID".to_string(),
+            PostgresAttributeType::INT => "INT".to_string(),
+            PostgresAttributeType::BIGINT => "BIGINT".to_string(),
+            PostgresAttributeType::REAL => "REAL".to_string(),
+            PostgresAttributeType::DOUBLE_PRECISION => "DOUBLE PRECISION".to_string(),
+            PostgresAttributeType::NUMERIC(None) => "NUMERIC".to_string(),
+            PostgresAttributeType::NUMERIC(Some((precision, scale))) => format!("NUMERIC({precision},{scale})"),
+            PostgresAttributeType::BOOLEAN => "BOOLEAN".to_string(),
+            PostgresAttributeType::TIMESTAMP => "TIMESTAMP".to_string(),
+            PostgresAttributeType::TIMESTAMPTZ => "TIMESTAMPTZ".to_string(),
+            PostgresAttributeType::DATE => "DATE".to_string(),
+            PostgresAttributeType::TIME => "TIME".to_string(),
+            PostgresAttributeType::INTERVAL => "INTERVAL".to_string(),
+            PostgresAttributeType::ENUM(enum_name) => to_snake_case(enum_name),
+            PostgresAttributeType::JSONB => "JSONB".to_string(),
+            PostgresAttributeType::ARRAY(attribute_type) => format!("{}[]", attribute_type.column_type()),
+            PostgresAttributeType::OPTION(attribute_type) => attribute_type.column_type(),
+            PostgresAttributeType::UNKNOWN => panic!("Unknown attribute type"),
+        }
     }
 }
 impl From<&AttributeType> for PostgresAttributeType {
     fn from(attribute_type: &AttributeType) -> Self {
         match attribute_type {
             AttributeType::String => PostgresAttributeType::VARCHAR,
+            AttributeType::VarChar(max_length) => PostgresAttributeType::VARCHAR_N(*max_length),
+            AttributeType::Text => PostgresAttributeType::TEXT,
             AttributeType::Uuid => PostgresAttributeType::UUID,
             AttributeType::I32 => PostgresAttributeType::INT,
             AttributeType::I64 => PostgresAttributeType::BIGINT,
             AttributeType::F32 => PostgresAttributeType::REAL,
             AttributeType::F64 => PostgresAttributeType::DOUBLE_PRECISION,
+            AttributeType::Decimal(precision_and_scale) => PostgresAttributeType::NUMERIC(*precision_and_scale),
             AttributeType::Boolean => PostgresAttributeType::BOOLEAN,
             AttributeType::NaiveDateTime => PostgresAttributeType::TIMESTAMP,
+            AttributeType::DateTimeUtc => PostgresAttributeType::TIMESTAMPTZ,
+            AttributeType::NaiveDate => PostgresAttributeType::DATE,
+            AttributeType::NaiveTime => PostgresAttributeType::TIME,
+            AttributeType::Interval => PostgresAttributeType::INTERVAL,
+            AttributeType::Enum(enum_name) => PostgresAttributeType::ENUM(enum_name.clone()),
+            AttributeType::Json(_) => PostgresAttributeType::JSONB,
+            AttributeType::Vec(attribute_type) => PostgresAttributeType::ARRAY(Box::new(Into::<PostgresAttributeType>::into(attribute_type.as_ref()))),
             AttributeType::Option(attribute_type) => PostgresAttributeType::OPTION(Box::new(Into::<PostgresAttributeType>::into(attribute_type.as_ref()))),
             AttributeType::Unknown(_) => PostgresAttributeType::UNKNOWN,
         }
@@ -264,54 +1022,212 @@ impl From<&AttributeType> for PostgresAttributeType {
 
 impl ToString for PostgresAttributeType {
     fn to_string(&self) -> String {
-        let attr_type = match self {
-            PostgresAttributeType::VARCHAR => "VARCHAR(255)".to_string(),
-            PostgresAttributeType::UUID => "UUID".to_string(),
-            PostgresAttributeType::INT => "INT".to_string(),
-            PostgresAttributeType::BIGINT => "BIGINT".to_string(),
-            PostgresAttributeType::REAL => "REAL".to_string(),
-            PostgresAttributeType::DOUBLE_PRECISION => "DOUBLE PRECISION".to_string(),
-            PostgresAttributeType::BOOLEAN => "BOOLEAN".to_string(),
-            PostgresAttributeType::TIMESTAMP => "TIMESTAMP".to_string(),
-            PostgresAttributeType::OPTION(attribute_type) => format!("{}", attribute_type.to_string()),
-            PostgresAttributeType::UNKNOWN => panic!("Unknown attribute type"),
-        };
-        attr_type + if self.is_nullable() { "" } else { " NOT NULL" }
+        self.column_type() + if self.is_nullable() { "" } else { " NOT NULL" }
     }
 }
 
 impl AttributeType {
     pub fn from_str(s: &str) -> AttributeType {
+        if let Some((_, attribute_type)) = PRIMITIVE_TYPES.iter().find(|(type_name, _)| *type_name == s) {
+            return attribute_type.clone();
+        }
         match s {
-            "String" => AttributeType::String,
-            "Uuid" => AttributeType::Uuid,
-            "i32" => AttributeType::I32,
-            "i64" => AttributeType::I64,
-            "f32" => AttributeType::F32,
-            "f64" => AttributeType::F64,
-            "bool" => AttributeType::Boolean,
-            "NaiveDateTime" => AttributeType::NaiveDateTime,
-            "chrono::NaiveDateTime" => AttributeType::NaiveDateTime,
+            _ if s.contains('(') && s.ends_with(")") => {
+                let (type_name, parameters) = s[..s.len() - 1].split_once('(').unwrap();
+                let parameters = parameters.split(',').map(|n| n.trim().parse::<u32>()).collect::<Result<Vec<u32>, _>>();
+                match (type_name, parameters.as_deref()) {
+                    ("String", Ok([max_length])) => AttributeType::VarChar(*max_length),
+                    ("Decimal", Ok([precision, scale])) => AttributeType::Decimal(Some((*precision, *scale))),
+                    _ => AttributeType::Unknown(s.to_string()),
+                }
+            }
+            "Json" => AttributeType::Json(None),
+            _ if s.starts_with("Json<") && s.ends_with(">") => {
+                let json_type_name = s[5..s.len() - 1].to_string();
+                if AttributeType::is_json_type_name(&json_type_name) {
+                    AttributeType::Json(Some(json_type_name))
+                } else {
+                    AttributeType::Unknown(s.to_string())
+                }
+            }
             _ if s.starts_with("Option<") && s.ends_with(">") => {
                 let inner_type = s[7..s.len() - 1].to_string();
                 AttributeType::Option(Box::new(AttributeType::from_str(inner_type.as_str())))
             }
+            _ if s.starts_with("Vec<") && s.ends_with(">") => {
+                let inner_type = s[4..s.len() - 1].to_string();
+                match AttributeType::from_str(inner_type.as_str()) {
+                    // Nested and nullable elements do not map well to Postgres arrays, JSONB has its own arrays
+                    AttributeType::Vec(_) | AttributeType::Option(_) | AttributeType::Json(_) => AttributeType::Unknown(s.to_string()),
+                    attribute_type => AttributeType::Vec(Box::new(attribute_type)),
+                }
+            }
             _ => AttributeType::Unknown(s.to_string()),
         }
 }
+
+    /**
+     * A JSON type name matches `JSON_TYPE_NAME_PATTERN`: an ASCII letter, then ASCII letters, digits or underscores.
+     */
+    fn is_json_type_name(json_type_name: &str) -> bool {
+        json_type_name.starts_with(|c: char| c.is_ascii_alphabetic()) && json_type_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    /**
+     * `from_str` cannot distinguish enums from unknown types, since enums are declared in the request.
+     */
+    pub fn resolve_enums(self, enum_names: &[EnumName]) -> AttributeType {
+        match self {
+            AttributeType::Unknown(name) if enum_names.contains(&name) => AttributeType::Enum(name),
+            AttributeType::Option(attribute_type) => AttributeType::Option(Box::new(attribute_type.resolve_enums(enum_names))),
+            AttributeType::Vec(attribute_type) => AttributeType::Vec(Box::new(attribute_type.resolve_enums(enum_names))),
+            attribute_type => attribute_type,
+        }
+    }
+
+    /**
+     * Verifies the parameters of parameterized types, such as the precision and scale of decimals.
+     */
+    pub fn verify_parameters(&self) -> Result<(), ValidationErrorKind> {
+        match self {
+            AttributeType::Decimal(Some((precision, scale))) if !(1..=MAX_DECIMAL_PRECISION).contains(precision) || scale > precision => {
+                Err(ValidationErrorKind::InvalidDecimalPrecision(*precision, *scale))
+            },
+            AttributeType::VarChar(max_length) if !(1..=MAX_VARCHAR_LENGTH).contains(max_length) => {
+                Err(ValidationErrorKind::InvalidStringLength(*max_length))
+            },
+            AttributeType::Option(attribute_type) | AttributeType::Vec(attribute_type) => attribute_type.verify_parameters(),
+            _ => Ok(()),
+        }
+    }
+
+    /**
+     * The Rust type that sqlx cannot infer from the column by itself, if any.
+     * It is used as type override for the output columns of `query_as!`.
+     */
+    pub fn sqlx_type_override(&self) -> Option<String> {
+        match self {
+            AttributeType::Enum(enum_name) => Some(enum_name.clone()),
+            AttributeType::Json(Some(_)) | AttributeType::Interval => Some(self.to_string()),
+            AttributeType::Option(attribute_type) => attribute_type.sqlx_type_override(),
+            AttributeType::Vec(attribute_type) => attribute_type.sqlx_type_override().map(|element_type| format!("Vec<{element_type}>")),
+            _ => None,
+        }
+    }
+
+    /**
+     * The type of the values by which the attribute is filtered.
+     * Arrays are filtered on one of their elements, i.e. the rows whose array contains the value.
+     * JSON attributes are filtered on the rows that contain the given JSON document, which is passed as a string.
+     */
+    pub fn filter_type(&self) -> &AttributeType {
+        match self {
+            AttributeType::Option(attribute_type) | AttributeType::Vec(attribute_type) => attribute_type.filter_type(),
+            AttributeType::Json(_) => &AttributeType::String,
+            attribute_type => attribute_type,
+        }
+    }
+
+    pub fn is_enum(&self) -> bool {
+        match self {
+            AttributeType::Enum(_) => true,
+            AttributeType::Option(attribute_type) | AttributeType::Vec(attribute_type) => attribute_type.is_enum(),
+            _ => false,
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        match self {
+            AttributeType::Json(_) => true,
+            AttributeType::Option(attribute_type) => attribute_type.is_json(),
+            _ => false,
+        }
+    }
+
+    pub fn json_type_name(&self) -> Option<&JsonTypeName> {
+        match self {
+            AttributeType::Json(json_type_name) => json_type_name.as_ref(),
+            AttributeType::Option(attribute_type) => attribute_type.json_type_name(),
+            _ => None,
+        }
+    }
+
+    /**
+     * The maximum number of characters of the attribute, or of its elements, if it is declared.
+     */
+    pub fn max_length(&self) -> Option<u32> {
+        match self {
+            AttributeType::VarChar(max_length) => Some(*max_length),
+            AttributeType::Option(attribute_type) | AttributeType::Vec(attribute_type) => attribute_type.max_length(),
+            _ => None,
+        }
+    }
+
+    pub fn is_interval(&self) -> bool {
+        match self {
+            AttributeType::Interval => true,
+            AttributeType::Option(attribute_type) | AttributeType::Vec(attribute_type) => attribute_type.is_interval(),
+            _ => false,
+        }
+    }
+
+    pub fn is_string(&self) -> bool {
+        match self {
+            AttributeType::String | AttributeType::VarChar(_) | AttributeType::Text => true,
+            AttributeType::Option(attribute_type) => attribute_type.is_string(),
+            _ => false,
+        }
+    }
+
+    /**
+     * Numbers, dates and times can be compared, so they can be filtered on a range of values.
+     */
+    pub fn is_orderable(&self) -> bool {
+        match self {
+            AttributeType::I32 | AttributeType::I64 | AttributeType::F32 | AttributeType::F64 | AttributeType::Decimal(_) => true,
+            AttributeType::NaiveDateTime | AttributeType::DateTimeUtc | AttributeType::NaiveDate | AttributeType::NaiveTime => true,
+            AttributeType::Option(attribute_type) => attribute_type.is_orderable(),
+            _ => false,
+        }
+    }
+
+    pub fn is_array(&self) -> bool {
+        match self {
+            AttributeType::Vec(_) => true,
+            AttributeType::Option(attribute_type) => attribute_type.is_array(),
+            _ => false,
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        match self {
+            AttributeType::Option(attribute_type) | AttributeType::Vec(attribute_type) => attribute_type.is_known(),
+            AttributeType::Unknown(_) => false,
+            _ => true,
+        }
+    }
 }
 
 impl Display for AttributeType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
             let str = match self {
-                AttributeType::String => "String".to_string(),
+                AttributeType::String | AttributeType::VarChar(_) | AttributeType::Text => "String".to_string(),
                 AttributeType::Uuid => "Uuid".to_string(),
                 AttributeType::I32 => "i32".to_string(),
                 AttributeType::I64 => "i64".to_string(),
                 AttributeType::F32 => "f32".to_string(),
                 AttributeType::F64 => "f64".to_string(),
+                AttributeType::Decimal(_) => "rust_decimal::Decimal".to_string(),
                 AttributeType::Boolean => "bool".to_string(),
                 AttributeType::NaiveDateTime => "chrono::NaiveDateTime".to_string(),
+                AttributeType::DateTimeUtc => "chrono::DateTime<chrono::Utc>".to_string(),
+                AttributeType::NaiveDate => "chrono::NaiveDate".to_string(),
+                AttributeType::NaiveTime => "chrono::NaiveTime".to_string(),
+                AttributeType::Interval => "crate::models::interval::Interval".to_string(),
+                AttributeType::Enum(enum_name) => enum_name.to_string(),
+                AttributeType::Json(None) => "serde_json::Value".to_string(),
+                AttributeType::Json(Some(json_type_name)) => format!("sqlx::types::Json<{json_type_name}>"),
+                AttributeType::Vec(attribute_type) => format!("Vec<{}>", attribute_type),
                 AttributeType::Option(attribute_type) => format!("Option<{}>", attribute_type.to_string()),
                 AttributeType::Unknown(unknown) => panic!("Unknown attribute type {unknown}"),
             };
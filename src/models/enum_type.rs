@@ -0,0 +1,67 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::utils::naming_convention::to_pascal_case;
+
+use super::validation::{pointer, ValidationError, ValidationErrorKind, ValidationErrors};
+
+pub type EnumName = String;
+pub type RawEnum = Value;
+
+/**
+ * An enum declared in the `enums` section of the request, for example:
+ * `"enums": [{ "CarStatus": ["available", "in_repair", "sold"] }]`
+ * The values are stored as is in the database and in the JSON payloads.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnumType {
+    pub name: EnumName,
+    pub values: Vec<String>,
+}
+
+impl EnumType {
+    /**
+     * The name of the variant in the generated Rust enum.
+     */
+    pub fn variant_name(value: &str) -> String {
+        to_pascal_case(value)
+    }
+
+    fn is_valid_value(value: &str) -> bool {
+        value.starts_with(|c: char| c.is_ascii_alphabetic())
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    }
+}
+
+impl TryFrom<(String, EnumName, RawEnum)> for EnumType {
+    type Error = ValidationErrors;
+
+    fn try_from((enum_path, enum_name, raw_enum): (String, EnumName, RawEnum)) -> Result<Self, Self::Error> {
+        let raw_values = match raw_enum.as_array() {
+            Some(raw_values) if !raw_values.is_empty() => raw_values,
+            _ => return Err(vec![ValidationError::for_entity(&enum_name, enum_path, ValidationErrorKind::MalformedEnum)]),
+        };
+        let mut errors = Vec::new();
+        let mut values: Vec<String> = Vec::new();
+        for (value_index, raw_value) in raw_values.iter().enumerate() {
+            let value_path = pointer(&enum_path, value_index);
+            match raw_value.as_str() {
+                Some(value) if !EnumType::is_valid_value(value) => {
+                    errors.push(ValidationError::for_entity(&enum_name, value_path, ValidationErrorKind::InvalidEnumValue(value.to_string())));
+                },
+                Some(value) if values.iter().any(|existing| EnumType::variant_name(existing) == EnumType::variant_name(value)) => {
+                    errors.push(ValidationError::for_entity(&enum_name, value_path, ValidationErrorKind::DuplicateEnumValue(value.to_string())));
+                },
+                Some(value) => values.push(value.to_string()),
+                None => errors.push(ValidationError::for_entity(&enum_name, value_path, ValidationErrorKind::MalformedEnum)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(EnumType {
+            name: enum_name,
+            values,
+        })
+    }
+}
@@ -1,2 +1,11 @@
 pub mod ddr_req;
-pub mod entity;
\ No newline at end of file
+pub mod entity;
+pub mod ddr_format;
+
+pub mod validation;
+
+pub mod ddr_schema;
+
+pub mod enum_type;
+pub mod attribute_default;
+pub mod relation;
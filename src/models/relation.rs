@@ -0,0 +1,98 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+use crate::utils::naming_convention::{to_plural, to_snake_case};
+
+use super::{entity::{AttributeName, AttributeType, Entity, EntityName, EntityPluralName}, validation::{pointer, ValidationError, ValidationErrorKind, ValidationErrors}};
+
+pub type RelationName = String;
+pub type RawRelation = Value;
+
+/**
+ * A many-to-many relation declared in the `relations` section of the request, for example:
+ * `"relations": [{ "Membership": ["User", "Group"] }]`
+ * Every relation is stored in a join table, `memberships`, with a column per related entity
+ * that references its primary key. The pair of columns is the primary key of the join table.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Relation {
+    pub name: RelationName,
+    pub plural_name: EntityPluralName,
+    pub ends: [RelationEnd; 2],
+}
+
+/**
+ * One of the two entities of a relation, and the column of the join table that references it: `user_id`.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelationEnd {
+    pub entity_name: EntityName,
+    pub plural_name: EntityPluralName,
+    pub primary_key: AttributeName,
+    pub attribute_name: AttributeName,
+    pub attribute_type: AttributeType,
+}
+
+impl RelationEnd {
+    fn new(entity: &Entity) -> Self {
+        let (primary_key, attribute_type) = entity.primary_key_attributes()[0];
+        RelationEnd {
+            entity_name: entity.name.clone(),
+            plural_name: entity.plural_name.clone(),
+            primary_key: primary_key.clone(),
+            attribute_name: format!("{}_{}", to_snake_case(&entity.name), primary_key),
+            attribute_type: attribute_type.clone(),
+        }
+    }
+}
+
+impl Relation {
+    /**
+     * The ends of the relation seen from one of them: the entity that is listed under, and the entities it is related to.
+     * Both directions get routes, `/v1/users/:id/groups` and `/v1/groups/:id/users`.
+     */
+    pub fn directions(&self) -> [(&RelationEnd, &RelationEnd); 2] {
+        [(&self.ends[0], &self.ends[1]), (&self.ends[1], &self.ends[0])]
+    }
+
+    pub fn uses_uuids(&self) -> bool {
+        self.ends.iter().any(|end| end.attribute_type == AttributeType::Uuid)
+    }
+}
+
+impl TryFrom<(String, RelationName, RawRelation, &[Entity])> for Relation {
+    type Error = ValidationErrors;
+
+    fn try_from((relation_path, relation_name, raw_relation, entities): (String, RelationName, RawRelation, &[Entity])) -> Result<Self, Self::Error> {
+        let entity_names = match raw_relation.as_array() {
+            Some(raw_entity_names) if raw_entity_names.len() == 2 => raw_entity_names.iter().filter_map(Value::as_str).collect::<Vec<&str>>(),
+            _ => vec![],
+        };
+        if entity_names.len() != 2 {
+            return Err(vec![ValidationError::for_entity(&relation_name, relation_path, ValidationErrorKind::MalformedRelation)]);
+        }
+        if entity_names[0] == entity_names[1] {
+            return Err(vec![ValidationError::for_entity(&relation_name, relation_path, ValidationErrorKind::SelfRelation(entity_names[0].to_string()))]);
+        }
+        let mut errors = Vec::new();
+        let mut ends = Vec::new();
+        for (entity_index, entity_name) in entity_names.into_iter().enumerate() {
+            let entity_path = pointer(&relation_path, entity_index);
+            match entities.iter().find(|entity| entity.name == entity_name) {
+                Some(entity) if entity.has_composite_primary_key() => {
+                    errors.push(ValidationError::for_entity(&relation_name, entity_path, ValidationErrorKind::RelationWithCompositePrimaryKey(entity_name.to_string())));
+                },
+                Some(entity) => ends.push(RelationEnd::new(entity)),
+                None => errors.push(ValidationError::for_entity(&relation_name, entity_path, ValidationErrorKind::UnknownRelationEntity(entity_name.to_string()))),
+            }
+        }
+        let Ok(ends) = <[RelationEnd; 2]>::try_from(ends) else {
+            return Err(errors);
+        };
+        Ok(Relation {
+            plural_name: to_plural(&relation_name),
+            name: relation_name,
+            ends,
+        })
+    }
+}
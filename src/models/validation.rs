@@ -0,0 +1,279 @@
+use std::fmt::{Display, Formatter};
+
+use super::entity::{AttributeName, EntityName, MAX_DECIMAL_PRECISION, MAX_VARCHAR_LENGTH, SEARCH_COLUMN};
+
+/**
+ * A problem found while validating a domain driven request.
+ * `path` is a JSON pointer (RFC 6901) to the offending value in the request,
+ * which also applies to YAML and TOML requests since they share the same structure.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub entity: Option<EntityName>,
+    pub attribute: Option<AttributeName>,
+    pub path: String,
+    pub kind: ValidationErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationErrorKind {
+    EntitiesNotAnArray,
+    MalformedEntity,
+    MalformedSemantics,
+    InvariantPlural(EntityName),
+    MalformedAttribute,
+    UnknownAttributeType(String),
+    InvalidDecimalPrecision(u32, u32),
+    InvalidStringLength(u32),
+    UnknownDefaultAttribute(AttributeName),
+    InvalidDefault(String, String),
+    UnknownForeignKeyEntity(EntityName),
+    UnknownForeignKeyAttribute(EntityName, AttributeName),
+    InvalidForeignKeyTarget(EntityName, AttributeName),
+    ForeignKeyTargetNotUnique(EntityName, AttributeName),
+    NotAForeignKey(AttributeName),
+    InvalidReferentialAction(String),
+    SetNullOnRequiredAttribute(AttributeName),
+    MalformedReservedKeyword(&'static str),
+    MissingPrimaryKey(AttributeName),
+    InvalidPrimaryKeyType(AttributeName, String),
+    UnknownUniqueAttribute(AttributeName),
+    UniqueContainerAttribute(AttributeName),
+    UnknownFilterByAttribute(AttributeName),
+    UnknownRangeFilterAttribute(AttributeName),
+    RangeFilterNotOrderable(AttributeName, String),
+    InvalidMatchMode(String),
+    MatchFilterNotString(AttributeName, String),
+    UnknownSortableAttribute(AttributeName),
+    SortableContainerAttribute(AttributeName),
+    UnknownSearchableAttribute(AttributeName),
+    SearchableNotString(AttributeName, String),
+    SearchColumnTaken,
+    UnknownCursorAttribute(AttributeName),
+    InvalidCursorAttribute(AttributeName, String),
+    SortableWithCursor,
+    SearchableWithCursor,
+    UniqueAttributesNotInFilterBy(Vec<AttributeName>),
+    UniqueAttributesInFineGrainedFilter(Vec<AttributeName>),
+    EnumsNotAnArray,
+    MalformedEnum,
+    InvalidEnumValue(String),
+    DuplicateEnumValue(String),
+    RelationsNotAnArray,
+    MalformedRelation,
+    UnknownRelationEntity(EntityName),
+    SelfRelation(EntityName),
+    RelationWithCompositePrimaryKey(EntityName),
+    DuplicateRelation(EntityName, EntityName),
+    RelationNameTaken(String),
+    RelationRouteTaken(String),
+}
+
+pub type ValidationErrors = Vec<ValidationError>;
+
+impl ValidationError {
+    pub fn new(path: String, kind: ValidationErrorKind) -> Self {
+        ValidationError {
+            entity: None,
+            attribute: None,
+            path,
+            kind,
+        }
+    }
+
+    pub fn for_entity(entity: &str, path: String, kind: ValidationErrorKind) -> Self {
+        ValidationError {
+            entity: Some(entity.to_string()),
+            attribute: None,
+            path,
+            kind,
+        }
+    }
+
+    pub fn for_attribute(entity: &str, attribute: &str, path: String, kind: ValidationErrorKind) -> Self {
+        ValidationError {
+            entity: Some(entity.to_string()),
+            attribute: Some(attribute.to_string()),
+            path,
+            kind,
+        }
+    }
+}
+
+/**
+ * Appends a reference token to a JSON pointer, escaping `~` and `/` as required by RFC 6901.
+ */
+pub fn pointer(path: &str, token: impl Display) -> String {
+    format!("{}/{}", path, token.to_string().replace('~', "~0").replace('/', "~1"))
+}
+
+impl Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationErrorKind::EntitiesNotAnArray => write!(f, "Entities must be an array"),
+            ValidationErrorKind::MalformedEntity => write!(f, "An entity must be an object mapping attribute names to their types"),
+            ValidationErrorKind::MalformedSemantics => write!(f, "The semantics of the entity are not correctly formatted, expected an object with a `plural` name"),
+            ValidationErrorKind::InvariantPlural(entity) => write!(f, "{entity} is spelled the same in the singular and the plural, provide a different plural explicitly in `semantics`, such as `{{\"{entity}\": {{\"plural\": \"{entity}Items\"}}}}`"),
+            ValidationErrorKind::MalformedAttribute => write!(f, "The type of an attribute must be a string"),
+            ValidationErrorKind::UnknownAttributeType(attribute_type) => write!(f, "Unknown attribute type {attribute_type}"),
+            ValidationErrorKind::InvalidDecimalPrecision(precision, scale) => write!(f, "Decimal({precision},{scale}) is invalid: the precision must be between 1 and {MAX_DECIMAL_PRECISION}, and the scale cannot exceed the precision"),
+            ValidationErrorKind::InvalidStringLength(max_length) => write!(f, "String({max_length}) is invalid: the length must be between 1 and {MAX_VARCHAR_LENGTH}"),
+            ValidationErrorKind::UnknownDefaultAttribute(attribute) => write!(f, "Default attribute {attribute} is not present in the attributes"),
+            ValidationErrorKind::InvalidDefault(value, attribute_type) => write!(f, "{value} is not a supported default value for {attribute_type}"),
+            ValidationErrorKind::UnknownForeignKeyEntity(entity) => write!(f, "Foreign key entity {entity} is not present in the entities"),
+            ValidationErrorKind::UnknownForeignKeyAttribute(entity, attribute) => write!(f, "Foreign key attribute {attribute} is not present in the attributes of {entity}"),
+            ValidationErrorKind::InvalidForeignKeyTarget(entity, attribute) => write!(f, "Foreign key {entity}.{attribute} does not reference an attribute with a known type"),
+            ValidationErrorKind::ForeignKeyTargetNotUnique(entity, attribute) => write!(f, "Foreign key {entity}.{attribute} must reference the primary key or a unique attribute of {entity}"),
+            ValidationErrorKind::NotAForeignKey(attribute) => write!(f, "{attribute} is not a foreign key"),
+            ValidationErrorKind::InvalidReferentialAction(action) => write!(f, "Unknown referential action {action}, expected `cascade`, `restrict` or `set null`"),
+            ValidationErrorKind::SetNullOnRequiredAttribute(attribute) => write!(f, "{attribute} cannot be set to null, since it is not an Option"),
+            ValidationErrorKind::MalformedReservedKeyword(keyword) => write!(f, "`{keyword}` is not correctly formatted"),
+            ValidationErrorKind::MissingPrimaryKey(primary_key) => write!(f, "Primary key {primary_key} is not present in the attributes"),
+            ValidationErrorKind::InvalidPrimaryKeyType(primary_key, attribute_type) => write!(f, "Primary key {primary_key} cannot be of type {attribute_type}"),
+            ValidationErrorKind::UnknownUniqueAttribute(attribute) => write!(f, "Unique attribute {attribute} is not present in the attributes"),
+            ValidationErrorKind::UniqueContainerAttribute(attribute) => write!(f, "Array or JSON attribute {attribute} cannot be unique, since filtering on it matches every row that contains the value"),
+            ValidationErrorKind::UnknownFilterByAttribute(attribute) => write!(f, "Filter by attribute {attribute} is not present in the attributes"),
+            ValidationErrorKind::UnknownRangeFilterAttribute(attribute) => write!(f, "Range filter attribute {attribute} is not present in the attributes"),
+            ValidationErrorKind::RangeFilterNotOrderable(attribute, attribute_type) => write!(f, "{attribute} of type {attribute_type} cannot be filtered on a range, only numbers, dates and times can"),
+            ValidationErrorKind::InvalidMatchMode(mode) => write!(f, "Invalid match mode {mode}, expected prefix, contains or icase"),
+            ValidationErrorKind::MatchFilterNotString(attribute, attribute_type) => write!(f, "{attribute} of type {attribute_type} cannot be matched on a pattern, only strings can"),
+            ValidationErrorKind::UnknownSortableAttribute(attribute) => write!(f, "Sortable attribute {attribute} is not present in the attributes"),
+            ValidationErrorKind::SortableContainerAttribute(attribute) => write!(f, "Array or JSON attribute {attribute} cannot be sortable"),
+            ValidationErrorKind::UnknownSearchableAttribute(attribute) => write!(f, "Searchable attribute {attribute} is not present in the attributes"),
+            ValidationErrorKind::SearchableNotString(attribute, attribute_type) => write!(f, "{attribute} of type {attribute_type} cannot be searchable, only strings can"),
+            ValidationErrorKind::SearchColumnTaken => write!(f, "{SEARCH_COLUMN} is the generated column of the searchable attributes, it cannot be an attribute"),
+            ValidationErrorKind::UnknownCursorAttribute(attribute) => write!(f, "Cursor attribute {attribute} is not present in the attributes"),
+            ValidationErrorKind::InvalidCursorAttribute(attribute, attribute_type) => write!(f, "{attribute} of type {attribute_type} cannot order the cursors, it must be required and cannot be an array, JSON or an interval"),
+            ValidationErrorKind::SortableWithCursor => write!(f, "Entities listed with cursors are always ordered by their cursor attribute, they cannot be sortable"),
+            ValidationErrorKind::SearchableWithCursor => write!(f, "Entities listed with cursors are always ordered by their cursor attribute, they cannot be searchable, since search results are ranked"),
+            ValidationErrorKind::UniqueAttributesNotInFilterBy(attributes) => write!(f, "Unique attributes {attributes:?} are not present in the filter_by"),
+            ValidationErrorKind::UniqueAttributesInFineGrainedFilter(attributes) => write!(f, "Unique attributes {attributes:?} are a sub attribute of another filter_by. It does not make sense to have fine grained filters on unique attributes, since they're unique"),
+            ValidationErrorKind::EnumsNotAnArray => write!(f, "Enums must be an array"),
+            ValidationErrorKind::MalformedEnum => write!(f, "An enum must be a non-empty array of string values"),
+            ValidationErrorKind::RelationsNotAnArray => write!(f, "Relations must be an array"),
+            ValidationErrorKind::MalformedRelation => write!(f, "A relation must be an array of the two entities it relates"),
+            ValidationErrorKind::UnknownRelationEntity(entity) => write!(f, "Related entity {entity} is not present in the entities"),
+            ValidationErrorKind::SelfRelation(entity) => write!(f, "{entity} cannot be related to itself"),
+            ValidationErrorKind::RelationWithCompositePrimaryKey(entity) => write!(f, "{entity} has a composite primary key, only entities with a single primary key can be related"),
+            ValidationErrorKind::DuplicateRelation(entity, other_entity) => write!(f, "{entity} and {other_entity} are already related"),
+            ValidationErrorKind::RelationNameTaken(table) => write!(f, "The table {table} of the relation is already used by an entity or another relation"),
+            ValidationErrorKind::RelationRouteTaken(route) => write!(f, "The route {route} of the relation is already used by a foreign key"),
+            ValidationErrorKind::InvalidEnumValue(value) => write!(f, "Enum value {value} must start with a letter and only contain letters, digits, `_` or `-`"),
+            ValidationErrorKind::DuplicateEnumValue(value) => write!(f, "Enum value {value} is declared more than once"),
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let location = match (&self.entity, &self.attribute) {
+            (Some(entity), Some(attribute)) => format!(" ({entity}.{attribute})"),
+            (Some(entity), None) => format!(" ({entity})"),
+            _ => String::new(),
+        };
+        write!(f, "{}{}: {}", self.path, location, self.kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{ddr_format::DdrFormat, ddr_req::DomainDrivenRequest};
+
+    use super::{pointer, ValidationErrorKind, ValidationErrors};
+
+    fn validate(json: &str) -> ValidationErrors {
+        let raw_ddr = DdrFormat::Json.parse(json).unwrap();
+        DomainDrivenRequest::try_from(raw_ddr).err().unwrap()
+    }
+
+    #[test]
+    fn escapes_the_reference_tokens() {
+        assert_eq!(pointer("/entities/0/Item", "sortable"), "/entities/0/Item/sortable");
+        assert_eq!(pointer("", "a/b~c"), "/a~1b~0c");
+    }
+
+    #[test]
+    fn points_at_the_entities_when_they_are_not_an_array() {
+        let errors = validate(r#"{"service_name": "shop", "entities": {}}"#);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/entities");
+        assert_eq!(errors[0].kind, ValidationErrorKind::EntitiesNotAnArray);
+    }
+
+    #[test]
+    fn points_at_the_offending_values_of_an_entity() {
+        let errors = validate(
+            r#"{
+                "service_name": "shop",
+                "entities": [
+                    {"Tag": {"id": "Uuid", "primary_key": "id"}},
+                    {"Item": {"id": "Uuid", "price": "Money", "name": "String", "sortable": ["name", "weight"], "primary_key": "id"}}
+                ]
+            }"#,
+        );
+        let attribute_type = errors.iter().find(|error| error.kind == ValidationErrorKind::UnknownAttributeType("Money".to_string())).unwrap();
+        assert_eq!(attribute_type.path, "/entities/1/Item/price");
+        assert_eq!(attribute_type.entity.as_deref(), Some("Item"));
+        assert_eq!(attribute_type.attribute.as_deref(), Some("price"));
+        let sortable = errors.iter().find(|error| error.kind == ValidationErrorKind::UnknownSortableAttribute("weight".to_string())).unwrap();
+        assert_eq!(sortable.path, "/entities/1/Item/sortable/1");
+    }
+
+    #[test]
+    fn rejects_an_entity_whose_plural_is_its_name() {
+        let errors = validate(r#"{"service_name": "farm", "entities": [{"Sheep": {"id": "Uuid"}}]}"#);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/entities/0/Sheep");
+        assert_eq!(errors[0].kind, ValidationErrorKind::InvariantPlural("Sheep".to_string()));
+        assert!(errors[0].to_string().contains("semantics"));
+
+        let errors = validate(r#"{"service_name": "farm", "entities": [{"Fish": {"id": "Uuid"}}], "semantics": [{"Fish": {"plural": "fish"}}]}"#);
+        assert_eq!(errors[0].path, "/semantics/0/Fish/plural");
+        assert_eq!(errors[0].kind, ValidationErrorKind::InvariantPlural("Fish".to_string()));
+
+        let raw_ddr = DdrFormat::Json.parse(r#"{"service_name": "farm", "entities": [{"Sheep": {"id": "Uuid"}}], "semantics": [{"Sheep": {"plural": "Flocks"}}]}"#).unwrap();
+        assert_eq!(DomainDrivenRequest::try_from(raw_ddr).unwrap().entities[0].plural_name, "Flocks");
+    }
+
+    #[test]
+    fn rejects_a_decimal_whose_scale_exceeds_its_precision() {
+        let errors = validate(r#"{"service_name": "shop", "entities": [{"Invoice": {"id": "Uuid", "total": "Decimal(2,3)", "rate": "Decimal(29,2)"}}]}"#);
+        assert_eq!(errors.len(), 2);
+        let total = errors.iter().find(|error| error.attribute.as_deref() == Some("total")).unwrap();
+        assert_eq!(total.path, "/entities/0/Invoice/total");
+        assert_eq!(total.kind, ValidationErrorKind::InvalidDecimalPrecision(2, 3));
+        let rate = errors.iter().find(|error| error.attribute.as_deref() == Some("rate")).unwrap();
+        assert_eq!(rate.kind, ValidationErrorKind::InvalidDecimalPrecision(29, 2));
+    }
+
+    #[test]
+    fn rejects_a_default_that_does_not_fit_the_attribute() {
+        let errors = validate(r#"{"service_name": "shop", "entities": [{"Product": {"id": "Uuid", "stock": "i32", "code": "String(2)", "defaults": {"stock": 3000000000, "code": "abc"}}}]}"#);
+        assert_eq!(errors.len(), 2);
+        let stock = errors.iter().find(|error| error.attribute.as_deref() == Some("stock")).unwrap();
+        assert_eq!(stock.path, "/entities/0/Product/defaults/stock");
+        assert_eq!(stock.kind, ValidationErrorKind::InvalidDefault("3000000000".to_string(), "i32".to_string()));
+        let code = errors.iter().find(|error| error.attribute.as_deref() == Some("code")).unwrap();
+        assert_eq!(code.kind, ValidationErrorKind::InvalidDefault(r#""abc""#.to_string(), "String".to_string()));
+    }
+
+    #[test]
+    fn rejects_set_null_on_a_required_foreign_key() {
+        let errors = validate(
+            r#"{
+                "service_name": "shop",
+                "entities": [
+                    {"Car": {"id": "Uuid", "owned_by": "User.id", "name": "String", "on_delete": {"owned_by": "set null", "name": "cascade"}, "on_update": {"owned_by": "nullify"}}},
+                    {"User": {"id": "Uuid"}}
+                ]
+            }"#,
+        );
+        assert_eq!(errors.len(), 3);
+        let set_null = errors.iter().find(|error| error.kind == ValidationErrorKind::SetNullOnRequiredAttribute("owned_by".to_string())).unwrap();
+        assert_eq!(set_null.path, "/entities/0/Car/on_delete/owned_by");
+        let not_a_foreign_key = errors.iter().find(|error| error.kind == ValidationErrorKind::NotAForeignKey("name".to_string())).unwrap();
+        assert_eq!(not_a_foreign_key.path, "/entities/0/Car/on_delete/name");
+        let unknown_action = errors.iter().find(|error| error.kind == ValidationErrorKind::InvalidReferentialAction(r#""nullify""#.to_string())).unwrap();
+        assert_eq!(unknown_action.path, "/entities/0/Car/on_update/owned_by");
+    }
+}
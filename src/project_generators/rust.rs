@@ -61,6 +61,13 @@ impl RustMicroserviceGenerator for RustMicroserviceGeneratorImpl {
          */
         let migrations_dynamic_template = domain_driven_request.generate_postgres_tables();
         let mut counter = 0;
+        // The enum types are used by the tables, so they need to be created first
+        if !domain_driven_request.enums.is_empty() {
+            let timestamp = Utc::now().format("%Y%m%d%H%M%S").to_string().parse::<i64>().unwrap() + counter;
+            let migration_path = format!("{}_enums.sql", timestamp);
+            self.generate_file(String::new(), domain_driven_request.generate_postgres_enums(), &format!("{}/{}/{}", out_dir, MIGRATIONS_DIR, migration_path))?;
+            counter += 1;
+        }
         for (entity, migration) in migrations_dynamic_template {
             let timestamp = Utc::now().format("%Y%m%d%H%M%S").to_string().parse::<i64>().unwrap() + counter;
             
@@ -68,6 +75,18 @@ impl RustMicroserviceGenerator for RustMicroserviceGeneratorImpl {
             self.generate_file(String::new(), migration, &format!("{}/{}/{}", out_dir, MIGRATIONS_DIR, migration_path))?;
             counter += 1;
         }
+        for (relation, migration) in domain_driven_request.generate_postgres_relation_tables() {
+            let timestamp = Utc::now().format("%Y%m%d%H%M%S").to_string().parse::<i64>().unwrap() + counter;
+            let migration_path = format!("{}_{}.sql", timestamp, to_snake_case(&relation.plural_name));
+            self.generate_file(String::new(), migration, &format!("{}/{}/{}", out_dir, MIGRATIONS_DIR, migration_path))?;
+            counter += 1;
+        }
+        // The foreign keys reference the tables, so they are created last
+        if domain_driven_request.uses_foreign_keys() {
+            let timestamp = Utc::now().format("%Y%m%d%H%M%S").to_string().parse::<i64>().unwrap() + counter;
+            let migration_path = format!("{}_foreign_keys.sql", timestamp);
+            self.generate_file(String::new(), domain_driven_request.generate_postgres_foreign_keys(), &format!("{}/{}/{}", out_dir, MIGRATIONS_DIR, migration_path))?;
+        }
 
         /*
          * Generate controllers
@@ -84,6 +103,10 @@ impl RustMicroserviceGenerator for RustMicroserviceGeneratorImpl {
             let controller_path = format!("{}_controller.rs", to_snake_case(&entity.plural_name));
             self.generate_file(String::new(), controller, &format!("{}/{}/{}", out_dir, CONTROLLERS_DIR, controller_path))?;
         }
+        for (relation, controller) in domain_driven_request.generate_relation_controllers() {
+            let controller_path = format!("{}_controller.rs", to_snake_case(&relation.plural_name));
+            self.generate_file(String::new(), controller, &format!("{}/{}/{}", out_dir, CONTROLLERS_DIR, controller_path))?;
+        }
 
         /*
          * Generate the endpoints
@@ -110,6 +133,16 @@ impl RustMicroserviceGenerator for RustMicroserviceGeneratorImpl {
         let models_mod_static_template = std::fs::read_to_string(models_mod_static_template_path)?;
         let models_mods_dynamic_template = domain_driven_request.generate_model_mods();
         self.generate_file(models_mod_static_template, models_mods_dynamic_template, &format!("{}/{}/mod.rs", out_dir, MODELS_DIR))?;
+        if !domain_driven_request.enums.is_empty() {
+            self.generate_file(String::new(), domain_driven_request.generate_enums_file(), &format!("{}/{}/enums.rs", out_dir, MODELS_DIR))?;
+        }
+        if domain_driven_request.uses_intervals() {
+            let interval_static_template = std::fs::read_to_string(rust_static_template_path.join("src/models/interval.rs"))?;
+            self.generate_file(interval_static_template, String::new(), &format!("{}/{}/interval.rs", out_dir, MODELS_DIR))?;
+        }
+        if !domain_driven_request.get_json_type_names().is_empty() {
+            self.generate_file(String::new(), domain_driven_request.generate_json_types_file(), &format!("{}/{}/json_types.rs", out_dir, MODELS_DIR))?;
+        }
         let models_dynamic_templates = domain_driven_request.generate_models();
         for (entity, model) in models_dynamic_templates {
             let model_path = format!("{}.rs", to_snake_case(&entity.name));
@@ -126,6 +159,10 @@ impl RustMicroserviceGenerator for RustMicroserviceGeneratorImpl {
             let service_path = format!("{}_service.rs", to_snake_case(&entity.plural_name));
             self.generate_file(String::new(), service, &format!("{}/{}/{}", out_dir, SERVICES_DIR, service_path))?;
         }
+        for (relation, service) in domain_driven_request.generate_relation_services() {
+            let service_path = format!("{}_service.rs", to_snake_case(&relation.plural_name));
+            self.generate_file(String::new(), service, &format!("{}/{}/{}", out_dir, SERVICES_DIR, service_path))?;
+        }
 
         /*
          * Generate sources
@@ -137,6 +174,10 @@ impl RustMicroserviceGenerator for RustMicroserviceGeneratorImpl {
             let source_path = format!("{}_table.rs", to_snake_case(&entity.plural_name));
             self.generate_file(String::new(), source, &format!("{}/{}/{}", out_dir, SOURCES_DIR, source_path))?;
         }
+        for (relation, source) in domain_driven_request.generate_relation_sources() {
+            let source_path = format!("{}_table.rs", to_snake_case(&relation.plural_name));
+            self.generate_file(String::new(), source, &format!("{}/{}/{}", out_dir, SOURCES_DIR, source_path))?;
+        }
 
         /*
          * Generate errors
@@ -1,27 +1,33 @@
-use crate::{models::entity::{Entity, FilterBy}, utils::naming_convention::to_snake_case};
+use crate::{models::{entity::{Entity, FilterBy, MatchFilter, MatchMode, PostgresAttributeType, SortKey, SEARCH_COLUMN, SEARCH_CONFIGURATION}, relation::{Relation, RelationEnd}}, utils::naming_convention::to_snake_case};
 
 pub static CREATE_ENTITY_QUERY: &str = r#"
             INSERT INTO {sc_plural_entity}
                 ({entity_fields})
             VALUES
                 ({entity_values})
-            RETURNING *;
+            RETURNING {selected_columns};
 "#;
 
 pub static GET_ENTITY_QUERY: &str = r#"
-            SELECT * FROM {sc_plural_entity}
-            WHERE {primary_key} = {entity_id};
+            SELECT {selected_columns} FROM {sc_plural_entity}
+            WHERE {primary_key_filter};
+"#;
+
+pub static GET_BY_ATTRIBUTE_IN_QUERY: &str = r#"
+            SELECT {selected_columns} FROM {sc_plural_entity}
+            WHERE {attribute_name} = ANY($1);
 "#;
 
 pub static FILTER_BY_QUERY: &str = r#"
-            SELECT * FROM {sc_plural_entity}
+            SELECT {selected_columns} FROM {sc_plural_entity}
             WHERE {filter_by_fields};
 "#;
 
 
 pub static FILTER_BY_PAGINATED_QUERY: &str = r#"
-            SELECT * FROM {sc_plural_entity}
+            SELECT {selected_columns} FROM {sc_plural_entity}
             WHERE {filter_by_fields}
+            ORDER BY {order_by}
             LIMIT {limit} OFFSET {offset};
 "#;
 
@@ -32,11 +38,63 @@ pub static FILTER_BY_PAGINATED_COUNT_QUERY: &str = r#"
 
 pub static FILTER_BY_FIELD: &str = r#"{field_name} = ${arg_num}"#;
 
+// The value is cast to the type of the column, otherwise it is bound as a `TEXT[]` that cannot be compared with a `VARCHAR[]` or an enum array
+pub static FILTER_BY_ARRAY_FIELD: &str = r#"{field_name} @> ARRAY[${arg_num}]::{column_type}"#;
+
+pub static FILTER_BY_JSON_FIELD: &str = r#"{field_name} @> ${arg_num}::TEXT::JSONB"#;
+
+// The value of a match filter is escaped, so that the `%`, `_` and `\` it contains are matched literally
+pub static MATCH_VALUE: &str = r#"replace(replace(replace(${arg_num}, '\', '\\'), '%', '\%'), '_', '\_')"#;
+
+pub static FILTER_BY_PREFIX_FIELD: &str = r#"{field_name} ILIKE {match_value} || '%'"#;
+
+pub static FILTER_BY_CONTAINS_FIELD: &str = r#"{field_name} ILIKE '%' || {match_value} || '%'"#;
+
+pub static FILTER_BY_ICASE_FIELD: &str = r#"{field_name} ILIKE {match_value}"#;
+
+// A missing bound matches every row. The bound is cast, since Postgres cannot infer its type from `IS NULL`
+pub static FILTER_BY_MIN_FIELD: &str = r#"(${arg_num}::{field_type} IS NULL OR {field_name} >= ${arg_num})"#;
+
+pub static FILTER_BY_MAX_FIELD: &str = r#"(${arg_num}::{field_type} IS NULL OR {field_name} <= ${arg_num})"#;
+
+pub static SELECTED_COLUMN_TYPE_OVERRIDE: &str = r#"{field_name} AS "{field_name}: {field_type}""#;
+
 pub static GET_PAGINATED_QUERY: &str = r#"
-            SELECT * FROM {sc_plural_entity}
+            SELECT {selected_columns} FROM {sc_plural_entity}
+            ORDER BY {order_by}
             LIMIT {limit} OFFSET {offset};
 "#;
 
+// Keyset pagination: the first page has its own query, the next pages start after the keys of the last entity of the previous page,
+// which seeks into the index of the cursor attributes
+pub static GET_FIRST_CURSOR_PAGE_QUERY: &str = r#"
+            SELECT {selected_columns} FROM {sc_plural_entity}
+            ORDER BY {cursor_attributes}
+            LIMIT {limit};
+"#;
+
+pub static FILTER_BY_CURSOR_PAGE_QUERY: &str = r#"
+            SELECT {selected_columns} FROM {sc_plural_entity}
+            WHERE {filter_by_fields}
+            ORDER BY {cursor_attributes}
+            LIMIT {limit};
+"#;
+
+pub static AFTER_CURSOR_FIELD: &str = r#"({cursor_attributes}) > ({cursor_values})"#;
+
+// The best matches come first, ties are broken by the primary key so that pages are deterministic
+pub static SEARCH_PAGINATED_QUERY: &str = r#"
+            SELECT {selected_columns} FROM {sc_plural_entity}
+            WHERE {search_column} @@ websearch_to_tsquery('{search_configuration}', $1)
+            ORDER BY ts_rank({search_column}, websearch_to_tsquery('{search_configuration}', $1)) DESC, {primary_key}
+            LIMIT $2 OFFSET $3;
+"#;
+
+pub static SEARCH_COUNT_QUERY: &str = r#"
+            SELECT COUNT(*) FROM {sc_plural_entity}
+            WHERE {search_column} @@ websearch_to_tsquery('{search_configuration}', $1);
+"#;
+
 pub static COUNT_ENTITY_QUERY: &str = r#"
             SELECT COUNT(*) FROM {sc_plural_entity};
 "#;
@@ -45,27 +103,113 @@ pub static UPDATE_ENTITY_QUERY: &str = r#"
             UPDATE {sc_plural_entity}
             SET 
                 {entity_fields}
-            WHERE {primary_key} = {entity_id}
-            RETURNING *;
+            WHERE {primary_key_filter}
+            RETURNING {selected_columns};
 "#;
 
 pub static DELETE_ENTITY_QUERY: &str = r#"
             DELETE FROM {sc_plural_entity}
-            WHERE {primary_key} = {entity_id};
+            WHERE {primary_key_filter};
+"#;
+
+// Linking twice is not an error, the link already exists
+pub static LINK_QUERY: &str = r#"
+            INSERT INTO {sc_plural_relation}
+                ({attribute_names})
+            VALUES
+                ($1, $2)
+            ON CONFLICT DO NOTHING;
+"#;
+
+pub static UNLINK_QUERY: &str = r#"
+            DELETE FROM {sc_plural_relation}
+            WHERE {attribute_name} = $1 AND {other_attribute_name} = $2;
+"#;
+
+pub static GET_RELATED_PAGINATED_QUERY: &str = r#"
+            SELECT {selected_columns} FROM {sc_related_plural_entity}
+            WHERE {related_primary_key} IN (
+                SELECT {related_attribute_name} FROM {sc_plural_relation}
+                WHERE {attribute_name} = $1
+            )
+            ORDER BY {related_primary_key}
+            LIMIT $2 OFFSET $3;
+"#;
+
+pub static COUNT_RELATED_QUERY: &str = r#"
+            SELECT COUNT(*) FROM {sc_plural_relation}
+            WHERE {attribute_name} = $1;
 "#;
 
 pub trait CrudQueryGenerator {
+    /**
+     * All columns are selected with `*`, unless sqlx needs a type override for some of them.
+     * In that case the columns are listed, using the `"column: Type"` override syntax of query_as!.
+     * The columns of searchable entities are listed as well, so that the search column is not selected.
+     */
+    fn generate_selected_columns(&self, entity: &Entity) -> String {
+        if entity.searchable.is_empty() && entity.attributes.iter().all(|(_, attribute_type)| attribute_type.sqlx_type_override().is_none()) {
+            return "*".to_string();
+        }
+        entity.attributes.iter().map(|(attribute_name, attribute_type)| {
+            match attribute_type.sqlx_type_override() {
+                Some(field_type) => SELECTED_COLUMN_TYPE_OVERRIDE
+                    .replace("{field_name}", attribute_name)
+                    .replace("{field_type}", &field_type),
+                None => attribute_name.to_string(),
+            }
+        }).collect::<Vec<String>>().join(", ")
+    }
+
+    /**
+     * Arrays and JSON attributes are filtered on the rows that contain the value, other attributes on equality.
+     */
+    fn generate_filter_by_field(&self, entity: &Entity, field_name: &str, arg_num: usize) -> String {
+        let template = match entity.attribute_type(field_name) {
+            Some(attribute_type) if attribute_type.is_array() => FILTER_BY_ARRAY_FIELD
+                .replace("{column_type}", &PostgresAttributeType::from(attribute_type).column_type()),
+            Some(attribute_type) if attribute_type.is_json() => FILTER_BY_JSON_FIELD.to_string(),
+            _ => FILTER_BY_FIELD.to_string(),
+        };
+        template
+            .replace("{field_name}", field_name)
+            .replace("{arg_num}", &arg_num.to_string())
+    }
+
+    /**
+     * Matches the primary key, whose attributes are bound starting at `first_arg_num`.
+     */
+    fn generate_primary_key_filter(&self, entity: &Entity, first_arg_num: usize) -> String {
+        entity.primary_key.iter().enumerate().map(|(index, primary_key)| {
+            FILTER_BY_FIELD
+                .replace("{field_name}", primary_key)
+                .replace("{arg_num}", &(first_arg_num + index).to_string())
+        }).collect::<Vec<String>>().join(" AND ")
+    }
+
+    /**
+     * Entities are ordered by their sort key, if any, then by their primary key, so that pages are deterministic.
+     */
+    fn generate_order_by(&self, entity: &Entity, sort_key: Option<&SortKey>) -> String {
+        let primary_key = entity.primary_key.join(", ");
+        match sort_key {
+            Some(sort_key) => format!("{}, {}", sort_key.order_by(), primary_key),
+            None => primary_key,
+        }
+    }
+
     fn generate_create_query(&self, entity: &Entity) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
         let mut entity_fields = Vec::new();
         let mut entity_values = Vec::new();
-        for (arg_num, (attribute_name, _)) in entity.attributes.iter().enumerate() {
+        for (arg_num, attribute_name) in entity.inserted_attributes().into_iter().enumerate() {
             entity_fields.push(format!("{}", attribute_name));
             entity_values.push(format!("${}", arg_num + 1));
         }
         let entity_fields = entity_fields.join(", ");
         let entity_values = entity_values.join(", ");
         CREATE_ENTITY_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
             .replace("{sc_plural_entity}", &sc_plural_entity)
             .replace("{entity_fields}", &entity_fields)
             .replace("{entity_values}", &entity_values)
@@ -73,34 +217,67 @@ pub trait CrudQueryGenerator {
 
     fn generate_get_query(&self, entity: &Entity) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
-        let primary_key = &entity.primary_key;
-        let entity_id = &format!("${}", 1);
         GET_ENTITY_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
             .replace("{sc_plural_entity}", &sc_plural_entity)
-            .replace("{primary_key}", &primary_key)
-            .replace("{entity_id}", &entity_id)
+            .replace("{primary_key_filter}", &self.generate_primary_key_filter(entity, 1))
     }
 
-    fn generate_get_paginated_query(&self, entity: &Entity) -> String {
+    fn generate_get_paginated_query(&self, entity: &Entity, sort_key: Option<&SortKey>) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
-        let limit = &format!("${}", 1);
-        let offset = &format!("${}", 2);
         GET_PAGINATED_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
             .replace("{sc_plural_entity}", &sc_plural_entity)
-            .replace("{limit}", &limit)
-            .replace("{offset}", &offset)
+            .replace("{order_by}", &self.generate_order_by(entity, sort_key))
+            .replace("{limit}", "$1")
+            .replace("{offset}", "$2")
+    }
+
+    /**
+     * A page of an entity listed with cursors, among the entities that match `filter_by_fields`, if any.
+     * The filter values are bound first, the cursor attributes after them when the page is after a cursor, and the limit last.
+     */
+    fn generate_cursor_page_query(&self, entity: &Entity, filter_by_fields: &str, filter_values: usize, after_cursor: bool) -> String {
+        let cursor_attributes = entity.cursor_attributes();
+        let cursor_attribute_names = cursor_attributes.iter().map(|(attribute_name, _)| attribute_name.as_str()).collect::<Vec<&str>>().join(", ");
+        let mut filter_by_fields = filter_by_fields.to_string();
+        let mut values = filter_values;
+        if after_cursor {
+            let cursor_values = (1..=cursor_attributes.len()).map(|index| format!("${}", filter_values + index)).collect::<Vec<String>>().join(", ");
+            let after_cursor_field = AFTER_CURSOR_FIELD
+                .replace("{cursor_attributes}", &cursor_attribute_names)
+                .replace("{cursor_values}", &cursor_values);
+            filter_by_fields = if filter_by_fields.is_empty() { after_cursor_field } else { format!("{} AND {}", filter_by_fields, after_cursor_field) };
+            values += cursor_attributes.len();
+        }
+        let template = if filter_by_fields.is_empty() { GET_FIRST_CURSOR_PAGE_QUERY } else { FILTER_BY_CURSOR_PAGE_QUERY };
+        template
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{filter_by_fields}", &filter_by_fields)
+            .replace("{cursor_attributes}", &cursor_attribute_names)
+            .replace("{limit}", &format!("${}", values + 1))
+    }
+
+    /**
+     * The values of the filter_by are bound as `$1, $2, ...`, in the order of its attributes.
+     */
+    fn generate_filter_by_fields(&self, entity: &Entity, filter_attr: &FilterBy) -> String {
+        filter_attr.iter().enumerate().map(|(arg_num, field_name)| {
+            self.generate_filter_by_field(entity, field_name, arg_num + 1)
+        }).collect::<Vec<String>>().join(" AND ")
     }
 
-    fn generate_filter_by_paginated_query(&self, entity: &Entity, filter_attr: &FilterBy) -> String {
+    fn generate_filter_by_paginated_query(&self, entity: &Entity, filter_attr: &FilterBy, sort_key: Option<&SortKey>) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
         let filter_by_fields = filter_attr.iter().enumerate().map(|(arg_num, field_name)| {
-            FILTER_BY_FIELD
-                .replace("{field_name}", &field_name)
-                .replace("{arg_num}", &format!("{}", arg_num + 1))
+            self.generate_filter_by_field(entity, field_name, arg_num + 1)
         }).collect::<Vec<String>>().join(" AND ");
         FILTER_BY_PAGINATED_QUERY
+                .replace("{selected_columns}", &self.generate_selected_columns(entity))
                 .replace("{sc_plural_entity}", &sc_plural_entity)
                 .replace("{filter_by_fields}", &filter_by_fields)
+                .replace("{order_by}", &self.generate_order_by(entity, sort_key))
                 .replace("{limit}", &format!("${}", filter_attr.len() + 1))
                 .replace("{offset}", &format!("${}", filter_attr.len() + 2))
     }
@@ -108,27 +285,113 @@ pub trait CrudQueryGenerator {
     fn generate_filter_by_paginated_count_query(&self, entity: &Entity, filter_attr: &FilterBy) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
         let filter_by_fields = filter_attr.iter().enumerate().map(|(arg_num, field_name)| {
-            FILTER_BY_FIELD
-                .replace("{field_name}", &field_name)
-                .replace("{arg_num}", &format!("{}", arg_num + 1))
+            self.generate_filter_by_field(entity, field_name, arg_num + 1)
         }).collect::<Vec<String>>().join(" AND ");
         FILTER_BY_PAGINATED_COUNT_QUERY
                 .replace("{sc_plural_entity}", &sc_plural_entity)
                 .replace("{filter_by_fields}", &filter_by_fields)
     }
 
+    /**
+     * The value of the match filter is bound as `$1`.
+     */
+    fn generate_match_filter_field(&self, match_filter: &MatchFilter) -> String {
+        let template = match match_filter.mode {
+            MatchMode::Prefix => FILTER_BY_PREFIX_FIELD,
+            MatchMode::Contains => FILTER_BY_CONTAINS_FIELD,
+            MatchMode::ICase => FILTER_BY_ICASE_FIELD,
+        };
+        template
+            .replace("{match_value}", &MATCH_VALUE.replace("{arg_num}", "1"))
+            .replace("{field_name}", &match_filter.attribute_name)
+    }
+
+    fn generate_match_filter_paginated_query(&self, entity: &Entity, match_filter: &MatchFilter, sort_key: Option<&SortKey>) -> String {
+        FILTER_BY_PAGINATED_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{filter_by_fields}", &self.generate_match_filter_field(match_filter))
+            .replace("{order_by}", &self.generate_order_by(entity, sort_key))
+            .replace("{limit}", "$2")
+            .replace("{offset}", "$3")
+    }
+
+    fn generate_match_filter_count_query(&self, entity: &Entity, match_filter: &MatchFilter) -> String {
+        FILTER_BY_PAGINATED_COUNT_QUERY
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{filter_by_fields}", &self.generate_match_filter_field(match_filter))
+    }
+
+    /**
+     * Every range attribute has an inclusive lower and upper bound, bound as `$1, $2` for the first attribute, `$3, $4` for the second one, and so on.
+     */
+    fn generate_filter_by_range_fields(&self, entity: &Entity) -> String {
+        entity.range_filter_attributes().iter().enumerate().flat_map(|(index, (attribute_name, attribute_type))| {
+            let postgres_attribute_type: PostgresAttributeType = (*attribute_type).into();
+            let field_type = postgres_attribute_type.column_type();
+            [(FILTER_BY_MIN_FIELD, 2 * index + 1), (FILTER_BY_MAX_FIELD, 2 * index + 2)].map(|(template, arg_num)| {
+                template
+                    .replace("{field_type}", &field_type)
+                    .replace("{field_name}", attribute_name)
+                    .replace("{arg_num}", &arg_num.to_string())
+            })
+        }).collect::<Vec<String>>().join(" AND ")
+    }
+
+    fn generate_filter_by_range_paginated_query(&self, entity: &Entity, sort_key: Option<&SortKey>) -> String {
+        let bounds = 2 * entity.range_filter_attributes().len();
+        FILTER_BY_PAGINATED_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{filter_by_fields}", &self.generate_filter_by_range_fields(entity))
+            .replace("{order_by}", &self.generate_order_by(entity, sort_key))
+            .replace("{limit}", &format!("${}", bounds + 1))
+            .replace("{offset}", &format!("${}", bounds + 2))
+    }
+
+    fn generate_filter_by_range_count_query(&self, entity: &Entity) -> String {
+        FILTER_BY_PAGINATED_COUNT_QUERY
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{filter_by_fields}", &self.generate_filter_by_range_fields(entity))
+    }
+
+    /**
+     * The search terms are bound as `$1` and parsed with the web search syntax, which never fails on user input.
+     */
+    fn generate_search_paginated_query(&self, entity: &Entity) -> String {
+        SEARCH_PAGINATED_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{search_column}", SEARCH_COLUMN)
+            .replace("{search_configuration}", SEARCH_CONFIGURATION)
+            .replace("{primary_key}", &entity.primary_key.join(", "))
+    }
+
+    fn generate_search_count_query(&self, entity: &Entity) -> String {
+        SEARCH_COUNT_QUERY
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{search_column}", SEARCH_COLUMN)
+            .replace("{search_configuration}", SEARCH_CONFIGURATION)
+    }
+
     fn generate_filter_by_query(&self, entity: &Entity, filter_attr: &FilterBy) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
         let filter_by_fields = filter_attr.iter().enumerate().map(|(arg_num, field_name)| {
-            FILTER_BY_FIELD
-                .replace("{field_name}", &field_name)
-                .replace("{arg_num}", &format!("{}", arg_num + 1))
+            self.generate_filter_by_field(entity, field_name, arg_num + 1)
         }).collect::<Vec<String>>().join(" AND ");
         FILTER_BY_QUERY
+                .replace("{selected_columns}", &self.generate_selected_columns(entity))
                 .replace("{sc_plural_entity}", &sc_plural_entity)
                 .replace("{filter_by_fields}", &filter_by_fields)
     }
 
+    fn generate_get_by_attribute_in_query(&self, entity: &Entity, attribute_name: &str) -> String {
+        GET_BY_ATTRIBUTE_IN_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{attribute_name}", attribute_name)
+    }
+
     fn generate_count_query(&self, entity: &Entity) -> String {
         COUNT_ENTITY_QUERY.replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
     }
@@ -136,21 +399,247 @@ pub trait CrudQueryGenerator {
     fn generate_update_query(&self, entity: &Entity) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
         let mut entity_fields = Vec::new();
-        for (arg_num, (attribute_name, _)) in entity.attributes.iter().enumerate() {
+        let inserted_attributes = entity.inserted_attributes();
+        for (arg_num, attribute_name) in inserted_attributes.iter().enumerate() {
             entity_fields.push(format!("{} = ${}", attribute_name, arg_num + 1 ));
         }
         let entity_fields = entity_fields.join(", ");
         UPDATE_ENTITY_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(entity))
             .replace("{sc_plural_entity}", &sc_plural_entity)
             .replace("{entity_fields}", &entity_fields)
-            .replace("{primary_key}", &entity.primary_key)
-            .replace("{entity_id}", &format!("${}", entity.attributes.len() + 1))
+            .replace("{primary_key_filter}", &self.generate_primary_key_filter(entity, inserted_attributes.len() + 1))
     }
     fn generate_delete_query(&self, entity: &Entity) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
         DELETE_ENTITY_QUERY
             .replace("{sc_plural_entity}", &sc_plural_entity)
-            .replace("{primary_key}", &&entity.primary_key)
-            .replace("{entity_id}", &format!("${}", 1))
+            .replace("{primary_key_filter}", &self.generate_primary_key_filter(entity, 1))
+    }
+
+    /**
+     * Links and unlinks bind the attributes in the order in which the entities are declared in the relation.
+     */
+    fn generate_link_query(&self, relation: &Relation) -> String {
+        let attribute_names = relation.ends.iter().map(|end| end.attribute_name.clone()).collect::<Vec<String>>().join(", ");
+        LINK_QUERY
+            .replace("{sc_plural_relation}", &to_snake_case(&relation.plural_name))
+            .replace("{attribute_names}", &attribute_names)
+    }
+
+    fn generate_unlink_query(&self, relation: &Relation) -> String {
+        UNLINK_QUERY
+            .replace("{sc_plural_relation}", &to_snake_case(&relation.plural_name))
+            .replace("{other_attribute_name}", &relation.ends[1].attribute_name)
+            .replace("{attribute_name}", &relation.ends[0].attribute_name)
+    }
+
+    /**
+     * The entities related to `end`, which is bound as `$1`.
+     */
+    fn generate_get_related_paginated_query(&self, relation: &Relation, end: &RelationEnd, related_end: &RelationEnd, related_entity: &Entity) -> String {
+        GET_RELATED_PAGINATED_QUERY
+            .replace("{selected_columns}", &self.generate_selected_columns(related_entity))
+            .replace("{sc_related_plural_entity}", &to_snake_case(&related_end.plural_name))
+            .replace("{related_primary_key}", &related_end.primary_key)
+            .replace("{related_attribute_name}", &related_end.attribute_name)
+            .replace("{sc_plural_relation}", &to_snake_case(&relation.plural_name))
+            .replace("{attribute_name}", &end.attribute_name)
+    }
+
+    fn generate_count_related_query(&self, relation: &Relation, end: &RelationEnd) -> String {
+        COUNT_RELATED_QUERY
+            .replace("{sc_plural_relation}", &to_snake_case(&relation.plural_name))
+            .replace("{attribute_name}", &end.attribute_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{ddr_format::DdrFormat, ddr_req::DomainDrivenRequest};
+
+    use super::CrudQueryGenerator;
+
+    fn request(ddr: &str) -> DomainDrivenRequest {
+        DomainDrivenRequest::try_from(DdrFormat::Yaml.parse(ddr).unwrap()).unwrap()
+    }
+
+    fn normalize(query: &str) -> String {
+        query.split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    #[test]
+    fn casts_the_value_of_an_array_filter() {
+        let ddr = request("
+service_name: shop
+enums:
+  - CarStatus: [available, sold]
+entities:
+  - Car:
+      id: Uuid
+      name: String
+      tags: Vec<String>
+      statuses: Vec<CarStatus>
+      filter_by: [name, tags, statuses]
+");
+        let car = &ddr.entities[0];
+        assert_eq!(ddr.generate_filter_by_field(car, "name", 1), "name = $1");
+        assert_eq!(ddr.generate_filter_by_field(car, "tags", 1), "tags @> ARRAY[$1]::VARCHAR(255)[]");
+        assert_eq!(ddr.generate_filter_by_field(car, "statuses", 2), "statuses @> ARRAY[$2]::car_status[]");
+    }
+
+    #[test]
+    fn orders_by_the_sort_key_then_the_primary_key() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Item:
+      id: Uuid
+      name: String
+      filter_by: [name]
+      sortable: [name]
+");
+        let item = &ddr.entities[0];
+        let sort_keys = item.sort_keys();
+        assert_eq!(sort_keys.iter().map(|sort_key| sort_key.param_value()).collect::<Vec<String>>(), ["name", "-name"]);
+        assert_eq!(ddr.generate_order_by(item, None), "id");
+        assert_eq!(ddr.generate_order_by(item, Some(&sort_keys[1])), "name DESC, id");
+        assert_eq!(
+            normalize(&ddr.generate_get_paginated_query(item, Some(&sort_keys[0]))),
+            "SELECT * FROM items ORDER BY name ASC, id LIMIT $1 OFFSET $2;"
+        );
+        assert_eq!(
+            normalize(&ddr.generate_filter_by_paginated_query(item, &item.filter_by[0], Some(&sort_keys[1]))),
+            "SELECT * FROM items WHERE name = $1 ORDER BY name DESC, id LIMIT $2 OFFSET $3;"
+        );
+    }
+
+    #[test]
+    fn seeks_the_next_cursor_page_after_the_cursor_attributes() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Event:
+      id: i64
+      name: String
+      happened_at: DateTime<Utc>
+      filter_by: [name]
+      cursor_by: happened_at
+");
+        let event = &ddr.entities[0];
+        assert_eq!(
+            normalize(&ddr.generate_cursor_page_query(event, "", 0, false)),
+            "SELECT * FROM events ORDER BY happened_at, id LIMIT $1;"
+        );
+        assert_eq!(
+            normalize(&ddr.generate_cursor_page_query(event, "", 0, true)),
+            "SELECT * FROM events WHERE (happened_at, id) > ($1, $2) ORDER BY happened_at, id LIMIT $3;"
+        );
+        let filter_by_fields = ddr.generate_filter_by_fields(event, &event.filter_by[0]);
+        assert_eq!(
+            normalize(&ddr.generate_cursor_page_query(event, &filter_by_fields, 1, false)),
+            "SELECT * FROM events WHERE name = $1 ORDER BY happened_at, id LIMIT $2;"
+        );
+        assert_eq!(
+            normalize(&ddr.generate_cursor_page_query(event, &filter_by_fields, 1, true)),
+            "SELECT * FROM events WHERE name = $1 AND (happened_at, id) > ($2, $3) ORDER BY happened_at, id LIMIT $4;"
+        );
+    }
+
+    #[test]
+    fn links_and_lists_the_entities_of_a_relation() {
+        let ddr = request("
+service_name: shop
+entities:
+  - User:
+      id: i64
+      name: String
+  - Group:
+      id: Uuid
+      name: String
+relations:
+  - Membership: [User, Group]
+");
+        let membership = &ddr.relations[0];
+        let [user_end, group_end] = &membership.ends;
+        assert_eq!(normalize(&ddr.generate_link_query(membership)), "INSERT INTO memberships (user_id, group_id) VALUES ($1, $2) ON CONFLICT DO NOTHING;");
+        assert_eq!(normalize(&ddr.generate_unlink_query(membership)), "DELETE FROM memberships WHERE user_id = $1 AND group_id = $2;");
+        assert_eq!(
+            normalize(&ddr.generate_get_related_paginated_query(membership, user_end, group_end, &ddr.entities[1])),
+            "SELECT * FROM groups WHERE id IN ( SELECT group_id FROM memberships WHERE user_id = $1 ) ORDER BY id LIMIT $2 OFFSET $3;"
+        );
+        assert_eq!(normalize(&ddr.generate_count_related_query(membership, group_end)), "SELECT COUNT(*) FROM memberships WHERE group_id = $1;");
+    }
+
+    #[test]
+    fn fetches_the_expanded_entities_of_a_page_at_once() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Car:
+      id: Uuid
+      owned_by: User.id
+      sold_by: Option<User.email>
+  - User:
+      id: Uuid
+      email: String
+      unique_attributes: [[email]]
+      filter_by: [email]
+");
+        let user = &ddr.entities[1];
+        assert_eq!(user.referenced_attributes(&ddr.entities), vec!["id", "email"]);
+        assert_eq!(normalize(&ddr.generate_get_by_attribute_in_query(user, "id")), "SELECT * FROM users WHERE id = ANY($1);");
+        assert_eq!(normalize(&ddr.generate_get_by_attribute_in_query(user, "email")), "SELECT * FROM users WHERE email = ANY($1);");
+    }
+
+    #[test]
+    fn ranks_the_search_results() {
+        let ddr = request("
+service_name: blog
+entities:
+  - Post:
+      id: Uuid
+      title: String
+      body: Text
+      searchable: [title, body]
+");
+        let post = &ddr.entities[0];
+        assert_eq!(
+            normalize(&ddr.generate_search_paginated_query(post)),
+            "SELECT body, id, title FROM posts WHERE search_vector @@ websearch_to_tsquery('simple', $1) ORDER BY ts_rank(search_vector, websearch_to_tsquery('simple', $1)) DESC, id LIMIT $2 OFFSET $3;"
+        );
+        assert_eq!(
+            normalize(&ddr.generate_search_count_query(post)),
+            "SELECT COUNT(*) FROM posts WHERE search_vector @@ websearch_to_tsquery('simple', $1);"
+        );
+    }
+
+    #[test]
+    fn matches_the_pattern_characters_of_a_match_filter_literally() {
+        let ddr = request("
+service_name: shop
+entities:
+  - User:
+      id: Uuid
+      name: String
+      city: String
+      email: String
+      filter_by: [{name: prefix, city: contains, email: icase}]
+");
+        let user = &ddr.entities[0];
+        let match_value = r#"replace(replace(replace($1, '\', '\\'), '%', '\%'), '_', '\_')"#;
+        let match_filter = |attribute_name: &str| user.match_filters.iter().find(|match_filter| match_filter.attribute_name == attribute_name).unwrap();
+        let (name, city, email) = (match_filter("name"), match_filter("city"), match_filter("email"));
+        assert_eq!(ddr.generate_match_filter_field(name), format!("name ILIKE {match_value} || '%'"));
+        assert_eq!(ddr.generate_match_filter_field(city), format!("city ILIKE '%' || {match_value} || '%'"));
+        assert_eq!(ddr.generate_match_filter_field(email), format!("email ILIKE {match_value}"));
+        assert_eq!(
+            normalize(&ddr.generate_match_filter_paginated_query(user, name, None)),
+            format!("SELECT * FROM users WHERE name ILIKE {match_value} || '%' ORDER BY id LIMIT $2 OFFSET $3;")
+        );
+        assert_eq!(
+            normalize(&ddr.generate_match_filter_count_query(user, city)),
+            format!("SELECT COUNT(*) FROM users WHERE city ILIKE '%' || {match_value} || '%';")
+        );
     }
-}
\ No newline at end of file
+}
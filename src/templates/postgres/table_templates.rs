@@ -1,4 +1,4 @@
-use crate::{models::entity::{Entity, PostgresAttributeType}, utils::naming_convention::to_snake_case};
+use crate::{models::{entity::{Entity, PostgresAttributeType, SEARCH_COLUMN, SEARCH_CONFIGURATION}, enum_type::EnumType, relation::Relation}, utils::naming_convention::to_snake_case};
 
 
 pub static SQL_TABLE_QUERY_TEMPLATE: &str = r#"
@@ -11,14 +11,81 @@ pub static SQL_INDEX_QUERY_TEMPLATE: &str = r#"
 CREATE INDEX IF NOT EXISTS {sc_plural_entity}_{attribute_name}_index ON {sc_plural_entity} ({attribute_names});
 "#;
 
+// Array and JSONB containment (@>) can only use a GIN index
+pub static SQL_GIN_INDEX_QUERY_TEMPLATE: &str = r#"
+CREATE INDEX IF NOT EXISTS {sc_plural_entity}_{attribute_name}_index ON {sc_plural_entity} USING GIN ({attribute_names});
+"#;
+
+// Range filters compare a single attribute, which a composite index starting with another attribute cannot serve
+pub static SQL_RANGE_INDEX_QUERY_TEMPLATE: &str = r#"
+CREATE INDEX IF NOT EXISTS {sc_plural_entity}_{attribute_name}_range_index ON {sc_plural_entity} ({attribute_name});
+"#;
+
+// ILIKE patterns can only use a trigram index, whether they are anchored or not
+pub static SQL_TRIGRAM_EXTENSION_QUERY: &str = r#"
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+"#;
+
+pub static SQL_TRIGRAM_INDEX_QUERY_TEMPLATE: &str = r#"
+CREATE INDEX IF NOT EXISTS {sc_plural_entity}_{attribute_name}_trigram_index ON {sc_plural_entity} USING GIN ({attribute_name} gin_trgm_ops);
+"#;
+
+// Serves the `(cursor_by, primary key) > (...)` comparison and the order of the pages
+pub static SQL_CURSOR_INDEX_QUERY_TEMPLATE: &str = r#"
+CREATE INDEX IF NOT EXISTS {sc_plural_entity}_{attribute_name}_cursor_index ON {sc_plural_entity} ({attribute_names});
+"#;
+
+// The searchable attributes are weighted in the order in which they are declared, from A to D.
+// The configuration is given explicitly, since generated columns can only use immutable expressions
+pub static SQL_SEARCH_COLUMN_TEMPLATE: &str = r#"
+    {search_column} TSVECTOR GENERATED ALWAYS AS ({search_document}) STORED,"#;
+
+pub static SQL_SEARCH_WEIGHT_TEMPLATE: &str = r#"setweight(to_tsvector('{search_configuration}', coalesce({attribute_name}, '')), '{weight}')"#;
+
+pub static SQL_SEARCH_INDEX_QUERY_TEMPLATE: &str = r#"
+CREATE INDEX IF NOT EXISTS {sc_plural_entity}_{search_column}_index ON {sc_plural_entity} USING GIN ({search_column});
+"#;
+
 pub static SQL_INDEX_QUERY_TEMPLATE_UNIQUE: &str = r#"
 CREATE UNIQUE INDEX IF NOT EXISTS unique_{attribute_name}_index ON {sc_plural_entity} ({attribute_names});
 "#;
 
+// Postgres has no CREATE TYPE IF NOT EXISTS
+pub static SQL_ENUM_TYPE_TEMPLATE: &str = r#"
+DO $$ BEGIN
+    CREATE TYPE {sc_enum_name} AS ENUM ({enum_values});
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+"#;
+
+pub static SQL_ENUM_VALUE_TEMPLATE: &str = r#"'{enum_value}'"#;
+
 pub static SQL_ATTRIBUTE_NAMES_TEMPLATE: &str = r#"{attribute_names}"#;
 
 pub static SQL_ATTRIBUTE_TEMPLATE: &str = r#"
-    {attribute_name} {attribute_type}"#;
+    {attribute_name} {attribute_type}{default}"#;
+
+pub static SQL_PRIMARY_KEY_TEMPLATE: &str = r#"
+    PRIMARY KEY ({attribute_names})"#;
+
+// Tables can reference each other in any order, so the constraints are added once all tables exist
+pub static SQL_FOREIGN_KEY_QUERY_TEMPLATE: &str = r#"
+ALTER TABLE {sc_plural_entity} ADD CONSTRAINT {sc_plural_entity}_{attribute_name}_fkey
+    FOREIGN KEY ({attribute_name}) REFERENCES {sc_referenced_plural_entity} ({referenced_attribute_name}){referential_actions};
+"#;
+
+// A link disappears with either of the entities it relates
+pub static SQL_RELATION_ATTRIBUTE_TEMPLATE: &str = r#"
+    {attribute_name} {attribute_type} REFERENCES {sc_referenced_plural_entity} ({referenced_attribute_name}) ON DELETE CASCADE,"#;
+
+pub static SQL_ON_DELETE_TEMPLATE: &str = " ON DELETE {referential_action}";
+
+pub static SQL_ON_UPDATE_TEMPLATE: &str = " ON UPDATE {referential_action}";
+
+pub static SQL_DEFAULT_TEMPLATE: &str = " DEFAULT {default_value}";
+
+pub static SQL_IDENTITY_TEMPLATE: &str = " GENERATED ALWAYS AS IDENTITY";
 
 pub trait PostgresTableGenerator {
     fn generate_attribute_names(&self, attributes: Vec<String>) -> String {
@@ -27,14 +94,93 @@ pub trait PostgresTableGenerator {
 
     fn generate_index_queries(&self, entity: &Entity) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
-        entity.filter_by.iter().map(|attribute_names| {
+        entity.filters().iter().map(|attribute_names| {
             let most_specific_attribute_name = attribute_names.last().unwrap();
+            let template = match entity.attribute_type(most_specific_attribute_name) {
+                Some(attribute_type) if attribute_names.len() == 1 && (attribute_type.is_array() || attribute_type.is_json()) => SQL_GIN_INDEX_QUERY_TEMPLATE,
+                _ => SQL_INDEX_QUERY_TEMPLATE,
+            };
             let attribute_names = self.generate_attribute_names(attribute_names.clone());
-            SQL_INDEX_QUERY_TEMPLATE
+            template
                 .replace("{sc_plural_entity}", &sc_plural_entity)
                 .replace("{attribute_name}", &most_specific_attribute_name)
                 .replace("{attribute_names}", &attribute_names)
-        }).collect::<Vec<String>>().join("\n")
+        }).chain(self.generate_match_index_queries(entity)).chain(self.generate_range_index_queries(entity)).chain(self.generate_cursor_index_query(entity)).chain(self.generate_search_index_query(entity)).collect::<Vec<String>>().join("\n")
+    }
+
+    /**
+     * An attribute matched with several modes gets a single trigram index, which serves all of them.
+     */
+    fn generate_match_index_queries(&self, entity: &Entity) -> Vec<String> {
+        let sc_plural_entity = to_snake_case(&entity.plural_name);
+        let mut attribute_names: Vec<&String> = Vec::new();
+        for match_filter in &entity.match_filters {
+            if !attribute_names.contains(&&match_filter.attribute_name) {
+                attribute_names.push(&match_filter.attribute_name);
+            }
+        }
+        if attribute_names.is_empty() {
+            return vec![];
+        }
+        std::iter::once(SQL_TRIGRAM_EXTENSION_QUERY.to_string()).chain(attribute_names.into_iter().map(|attribute_name| {
+            SQL_TRIGRAM_INDEX_QUERY_TEMPLATE
+                .replace("{sc_plural_entity}", &sc_plural_entity)
+                .replace("{attribute_name}", attribute_name)
+        })).collect()
+    }
+
+    /**
+     * Attributes that are already indexed on their own, by a filter or as primary key, need no range index.
+     */
+    fn generate_range_index_queries(&self, entity: &Entity) -> Vec<String> {
+        let sc_plural_entity = to_snake_case(&entity.plural_name);
+        let filters = entity.filters();
+        entity.range_filter_by.iter()
+            .filter(|attribute_name| {
+                let single_attribute = vec![attribute_name.to_string()];
+                !filters.contains(&single_attribute) && entity.primary_key != single_attribute
+            })
+            .map(|attribute_name| {
+                SQL_RANGE_INDEX_QUERY_TEMPLATE
+                    .replace("{sc_plural_entity}", &sc_plural_entity)
+                    .replace("{attribute_name}", attribute_name)
+            }).collect()
+    }
+
+    /**
+     * The primary key index already serves the cursors of entities that are ordered by their primary key.
+     */
+    fn generate_cursor_index_query(&self, entity: &Entity) -> Option<String> {
+        let cursor_by = entity.cursor_by.as_ref().filter(|cursor_by| entity.primary_key.first() != Some(cursor_by))?;
+        let attribute_names = entity.cursor_attributes().iter().map(|(attribute_name, _)| attribute_name.to_string()).collect::<Vec<String>>();
+        Some(SQL_CURSOR_INDEX_QUERY_TEMPLATE
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{attribute_name}", cursor_by)
+            .replace("{attribute_names}", &self.generate_attribute_names(attribute_names)))
+    }
+
+    fn generate_search_index_query(&self, entity: &Entity) -> Option<String> {
+        if entity.searchable.is_empty() {
+            return None;
+        }
+        Some(SQL_SEARCH_INDEX_QUERY_TEMPLATE
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{search_column}", SEARCH_COLUMN))
+    }
+
+    fn generate_search_column(&self, entity: &Entity) -> String {
+        if entity.searchable.is_empty() {
+            return String::new();
+        }
+        let search_document = entity.searchable.iter().enumerate().map(|(index, attribute_name)| {
+            SQL_SEARCH_WEIGHT_TEMPLATE
+                .replace("{search_configuration}", SEARCH_CONFIGURATION)
+                .replace("{attribute_name}", attribute_name)
+                .replace("{weight}", ["A", "B", "C", "D"][index.min(3)])
+        }).collect::<Vec<String>>().join(" || ");
+        SQL_SEARCH_COLUMN_TEMPLATE
+            .replace("{search_column}", SEARCH_COLUMN)
+            .replace("{search_document}", &search_document)
     }
 
     fn generate_unique_index_queries(&self, entity: &Entity) -> String {
@@ -48,24 +194,80 @@ pub trait PostgresTableGenerator {
                 .replace("{attribute_names}", &attribute_names)
         }).collect::<Vec<String>>().join("\n")
     }
+    fn generate_foreign_key_queries(&self, entity: &Entity, entities: &[Entity]) -> String {
+        let sc_plural_entity = to_snake_case(&entity.plural_name);
+        entity.foreign_keys.iter().filter_map(|(attribute_name, foreign_key)| {
+            let referenced_entity = entities.iter().find(|referenced_entity| referenced_entity.name == foreign_key.entity_name)?;
+            let on_delete = foreign_key.on_delete.as_ref()
+                .map(|action| SQL_ON_DELETE_TEMPLATE.replace("{referential_action}", &action.to_string()))
+                .unwrap_or_default();
+            let on_update = foreign_key.on_update.as_ref()
+                .map(|action| SQL_ON_UPDATE_TEMPLATE.replace("{referential_action}", &action.to_string()))
+                .unwrap_or_default();
+            Some(SQL_FOREIGN_KEY_QUERY_TEMPLATE
+                .replace("{sc_plural_entity}", &sc_plural_entity)
+                .replace("{attribute_name}", attribute_name)
+                .replace("{sc_referenced_plural_entity}", &to_snake_case(&referenced_entity.plural_name))
+                .replace("{referenced_attribute_name}", &foreign_key.attribute_name)
+                .replace("{referential_actions}", &(on_delete + &on_update)))
+        }).collect::<Vec<String>>().join("")
+    }
+
+    fn generate_enum_type_query(&self, enum_type: &EnumType) -> String {
+        let enum_values = enum_type.values.iter().map(|enum_value| {
+            SQL_ENUM_VALUE_TEMPLATE.replace("{enum_value}", enum_value)
+        }).collect::<Vec<String>>().join(", ");
+        SQL_ENUM_TYPE_TEMPLATE
+            .replace("{sc_enum_name}", &to_snake_case(&enum_type.name))
+            .replace("{enum_values}", &enum_values)
+    }
+
+    /**
+     * The join table of a relation. The primary key starts with the first entity, so only the second one needs an index.
+     */
+    fn generate_relation_table_query(&self, relation: &Relation) -> String {
+        let sc_plural_relation = to_snake_case(&relation.plural_name);
+        let mut attributes = String::new();
+        for end in &relation.ends {
+            let postgres_attribute_type: PostgresAttributeType = (&end.attribute_type).into();
+            attributes.push_str(&SQL_RELATION_ATTRIBUTE_TEMPLATE
+                .replace("{attribute_name}", &end.attribute_name)
+                .replace("{attribute_type}", &postgres_attribute_type.to_string())
+                .replace("{sc_referenced_plural_entity}", &to_snake_case(&end.plural_name))
+                .replace("{referenced_attribute_name}", &end.primary_key));
+        }
+        let attribute_names = relation.ends.iter().map(|end| end.attribute_name.clone()).collect::<Vec<String>>();
+        attributes.push_str(&SQL_PRIMARY_KEY_TEMPLATE
+            .replace("{attribute_names}", &attribute_names.join(", ")));
+        SQL_TABLE_QUERY_TEMPLATE
+            .replace("{sc_plural_entity}", &sc_plural_relation)
+            .replace("{attributes}", &attributes)
+        +
+        &SQL_INDEX_QUERY_TEMPLATE
+            .replace("{sc_plural_entity}", &sc_plural_relation)
+            .replace("{attribute_name}", &relation.ends[1].attribute_name)
+            .replace("{attribute_names}", &relation.ends[1].attribute_name)
+    }
+
     fn generate_table_query(&self, entity: &Entity) -> String {
         let sc_plural_entity = to_snake_case(&entity.plural_name);
         let mut attributes = String::new();
         for (attribute_name, attribute_type) in &entity.attributes {
             let postgres_attribute_type: PostgresAttributeType = attribute_type.into();
-            // Latest attribute cannot have a comma.
-            if entity.is_last(&attribute_name) {
-                attributes.push_str(&SQL_ATTRIBUTE_TEMPLATE
-                    .replace("{attribute_name}", &attribute_name)
-                    .replace("{attribute_type}", &postgres_attribute_type.to_string()));
-            } else {
-                attributes.push_str(&SQL_ATTRIBUTE_TEMPLATE
-                    .replace("{attribute_name}", &attribute_name)
-                    .replace("{attribute_type}", (postgres_attribute_type.to_string() + ",").as_str()));
-            }
-
-            
+            let default = match entity.default_value(attribute_name) {
+                _ if entity.is_database_generated(attribute_name) => SQL_IDENTITY_TEMPLATE.to_string(),
+                Some(default) => SQL_DEFAULT_TEMPLATE.replace("{default_value}", &default.sql),
+                None => String::new(),
+            };
+            // The primary key constraint follows the attributes, so every attribute has a comma.
+            attributes.push_str(&SQL_ATTRIBUTE_TEMPLATE
+                .replace("{attribute_name}", attribute_name)
+                .replace("{attribute_type}", &postgres_attribute_type.to_string())
+                .replace("{default}", (default + ",").as_str()));
         }
+        attributes.push_str(&self.generate_search_column(entity));
+        attributes.push_str(&SQL_PRIMARY_KEY_TEMPLATE
+            .replace("{attribute_names}", &entity.primary_key.join(", ")));
         SQL_TABLE_QUERY_TEMPLATE
             .replace("{sc_plural_entity}", &sc_plural_entity)
             .replace("{attributes}", &attributes)
@@ -74,4 +276,269 @@ pub trait PostgresTableGenerator {
         +
         &self.generate_unique_index_queries(entity)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{models::{ddr_format::DdrFormat, ddr_req::DomainDrivenRequest}, templates::postgres::crud_query_templates::CrudQueryGenerator};
+
+    use super::PostgresTableGenerator;
+
+    fn request(ddr: &str) -> DomainDrivenRequest {
+        DomainDrivenRequest::try_from(DdrFormat::Yaml.parse(ddr).unwrap()).unwrap()
+    }
+
+    fn normalize(query: &str) -> String {
+        query.split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    #[test]
+    fn bounds_the_length_of_a_string_column() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Country:
+      id: Uuid
+      code: String(2)
+      aliases: Vec<String(3)>
+      name: Text
+");
+        assert_eq!(
+            normalize(&ddr.generate_table_query(&ddr.entities[0])),
+            "CREATE TABLE IF NOT EXISTS countries ( aliases VARCHAR(3)[] NOT NULL, code VARCHAR(2) NOT NULL, id UUID NOT NULL, name TEXT NOT NULL, PRIMARY KEY (id) );"
+        );
+    }
+
+    #[test]
+    fn creates_the_enum_types_of_the_enum_columns() {
+        let ddr = request("
+service_name: shop
+enums:
+  - CarStatus: [available, in_repair, sold]
+entities:
+  - Car:
+      id: Uuid
+      status: CarStatus
+      next_status: Option<CarStatus>
+      previous_statuses: Vec<CarStatus>
+");
+        assert_eq!(
+            normalize(&ddr.generate_enum_type_query(&ddr.enums[0])),
+            "DO $$ BEGIN CREATE TYPE car_status AS ENUM ('available', 'in_repair', 'sold'); EXCEPTION WHEN duplicate_object THEN null; END $$;"
+        );
+        let car = &ddr.entities[0];
+        assert_eq!(
+            normalize(&ddr.generate_table_query(car)),
+            "CREATE TABLE IF NOT EXISTS cars ( id UUID NOT NULL, next_status car_status, previous_statuses car_status[] NOT NULL, status car_status NOT NULL, PRIMARY KEY (id) );"
+        );
+        assert_eq!(
+            normalize(&ddr.generate_get_query(car)),
+            r#"SELECT id, next_status AS "next_status: CarStatus", previous_statuses AS "previous_statuses: Vec<CarStatus>", status AS "status: CarStatus" FROM cars WHERE id = $1;"#
+        );
+    }
+
+    #[test]
+    fn stores_json_attributes_as_jsonb() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Product:
+      id: Uuid
+      metadata: Json
+      dimensions: Option<Json<Dimensions>>
+      filter_by: [metadata]
+");
+        let product = &ddr.entities[0];
+        assert_eq!(product.attribute_type("metadata").unwrap().to_string(), "serde_json::Value");
+        assert_eq!(product.attribute_type("dimensions").unwrap().to_string(), "Option<sqlx::types::Json<Dimensions>>");
+        assert_eq!(
+            normalize(&ddr.generate_table_query(product)),
+            "CREATE TABLE IF NOT EXISTS products ( dimensions JSONB, id UUID NOT NULL, metadata JSONB NOT NULL, PRIMARY KEY (id) ); CREATE INDEX IF NOT EXISTS products_metadata_index ON products USING GIN (metadata);"
+        );
+        assert_eq!(
+            normalize(&ddr.generate_get_query(product)),
+            r#"SELECT dimensions AS "dimensions: sqlx::types::Json<Dimensions>", id, metadata FROM products WHERE id = $1;"#
+        );
+    }
+
+    #[test]
+    fn stores_decimal_attributes_as_numeric() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Invoice:
+      id: Uuid
+      total: Decimal(10, 2)
+      discount: Option<Decimal(5,4)>
+      exchange_rate: Decimal
+");
+        let invoice = &ddr.entities[0];
+        assert_eq!(invoice.attribute_type("total").unwrap().to_string(), "rust_decimal::Decimal");
+        assert_eq!(invoice.attribute_type("discount").unwrap().to_string(), "Option<rust_decimal::Decimal>");
+        assert_eq!(
+            normalize(&ddr.generate_table_query(invoice)),
+            "CREATE TABLE IF NOT EXISTS invoices ( discount NUMERIC(5,4), exchange_rate NUMERIC NOT NULL, id UUID NOT NULL, total NUMERIC(10,2) NOT NULL, PRIMARY KEY (id) );"
+        );
+    }
+
+    #[test]
+    fn stores_dates_times_and_intervals() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Booking:
+      id: Uuid
+      created_at: NaiveDateTime
+      starts_at: DateTime<Utc>
+      day: NaiveDate
+      opens_at: chrono::NaiveTime
+      length: Interval
+      grace_period: Option<Duration>
+");
+        let booking = &ddr.entities[0];
+        assert_eq!(booking.attribute_type("starts_at").unwrap().to_string(), "chrono::DateTime<chrono::Utc>");
+        assert_eq!(booking.attribute_type("length").unwrap().to_string(), "crate::models::interval::Interval");
+        assert_eq!(
+            normalize(&ddr.generate_table_query(booking)),
+            "CREATE TABLE IF NOT EXISTS bookings ( created_at TIMESTAMP NOT NULL, day DATE NOT NULL, grace_period INTERVAL, id UUID NOT NULL, length INTERVAL NOT NULL, opens_at TIME NOT NULL, starts_at TIMESTAMPTZ NOT NULL, PRIMARY KEY (id) );"
+        );
+        assert_eq!(
+            normalize(&ddr.generate_get_query(booking)),
+            r#"SELECT created_at, day, grace_period AS "grace_period: crate::models::interval::Interval", id, length AS "length: crate::models::interval::Interval", opens_at, starts_at FROM bookings WHERE id = $1;"#
+        );
+    }
+
+    #[test]
+    fn translates_the_defaults_into_sql_literals() {
+        let ddr = request(r#"
+service_name: shop
+enums:
+  - ProductStatus: [draft, published]
+entities:
+  - Product:
+      id: Uuid
+      active: bool
+      stock: i32
+      price: Decimal(10,2)
+      name: String
+      status: ProductStatus
+      tags: Vec<String>
+      metadata: Json
+      note: Option<String>
+      created_at: DateTime<Utc>
+      defaults:
+        id: gen_random_uuid()
+        active: true
+        stock: 0
+        price: "9.99"
+        name: "O'Brien"
+        status: draft
+        tags: []
+        metadata: {"color": "red"}
+        note: null
+        created_at: now()
+"#);
+        let product = &ddr.entities[0];
+        assert_eq!(
+            normalize(&ddr.generate_table_query(product)),
+            normalize(r#"CREATE TABLE IF NOT EXISTS products (
+                active BOOLEAN NOT NULL DEFAULT TRUE,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                id UUID NOT NULL DEFAULT gen_random_uuid(),
+                metadata JSONB NOT NULL DEFAULT '{"color":"red"}'::JSONB,
+                name VARCHAR(255) NOT NULL DEFAULT 'O''Brien',
+                note VARCHAR(255) DEFAULT NULL,
+                price NUMERIC(10,2) NOT NULL DEFAULT 9.99,
+                status product_status NOT NULL DEFAULT 'draft',
+                stock INT NOT NULL DEFAULT 0,
+                tags VARCHAR(255)[] NOT NULL DEFAULT '{}',
+                PRIMARY KEY (id)
+            );"#)
+        );
+        let rust_default = |attribute_name: &str| product.default_value(attribute_name).unwrap().rust.clone();
+        assert_eq!(rust_default("id"), "Uuid::new_v4()");
+        assert_eq!(rust_default("name"), r#""O'Brien".to_string()"#);
+        assert_eq!(rust_default("price"), r#"rust_decimal::Decimal::from_str_exact("9.99").unwrap()"#);
+        assert_eq!(rust_default("status"), "ProductStatus::Draft");
+        assert_eq!(rust_default("note"), "None");
+        assert_eq!(rust_default("created_at"), "chrono::Utc::now()");
+    }
+
+    #[test]
+    fn adds_the_referential_actions_to_the_foreign_key_constraints() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Car:
+      id: Uuid
+      owned_by: User.id
+      co_owned_by: Option<User.id>
+      sold_by: Option<User.id>
+      on_delete: {owned_by: cascade, co_owned_by: set null, sold_by: restrict}
+      on_update: {owned_by: cascade}
+  - User:
+      id: Uuid
+");
+        assert_eq!(
+            normalize(&ddr.generate_foreign_key_queries(&ddr.entities[0], &ddr.entities)),
+            normalize("
+                ALTER TABLE cars ADD CONSTRAINT cars_co_owned_by_fkey FOREIGN KEY (co_owned_by) REFERENCES users (id) ON DELETE SET NULL;
+                ALTER TABLE cars ADD CONSTRAINT cars_owned_by_fkey FOREIGN KEY (owned_by) REFERENCES users (id) ON DELETE CASCADE ON UPDATE CASCADE;
+                ALTER TABLE cars ADD CONSTRAINT cars_sold_by_fkey FOREIGN KEY (sold_by) REFERENCES users (id) ON DELETE RESTRICT;
+            ")
+        );
+    }
+
+    #[test]
+    fn creates_a_join_table_for_a_relation() {
+        let ddr = request("
+service_name: shop
+entities:
+  - User:
+      id: i64
+      name: String
+  - Group:
+      id: Uuid
+      name: String
+relations:
+  - Membership: [User, Group]
+");
+        assert_eq!(
+            normalize(&ddr.generate_relation_table_query(&ddr.relations[0])),
+            normalize("
+                CREATE TABLE IF NOT EXISTS memberships (
+                    user_id BIGINT NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+                    group_id UUID NOT NULL REFERENCES groups (id) ON DELETE CASCADE,
+                    PRIMARY KEY (user_id, group_id)
+                );
+                CREATE INDEX IF NOT EXISTS memberships_group_id_index ON memberships (group_id);
+            ")
+        );
+    }
+
+    #[test]
+    fn generates_a_weighted_search_column() {
+        let ddr = request("
+service_name: blog
+entities:
+  - Post:
+      id: Uuid
+      title: String
+      body: Text
+      searchable: [title, body]
+");
+        assert_eq!(
+            normalize(&ddr.generate_table_query(&ddr.entities[0])),
+            normalize("
+                CREATE TABLE IF NOT EXISTS posts (
+                    body TEXT NOT NULL,
+                    id UUID NOT NULL,
+                    title VARCHAR(255) NOT NULL,
+                    search_vector TSVECTOR GENERATED ALWAYS AS (setweight(to_tsvector('simple', coalesce(title, '')), 'A') || setweight(to_tsvector('simple', coalesce(body, '')), 'B')) STORED,
+                    PRIMARY KEY (id)
+                );
+                CREATE INDEX IF NOT EXISTS posts_search_vector_index ON posts USING GIN (search_vector);
+            ")
+        );
+    }
+}
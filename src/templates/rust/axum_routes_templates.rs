@@ -1,4 +1,4 @@
-use crate::{models::entity::Entity, utils::naming_convention::to_snake_case};
+use crate::{models::{entity::Entity, relation::Relation}, utils::naming_convention::to_snake_case};
 
 use super::import_templates::ImportGenerator;
 
@@ -15,7 +15,17 @@ pub static AXUM_ENTITIY_COLLECTION_ROUTE_TEMPLATE: &str = r#"
         .route("/v1/{sc_plural_entity}", get(filter_{sc_plural_entity}).post(create_{sc_entity_name}))"#;
 
 pub static AXUM_ENTITY_ROUTE_TEMPLATE: &str = r#"
-        .route("/v1/{sc_plural_entity}/:id", get(get_{sc_entity_name}).put(update_{sc_entity_name}).delete(delete_{sc_entity_name}))"#;
+        .route("/v1/{sc_plural_entity}/{primary_key_segments}", get(get_{sc_entity_name}).put(replace_{sc_entity_name}).patch(update_{sc_entity_name}).delete(delete_{sc_entity_name}))"#;
+
+pub static AXUM_ENTITY_BATCH_ROUTE_TEMPLATE: &str = r#"
+        .route("/v1/{sc_plural_entity}/batch", post(create_{sc_entity_name}_batch).patch(update_{sc_entity_name}_batch).delete(delete_{sc_entity_name}_batch))"#;
+
+pub static AXUM_NESTED_COLLECTION_ROUTE_TEMPLATE: &str = r#"
+        .route("/v1/{sc_referenced_plural_entity}/{referenced_segment}/{nested_collection}", get(filter_{sc_plural_entity}_by_{attribute_name}).post(create_{sc_entity_name}_by_{attribute_name}))"#;
+
+pub static AXUM_RELATED_COLLECTION_ROUTE_TEMPLATE: &str = r#"
+        .route("/v1/{sc_plural_entity}/:id/{sc_related_plural_entity}", get(get_{sc_related_plural_entity}_of_{sc_entity_name}))
+        .route("/v1/{sc_plural_entity}/:id/{sc_related_plural_entity}/:{related_attribute_name}", post(link_{sc_related_entity_name}_to_{sc_entity_name}).delete(unlink_{sc_related_entity_name}_from_{sc_entity_name}))"#;
 
 pub static ROUTES_FILE_TEMPLATE: &str = r#"
 use std::sync::Arc;
@@ -32,29 +42,86 @@ use crate::services::ServicesState;
 
 
 pub trait AxumRoutesGenerator: ImportGenerator {
+    /**
+     * A single primary key is matched as `:id`, composite primary keys get a segment per attribute: `:user_id/:role_id`.
+     */
+    fn generate_primary_key_segments(&self, entity: &Entity) -> String {
+        if !entity.has_composite_primary_key() {
+            return ":id".to_string();
+        }
+        entity.primary_key.iter().map(|primary_key| format!(":{}", primary_key)).collect::<Vec<String>>().join("/")
+    }
+
+    /**
+     * A nested collection is named after the entities it lists: `/v1/users/:id/cars`.
+     * When several foreign keys reference the same entity, the attribute tells them apart: `/v1/users/:id/cars_by_co_owner_id`.
+     * The segment of the referenced entity has the same name as in its own routes, since the router requires it.
+     */
+    fn generate_nested_routes(&self, entity: &Entity, entities: &Vec<&Entity>) -> String {
+        let nested_foreign_keys = entity.nested_foreign_keys();
+        nested_foreign_keys.iter().filter_map(|(attribute_name, foreign_key)| {
+            let referenced_entity = entities.iter().find(|referenced_entity| referenced_entity.name == foreign_key.entity_name)?;
+            let referenced_segments = self.generate_primary_key_segments(referenced_entity);
+            let referenced_segment = referenced_segments.split('/').next().unwrap_or_default();
+            let mut nested_collection = to_snake_case(&entity.plural_name);
+            if nested_foreign_keys.iter().filter(|(_, other_foreign_key)| other_foreign_key.entity_name == foreign_key.entity_name).count() > 1 {
+                nested_collection = format!("{}_by_{}", nested_collection, attribute_name);
+            }
+            Some(AXUM_NESTED_COLLECTION_ROUTE_TEMPLATE
+                .replace("{sc_referenced_plural_entity}", &to_snake_case(&referenced_entity.plural_name))
+                .replace("{referenced_segment}", referenced_segment)
+                .replace("{nested_collection}", &nested_collection)
+                .replace("{attribute_name}", attribute_name)
+                .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name)))
+        }).collect::<Vec<String>>().join("")
+    }
+
     fn generate_axum_routes(&self, entities: &Vec<&Entity>) -> String {
         let mut axum_routes = String::new();
         for entity in entities {
             let entity_route = AXUM_ENTITY_ROUTE_TEMPLATE
+            .replace("{primary_key_segments}", &self.generate_primary_key_segments(entity))
             .replace("{sc_entity_name}", &to_snake_case(&entity.name))
             .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name));
             let entity_collection_route = AXUM_ENTITIY_COLLECTION_ROUTE_TEMPLATE
             .replace("{sc_entity_name}", &to_snake_case(&entity.name))
             .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name));
+            let entity_batch_route = AXUM_ENTITY_BATCH_ROUTE_TEMPLATE
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name));
             axum_routes.push_str(&entity_collection_route);
+            axum_routes.push_str(&entity_batch_route);
             axum_routes.push_str(&entity_route);
+            axum_routes.push_str(&self.generate_nested_routes(entity, entities));
         }
         axum_routes
     }
 
-    fn generate_axum_routes_system(&self, entities: &Vec<&Entity>) -> String {
-        let axum_routes = self.generate_axum_routes(entities);
+    /**
+     * Both entities of a relation list the other one: `/v1/users/:id/groups` and `/v1/groups/:id/users`.
+     */
+    fn generate_relation_routes(&self, relations: &[Relation]) -> String {
+        relations.iter().flat_map(|relation| relation.directions()).map(|(end, related_end)| {
+            AXUM_RELATED_COLLECTION_ROUTE_TEMPLATE
+                .replace("{sc_plural_entity}", &to_snake_case(&end.plural_name))
+                .replace("{sc_related_plural_entity}", &to_snake_case(&related_end.plural_name))
+                .replace("{related_attribute_name}", &related_end.attribute_name)
+                .replace("{sc_related_entity_name}", &to_snake_case(&related_end.entity_name))
+                .replace("{sc_entity_name}", &to_snake_case(&end.entity_name))
+        }).collect::<Vec<String>>().join("")
+    }
+
+    fn generate_axum_routes_system(&self, entities: &Vec<&Entity>, relations: &[Relation]) -> String {
+        let axum_routes = self.generate_axum_routes(entities) + &self.generate_relation_routes(relations);
         AXUM_ROUTES_SYSTEM_TEMPLATE.replace("{axum_entity_routes}", &axum_routes)
     }
 
-    fn generate_routes_file(&self, entities: Vec<&Entity>) -> String {
-        let axum_routes_system = self.generate_axum_routes_system(&entities);
-        let controller_imports = entities.iter().map(|entity| self.generate_controller_imports(&entity)).collect::<Vec<String>>().join("\n");
+    fn generate_routes_file(&self, entities: Vec<&Entity>, relations: &[Relation]) -> String {
+        let axum_routes_system = self.generate_axum_routes_system(&entities, relations);
+        let controller_imports = entities.iter().map(|entity| self.generate_controller_imports(entity))
+            .chain(relations.iter().map(|relation| self.generate_relation_controller_imports(relation)))
+            .collect::<Vec<String>>().join("\n");
         ROUTES_FILE_TEMPLATE
         .replace("{routes_system}", &axum_routes_system)
         .replace("{controller_imports}", &controller_imports)
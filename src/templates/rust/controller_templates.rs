@@ -1,4 +1,4 @@
-use crate::{utils::naming_convention::to_snake_case, models::{entity::AttributeType, entity::Entity}};
+use crate::{utils::naming_convention::{to_pascal_case, to_snake_case}, models::{entity::AttributeType, entity::Entity, relation::Relation}};
 
 use super::{model_templates::ATTRIBUTE_TEMPLATE, import_templates::ImportGenerator};
 
@@ -19,51 +19,61 @@ pub async fn create_{sc_entity_name}(
 
 pub static CONTROLLER_GET_ENTITY_TEMPLATE: &str = r#"
 pub async fn get_{sc_entity_name}(
-    Path(id): Path<Uuid>,
+    Path(id): Path<{primary_key_type}>,{expand_params}
     State(services): State<Arc<ServicesState>>,
-) -> Result<impl IntoResponse> {
+) -> Result<impl IntoResponse> {{parse_expand}
     return services
             .{sc_plural_entity}_service
             .get_{sc_entity_name}(&id)
             .await
-            .map(|{sc_entity_name}| {
-                (StatusCode::OK, Json({entity_name}Response::{entity_name}({sc_entity_name})))
-            });
+            {response};
 }
 "#;
 
 pub static CONTROLLER_GET_PAGINATED_ENTITIES_TEMPLATE: &str = r#"
 pub async fn filter_{sc_plural_entity}(
-    Query(filter_params): Query<{entity_name}FilterParams>,
+    Query(filter_params): Query<{entity_name}FilterParams>,{cursor_params}
     State(services): State<Arc<ServicesState>>,
-) -> Result<impl IntoResponse> {
+) -> Result<impl IntoResponse> {{parse_expand}{parse_sort}{reject_page}
     {filter_by}
     return services
             .{sc_plural_entity}_service
-            .get_paginated_{sc_plural_entity}(
-                filter_params.page.unwrap_or(1),
-                filter_params.page_size.unwrap_or(10)
-            )
+            {get_page}
             .await
-            .map(|{sc_plural_entity}| {
-                (StatusCode::OK, Json({entity_name}Response::Paginated{plural_entity}({sc_plural_entity})))
-            });
+            {response};
     }
 "#;
 
+pub static GET_PAGE_TEMPLATE: &str = r#".get_paginated_{sc_plural_entity}({sort_arg}
+                filter_params.page.unwrap_or(1),
+                filter_params.page_size.unwrap_or(10)
+            )"#;
+
+// Entities listed with cursors are paged with `?cursor=`, starting from the first page when it is missing
+pub static GET_PAGE_AFTER_CURSOR_TEMPLATE: &str = r#".get_{sc_plural_entity}_after(
+                cursor_params.cursor.as_deref(),
+                cursor_params.page_size.unwrap_or(10)
+            )"#;
+
+pub static CONTROLLER_CURSOR_PARAMS_TEMPLATE: &str = r#"
+    Query(cursor_params): Query<CursorParams>,"#;
+
+// A page number would be ignored by entities listed with cursors, it is rejected instead
+pub static CONTROLLER_REJECT_PAGE_TEMPLATE: &str = r#"
+    if {paging_params}.page.is_some() {
+        return Err(Error::{entity_name}ValidationError("{plural_entity} are paged with `cursor` instead of `page`".to_string()));
+    }"#;
+
 pub static FILTER_BY_PAGINATED_TEMPLATE: &str = r#"
     if filter_params.is_{attribute_name}_filter() {
         return services
                 .{sc_plural_entity}_service
                 .filter_{sc_plural_entity}_by_{attribute_name}(
-                    {filter_by_fields},
-                    filter_params.page.unwrap_or(1),
-                    filter_params.page_size.unwrap_or(10)
+                    {filter_by_fields},{sort_arg}
+                    {paging_args}
                 )
                 .await
-                .map(|{sc_plural_entity}| {
-                    (StatusCode::OK, Json({entity_name}Response::Paginated{plural_entity_name}({sc_plural_entity})))
-                });
+                {response};
     }
 "#;
 
@@ -75,12 +85,102 @@ pub static FILTER_BY_TEMPLATE: &str = r#"
                     {filter_by_fields}
                 )
                 .await
-                .map(|{sc_entity_name}| {
-                    (StatusCode::OK, Json({entity_name}Response::{entity_name}({sc_entity_name})))
-                })
+                {response}
+    }
+"#;
+
+// A range filter is not combined with the other filters, which would otherwise drop its bounds
+pub static REJECT_COMBINED_RANGE_FILTER_TEMPLATE: &str = r#"
+    if filter_params.is_range_filter() && ({is_other_filter}) {
+        return Err(Error::{entity_name}ValidationError("The range filters cannot be combined with the other filters".to_string()));
+    }
+"#;
+
+pub static FILTER_BY_RANGE_TEMPLATE: &str = r#"
+    if filter_params.is_range_filter() {
+        return services
+                .{sc_plural_entity}_service
+                .filter_{sc_plural_entity}_by_range(
+                    {range_values},{sort_arg}
+                    {paging_args}
+                )
+                .await
+                {response};
+    }
+"#;
+
+// Search results are ranked by relevance, hence they are not sorted
+pub static SEARCH_TEMPLATE: &str = r#"
+    if let Some(q) = &filter_params.q {
+        return services
+                .{sc_plural_entity}_service
+                .search_{sc_plural_entity}(
+                    q,
+                    filter_params.page.unwrap_or(1),
+                    filter_params.page_size.unwrap_or(10)
+                )
+                .await
+                {response};
     }
 "#;
 
+/**
+ * Nested routes list the entities that reference a parent: `GET /v1/users/:id/cars`,
+ * and create them under it: `POST /v1/users/:id/cars`, where the foreign key is taken from the path.
+ */
+pub static CONTROLLER_FILTER_NESTED_ENTITIES_TEMPLATE: &str = r#"
+pub async fn filter_{sc_plural_entity}_by_{attribute_name}(
+    Path(id): Path<{attribute_type}>,
+    Query(paginated_params): Query<{paging_params}>,{sort_params}
+    State(services): State<Arc<ServicesState>>,
+) -> Result<impl IntoResponse> {{parse_sort}{reject_page}
+    services
+        .{sc_plural_entity}_service
+        .filter_{sc_plural_entity}_by_{attribute_name}(
+            &id,{sort_arg}
+            {paging_args}
+        )
+        .await
+        .map(|{sc_plural_entity}| {
+            (StatusCode::OK, Json({entity_name}Response::{page_variant}({sc_plural_entity})))
+        })
+}
+"#;
+
+pub static CONTROLLER_CREATE_NESTED_ENTITY_TEMPLATE: &str = r#"
+pub async fn create_{sc_entity_name}_by_{attribute_name}(
+    Path(id): Path<{attribute_type}>,
+    State(services): State<Arc<ServicesState>>,
+    Json(payload): Json<Add{entity_name}By{pascal_attribute_name}Payload>
+) -> Result<impl IntoResponse> {
+    services
+        .{sc_plural_entity}_service
+        .create_{sc_entity_name}(payload.with_{attribute_name}(id))
+        .await
+        .map(|{sc_entity_name}| {
+            (StatusCode::CREATED, Json({sc_entity_name}))
+        })
+}
+"#;
+
+pub static CONTROLLER_CREATE_NESTED_ENTITY_PAYLOAD_TEMPLATE: &str = r#"
+#[derive(Deserialize)]
+pub struct Add{entity_name}By{pascal_attribute_name}Payload {
+    {attributes}
+}
+
+impl Add{entity_name}By{pascal_attribute_name}Payload {
+    pub fn with_{attribute_name}(self, {attribute_name}: {attribute_type}) -> Add{entity_name}Payload {
+        Add{entity_name}Payload {
+            {foreign_key_field},{payload_fields}
+        }
+    }
+}
+"#;
+
+pub static CONTROLLER_PAYLOAD_FIELD_TEMPLATE: &str = r#"
+            {attribute_name}: self.{attribute_name},"#;
+
 pub static CONTROLLER_CREATE_ENTITY_PAYLOAD_TEMPLATE: &str = r#"
 #[derive(Deserialize)]
 pub struct Add{entity_name}Payload {
@@ -89,9 +189,21 @@ pub struct Add{entity_name}Payload {
 "#;
 
 
+/**
+ * Attributes with a default value can be left out of the create payload.
+ * Nullable attributes distinguish a missing value, which takes the default, from an explicit `null`.
+ */
+pub static CONTROLLER_CREATE_ENTITY_DEFAULT_ATTRIBUTE_TEMPLATE: &str = r#"
+    #[serde(default)]
+    pub {attribute_name}: Option<{attribute_type}>,"#;
+
+pub static CONTROLLER_CREATE_ENTITY_NULLABLE_DEFAULT_ATTRIBUTE_TEMPLATE: &str = r#"
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub {attribute_name}: Option<{attribute_type}>,"#;
+
 pub static CONTROLLER_UPDATE_ENTITY_TEMPLATE: &str = r#"
 pub async fn update_{sc_entity_name}(
-    Path(id): Path<Uuid>,
+    Path(id): Path<{primary_key_type}>,
     State(services): State<Arc<ServicesState>>,
     Json(payload): Json<Update{entity_name}Payload>
 ) -> Result<impl IntoResponse> {
@@ -105,8 +217,10 @@ pub async fn update_{sc_entity_name}(
 }
 "#;
 
+// `PATCH` tells a missing attribute, which is kept, from `null`, which clears a nullable attribute and is rejected for a required one
 pub static CONTROLLER_UPDATE_ENTITY_ATTRIBUTE_TEMPLATE: &str = r#"
-    pub {attribute_name}: Option<{attribute_type}>,"#;
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub {attribute_name}: Option<Option<{attribute_type}>>,"#;
 
 pub static CONTROLLER_UPDATE_ENTITY_PAYLOAD_TEMPLATE: &str = r#"
 #[derive(Deserialize)]
@@ -115,9 +229,37 @@ pub struct Update{entity_name}Payload {
 }
 "#;
 
+pub static CONTROLLER_REPLACE_ENTITY_TEMPLATE: &str = r#"
+pub async fn replace_{sc_entity_name}(
+    Path(id): Path<{primary_key_type}>,
+    State(services): State<Arc<ServicesState>>,
+    Json(payload): Json<Replace{entity_name}Payload>
+) -> Result<impl IntoResponse> {
+    services
+        .{sc_plural_entity}_service
+        .replace_{sc_entity_name}(&id, payload)
+        .await
+        .map(|{sc_entity_name}| {
+            (StatusCode::OK, Json({sc_entity_name}))
+        })
+}
+"#;
+
+// `PUT` replaces the whole entity, its payload requires every attribute, and `null` for the nullable ones
+pub static CONTROLLER_REPLACE_ENTITY_NULLABLE_ATTRIBUTE_TEMPLATE: &str = r#"
+    #[serde(deserialize_with = "Option::deserialize")]
+    pub {attribute_name}: {attribute_type},"#;
+
+pub static CONTROLLER_REPLACE_ENTITY_PAYLOAD_TEMPLATE: &str = r#"
+#[derive(Deserialize)]
+pub struct Replace{entity_name}Payload {
+    {attributes}
+}
+"#;
+
 pub static CONTROLLER_DELETE_ENTITY_TEMPLATE: &str = r#"
 pub async fn delete_{sc_entity_name}(
-    Path(id): Path<Uuid>,
+    Path(id): Path<{primary_key_type}>,
     State(services): State<Arc<ServicesState>>,
 ) -> Result<impl IntoResponse> {
     services
@@ -127,18 +269,184 @@ pub async fn delete_{sc_entity_name}(
 }
 "#;
 
+/**
+ * Batches write many entities in a single request: `POST`, `PATCH` and `DELETE /v1/users/batch`.
+ * They are atomic unless `?atomic=false` is given, see `batch_response`.
+ */
+pub static CONTROLLER_CREATE_ENTITY_BATCH_TEMPLATE: &str = r#"
+pub async fn create_{sc_entity_name}_batch(
+    Query(batch_params): Query<BatchParams>,
+    State(services): State<Arc<ServicesState>>,
+    Json(payloads): Json<Vec<Add{entity_name}Payload>>
+) -> Result<impl IntoResponse> {
+    let atomic = batch_params.atomic.unwrap_or(true);
+    services
+        .{sc_plural_entity}_service
+        .create_{sc_entity_name}_batch(payloads, atomic)
+        .await
+        .map(|results| batch_response(results, atomic, StatusCode::CREATED))
+}
+"#;
+
+pub static CONTROLLER_UPDATE_ENTITY_BATCH_TEMPLATE: &str = r#"
+pub async fn update_{sc_entity_name}_batch(
+    Query(batch_params): Query<BatchParams>,
+    State(services): State<Arc<ServicesState>>,
+    Json(items): Json<Vec<Update{entity_name}BatchItem>>
+) -> Result<impl IntoResponse> {
+    let atomic = batch_params.atomic.unwrap_or(true);
+    services
+        .{sc_plural_entity}_service
+        .update_{sc_entity_name}_batch(items, atomic)
+        .await
+        .map(|results| batch_response(results, atomic, StatusCode::OK))
+}
+"#;
+
+pub static CONTROLLER_DELETE_ENTITY_BATCH_TEMPLATE: &str = r#"
+pub async fn delete_{sc_entity_name}_batch(
+    Query(batch_params): Query<BatchParams>,
+    State(services): State<Arc<ServicesState>>,
+    Json(ids): Json<Vec<{primary_key_type}>>
+) -> Result<impl IntoResponse> {
+    let atomic = batch_params.atomic.unwrap_or(true);
+    services
+        .{sc_plural_entity}_service
+        .delete_{sc_entity_name}_batch(ids, atomic)
+        .await
+        .map(|results| batch_response(results, atomic, StatusCode::OK))
+}
+"#;
+
+// An item of an update batch is the update payload of the entity, along with its primary key
+pub static CONTROLLER_UPDATE_ENTITY_BATCH_ITEM_TEMPLATE: &str = r#"
+#[derive(Deserialize)]
+pub struct Update{entity_name}BatchItem {
+    pub id: {primary_key_type},
+    #[serde(flatten)]
+    pub payload: Update{entity_name}Payload,
+}
+"#;
+
+/**
+ * A relation lists the entities related to either side, `GET /v1/users/:id/groups`,
+ * and links or unlinks a pair of them: `POST/DELETE /v1/users/:id/groups/:group_id`.
+ */
+pub static CONTROLLER_GET_RELATED_ENTITIES_TEMPLATE: &str = r#"
+pub async fn get_{sc_related_plural_entity}_of_{sc_entity_name}(
+    Path(id): Path<{attribute_type}>,
+    Query(paginated_params): Query<PaginatedParams>,
+    State(services): State<Arc<ServicesState>>,
+) -> Result<impl IntoResponse> {
+    services
+        .{sc_plural_relation}_service
+        .get_{sc_related_plural_entity}_of_{sc_entity_name}(
+            &id,
+            paginated_params.page.unwrap_or(1),
+            paginated_params.page_size.unwrap_or(10)
+        )
+        .await
+        .map(|{sc_related_plural_entity}| {
+            (StatusCode::OK, Json({related_entity_name}Response::Paginated{related_plural_entity}({sc_related_plural_entity})))
+        })
+}
+"#;
+
+pub static CONTROLLER_LINK_TEMPLATE: &str = r#"
+pub async fn link_{sc_related_entity_name}_to_{sc_entity_name}(
+    Path((id, {related_attribute_name})): Path<({attribute_type}, {related_attribute_type})>,
+    State(services): State<Arc<ServicesState>>,
+) -> Result<impl IntoResponse> {
+    services
+        .{sc_plural_relation}_service
+        .link({relation_args})
+        .await
+        .map(|_| StatusCode::CREATED)
+}
+"#;
+
+pub static CONTROLLER_UNLINK_TEMPLATE: &str = r#"
+pub async fn unlink_{sc_related_entity_name}_from_{sc_entity_name}(
+    Path((id, {related_attribute_name})): Path<({attribute_type}, {related_attribute_type})>,
+    State(services): State<Arc<ServicesState>>,
+) -> Result<impl IntoResponse> {
+    services
+        .{sc_plural_relation}_service
+        .unlink({relation_args})
+        .await
+}
+"#;
+
+pub static CONTROLLER_RESPONSE_TEMPLATE: &str = r#".map(|{value}| {
+{indentation}    (StatusCode::OK, Json({entity_name}Response::{variant}({value})))
+{indentation}})"#;
+
+/**
+ * Entities with foreign keys accept `?expand=`, and embed the referenced entities in the response.
+ */
+pub static CONTROLLER_EXPANDED_RESPONSE_TEMPLATE: &str = r#".map({entity_name}Response::{variant})?
+{indentation}.expand(&services.{sc_plural_entity}_service, &{sc_entity_name}_expand)
+{indentation}.await
+{indentation}.map(|response| (StatusCode::OK, Json(response)))"#;
+
+pub static PAGING_ARGS_TEMPLATE: &str = r#"{paging_params}.page.unwrap_or(1),
+{indentation}{paging_params}.page_size.unwrap_or(10)"#;
+
+pub static CURSOR_PAGING_ARGS_TEMPLATE: &str = r#"{paging_params}.cursor.as_deref(),
+{indentation}{paging_params}.page_size.unwrap_or(10)"#;
+
+pub static CONTROLLER_EXPAND_PARAMS_TEMPLATE: &str = r#"
+    Query(expand_params): Query<ExpandParams>,"#;
+
+pub static CONTROLLER_PARSE_EXPAND_TEMPLATE: &str = r#"
+    let {sc_entity_name}_expand = {entity_name}Expand::parse({expand_params}.expand.as_deref())?;"#;
+
+pub static IMPORT_EXPAND_PARAMS_TEMPLATE: &str = r#"
+use crate::models::ExpandParams;"#;
+
+pub static CONTROLLER_SORT_PARAMS_TEMPLATE: &str = r#"
+    Query(sort_params): Query<SortParams>,"#;
+
+pub static CONTROLLER_PARSE_SORT_TEMPLATE: &str = r#"
+    let {sc_entity_name}_sort = {entity_name}Sort::parse({sort_params}.sort.as_deref())?;"#;
+
+// Entities that are not sortable are always ordered by their primary key
+pub static CONTROLLER_REJECT_SORT_TEMPLATE: &str = r#"
+    if {sort_params}.sort.is_some() {
+        return Err(Error::{entity_name}ValidationError("{plural_entity} cannot be sorted".to_string()));
+    }"#;
+
+pub static CONTROLLER_SORT_ARG_TEMPLATE: &str = r#"
+{indentation}{sc_entity_name}_sort,"#;
+
+pub static IMPORT_SORT_PARAMS_TEMPLATE: &str = r#"
+use crate::models::SortParams;"#;
+
+pub static IMPORT_PAGINATED_PARAMS_TEMPLATE: &str = r#"
+use crate::models::PaginatedParams;"#;
+
+pub static IMPORT_CURSOR_PARAMS_TEMPLATE: &str = r#"
+use crate::models::CursorParams;"#;
+
+pub static IMPORT_ERROR_TEMPLATE: &str = r#"
+use crate::error::Error;"#;
+
+pub static IMPORT_BATCH_TEMPLATE: &str = r#"
+use crate::models::{batch_response, BatchParams};"#;
+
+// Only the controllers of entities have payloads to deserialize
+pub static IMPORT_DESERIALIZE_TEMPLATE: &str = r#"
+use serde::Deserialize;"#;
+
 pub static CONTROLLER_FILE_TEMPLATE: &str = r#"
 use std::sync::Arc;
 
-use crate::models::PaginatedParams;
 use axum::extract::Path;
 use axum::extract::Query;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::Json;
-use serde::Deserialize;
-use uuid::Uuid;
+use axum::Json;{deserialize_import}
 
 use crate::error::Result;
 use crate::services::ServicesState;
@@ -153,6 +461,82 @@ use crate::services::ServicesState;
 
 
 pub trait ControllerGenerator: ImportGenerator {
+    /**
+     * Maps what the service returns to the response, expanding it when the entity has foreign keys.
+     */
+    fn generate_response(&self, entity: &Entity, variant: &str, value: &str, indentation: usize) -> String {
+        let template = if entity.foreign_keys.is_empty() { CONTROLLER_RESPONSE_TEMPLATE } else { CONTROLLER_EXPANDED_RESPONSE_TEMPLATE };
+        template
+            .replace("{indentation}", &" ".repeat(indentation))
+            .replace("{variant}", variant)
+            .replace("{value}", value)
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_parse_expand(&self, entity: &Entity, expand_params: &str) -> String {
+        if entity.foreign_keys.is_empty() {
+            return String::new();
+        }
+        CONTROLLER_PARSE_EXPAND_TEMPLATE
+            .replace("{expand_params}", expand_params)
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_parse_sort(&self, entity: &Entity, sort_params: &str) -> String {
+        let template = if entity.sortable.is_empty() { CONTROLLER_REJECT_SORT_TEMPLATE } else { CONTROLLER_PARSE_SORT_TEMPLATE };
+        template
+            .replace("{sort_params}", sort_params)
+            .replace("{plural_entity}", &entity.plural_name)
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_sort_arg(&self, entity: &Entity, indentation: usize) -> String {
+        if entity.sortable.is_empty() {
+            return String::new();
+        }
+        CONTROLLER_SORT_ARG_TEMPLATE
+            .replace("{indentation}", &" ".repeat(indentation))
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+    }
+
+    /**
+     * Entities listed with cursors are paged after the `cursor` parameter, the others by page number.
+     */
+    fn generate_paging_args(&self, entity: &Entity, paging_params: &str, indentation: usize) -> String {
+        let template = if entity.cursor_by.is_some() { CURSOR_PAGING_ARGS_TEMPLATE } else { PAGING_ARGS_TEMPLATE };
+        template
+            .replace("{paging_params}", paging_params)
+            .replace("{indentation}", &" ".repeat(indentation))
+    }
+
+    fn generate_reject_page(&self, entity: &Entity, paging_params: &str) -> String {
+        if entity.cursor_by.is_none() {
+            return String::new();
+        }
+        CONTROLLER_REJECT_PAGE_TEMPLATE.replace("{paging_params}", paging_params)
+    }
+
+    /**
+     * The list route reads the page number from the filter parameters, and the cursor from its own parameters.
+     */
+    fn list_paging_params(&self, entity: &Entity) -> &'static str {
+        if entity.cursor_by.is_some() { "cursor_params" } else { "filter_params" }
+    }
+
+    /**
+     * The variant of the response of a list of entities.
+     */
+    fn page_variant(&self, entity: &Entity) -> String {
+        match entity.cursor_by {
+            Some(_) => format!("Cursor{}", entity.plural_name),
+            None => format!("Paginated{}", entity.plural_name),
+        }
+    }
+
     fn generate_create_fn(&self, entity: &Entity) -> String {
         CONTROLLER_CREATE_ENTITY_TEMPLATE
             .replace("{sc_entity_name}", to_snake_case(&entity.name).as_str())
@@ -161,7 +545,12 @@ pub trait ControllerGenerator: ImportGenerator {
     }
 
     fn generate_get_fn(&self, entity: &Entity) -> String {
+        let expand_params = if entity.foreign_keys.is_empty() { "" } else { CONTROLLER_EXPAND_PARAMS_TEMPLATE };
         CONTROLLER_GET_ENTITY_TEMPLATE
+            .replace("{expand_params}", expand_params)
+            .replace("{parse_expand}", &self.generate_parse_expand(entity, "expand_params"))
+            .replace("{response}", &self.generate_response(entity, &entity.name, &to_snake_case(&entity.name), 12))
+            .replace("{primary_key_type}", &entity.primary_key_type())
             .replace("{sc_entity_name}", to_snake_case(&entity.name).as_str())
             .replace("{sc_plural_entity}", to_snake_case(&entity.plural_name).as_str())
             .replace("{plural_entity}", entity.plural_name.as_str())
@@ -169,13 +558,14 @@ pub trait ControllerGenerator: ImportGenerator {
     }
 
     fn generate_get_paginated_fn(&self, entity: &Entity) -> String {
-        let filters = entity.filter_by.iter().map(|filter_by| {
+        let filters = self.generate_search(entity) + &self.generate_reject_combined_range_filter(entity) + &entity.filters().iter().map(|filter_by| {
             let filter_by_fields = filter_by.iter().map(|field| {
                 format!("&filter_params.{}.unwrap()", field)
             }).collect::<Vec<String>>().join(", ");
             let most_specific_filter_by = filter_by.last().unwrap();
             if filter_by.iter().filter(|field| entity.is_unique(field)).count() > 0 {
                 FILTER_BY_TEMPLATE
+                .replace("{response}", &self.generate_response(entity, &entity.name, &to_snake_case(&entity.name), 16))
                 .replace("{attribute_name}", most_specific_filter_by)
                 .replace("{filter_by_fields}", &filter_by_fields)
                 .replace("{sc_plural_entity}", to_snake_case(&entity.plural_name).as_str())
@@ -185,6 +575,9 @@ pub trait ControllerGenerator: ImportGenerator {
 
             } else {
                 FILTER_BY_PAGINATED_TEMPLATE
+                .replace("{sort_arg}", &self.generate_sort_arg(entity, 20))
+                .replace("{paging_args}", &self.generate_paging_args(entity, self.list_paging_params(entity), 20))
+                .replace("{response}", &self.generate_response(entity, &self.page_variant(entity), &to_snake_case(&entity.plural_name), 16))
                 .replace("{attribute_name}", most_specific_filter_by)
                 .replace("{filter_by_fields}", &filter_by_fields)
                 .replace("{sc_plural_entity}", to_snake_case(&entity.plural_name).as_str())
@@ -194,9 +587,20 @@ pub trait ControllerGenerator: ImportGenerator {
 
             }
            
-        }).collect::<Vec<String>>().join("\n");
+        }).collect::<Vec<String>>().join("\n") + &self.generate_match_filters(entity) + &self.generate_filter_by_range(entity);
         
+        let (get_page, cursor_params) = match entity.cursor_by {
+            Some(_) => (GET_PAGE_AFTER_CURSOR_TEMPLATE, CONTROLLER_CURSOR_PARAMS_TEMPLATE),
+            None => (GET_PAGE_TEMPLATE, ""),
+        };
         CONTROLLER_GET_PAGINATED_ENTITIES_TEMPLATE
+            .replace("{get_page}", get_page)
+            .replace("{cursor_params}", cursor_params)
+            .replace("{reject_page}", &self.generate_reject_page(entity, "cursor_params"))
+            .replace("{parse_expand}", &self.generate_parse_expand(entity, "filter_params"))
+            .replace("{parse_sort}", &self.generate_parse_sort(entity, "filter_params"))
+            .replace("{sort_arg}", &self.generate_sort_arg(entity, 16))
+            .replace("{response}", &self.generate_response(entity, &self.page_variant(entity), &to_snake_case(&entity.plural_name), 12))
             .replace("{sc_entity_name}", to_snake_case(&entity.name).as_str())
             .replace("{sc_plural_entity}", to_snake_case(&entity.plural_name).as_str())
             .replace("{entity_name}", &entity.name)
@@ -204,8 +608,84 @@ pub trait ControllerGenerator: ImportGenerator {
             .replace("{plural_entity}", entity.plural_name.as_str())
     }
 
+    /**
+     * A search takes precedence over the filters, which narrow down the list of entities rather than ranking them.
+     */
+    fn generate_search(&self, entity: &Entity) -> String {
+        if entity.searchable.is_empty() {
+            return String::new();
+        }
+        SEARCH_TEMPLATE
+            .replace("{response}", &self.generate_response(entity, &format!("Paginated{}", entity.plural_name), &to_snake_case(&entity.plural_name), 16))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+    }
+
+    /**
+     * Match filters apply when no filter_by on equality matches.
+     */
+    fn generate_match_filters(&self, entity: &Entity) -> String {
+        entity.match_filters.iter().map(|match_filter| {
+            let param_name = match_filter.param_name();
+            FILTER_BY_PAGINATED_TEMPLATE
+                .replace("{sort_arg}", &self.generate_sort_arg(entity, 20))
+                .replace("{paging_args}", &self.generate_paging_args(entity, self.list_paging_params(entity), 20))
+                .replace("{response}", &self.generate_response(entity, &self.page_variant(entity), &to_snake_case(&entity.plural_name), 16))
+                .replace("{attribute_name}", &param_name)
+                .replace("{filter_by_fields}", &format!("&filter_params.{}.unwrap()", param_name))
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    /**
+     * The filters on equality and the match filters that are used with a range filter return a validation error.
+     */
+    fn generate_reject_combined_range_filter(&self, entity: &Entity) -> String {
+        if !self.rejects_combined_range_filter(entity) {
+            return String::new();
+        }
+        let is_other_filter = entity.filters().iter().filter_map(|filter_by| filter_by.last().cloned())
+            .chain(entity.match_filters.iter().map(|match_filter| match_filter.param_name()))
+            .map(|attribute_name| format!("filter_params.is_{}_filter()", attribute_name))
+            .collect::<Vec<String>>().join(" || ");
+        REJECT_COMBINED_RANGE_FILTER_TEMPLATE
+            .replace("{is_other_filter}", &is_other_filter)
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn rejects_combined_range_filter(&self, entity: &Entity) -> bool {
+        !entity.range_filter_by.is_empty() && (!entity.filters().is_empty() || !entity.match_filters.is_empty())
+    }
+
+    /**
+     * Range filters apply when no other filter matches, since they cannot be combined with them.
+     */
+    fn generate_filter_by_range(&self, entity: &Entity) -> String {
+        let range_attributes = entity.range_filter_attributes();
+        if range_attributes.is_empty() {
+            return String::new();
+        }
+        let range_values = range_attributes.iter().map(|(attribute_name, _)| {
+            format!("filter_params.min_{attribute_name}, filter_params.max_{attribute_name}")
+        }).collect::<Vec<String>>().join(",\n                    ");
+        FILTER_BY_RANGE_TEMPLATE
+            .replace("{sort_arg}", &self.generate_sort_arg(entity, 20))
+            .replace("{paging_args}", &self.generate_paging_args(entity, self.list_paging_params(entity), 20))
+            .replace("{response}", &self.generate_response(entity, &self.page_variant(entity), &to_snake_case(&entity.plural_name), 16))
+            .replace("{range_values}", &range_values)
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+    }
+
     fn generate_update_fn(&self, entity: &Entity) -> String {
         CONTROLLER_UPDATE_ENTITY_TEMPLATE
+            .replace("{primary_key_type}", &entity.primary_key_type())
+            .replace("{sc_entity_name}", to_snake_case(&entity.name).as_str())
+            .replace("{sc_plural_entity}", to_snake_case(&entity.plural_name).as_str())
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_replace_fn(&self, entity: &Entity) -> String {
+        CONTROLLER_REPLACE_ENTITY_TEMPLATE
+            .replace("{primary_key_type}", &entity.primary_key_type())
             .replace("{sc_entity_name}", to_snake_case(&entity.name).as_str())
             .replace("{sc_plural_entity}", to_snake_case(&entity.plural_name).as_str())
             .replace("{entity_name}", &entity.name)
@@ -213,62 +693,232 @@ pub trait ControllerGenerator: ImportGenerator {
 
     fn generate_delete_fn(&self, entity: &Entity) -> String {
         CONTROLLER_DELETE_ENTITY_TEMPLATE
+            .replace("{primary_key_type}", &entity.primary_key_type())
             .replace("{sc_entity_name}", to_snake_case(&entity.name).as_str())
             .replace("{sc_plural_entity}", to_snake_case(&entity.plural_name).as_str())
             .replace("{entity_name}", &entity.name)
     }
 
-    fn generate_create_payload(&self, entity: &Entity) -> String {
+    /**
+     * The attributes of the create payload, except for the ones that are generated or taken from the path.
+     */
+    fn payload_attributes<'a>(&self, entity: &'a Entity, excluded_attribute: Option<&str>) -> Vec<&'a (String, AttributeType)> {
+        entity.attributes.iter().filter(|(attribute_name, _)| {
+            !(entity.is_primary_key(attribute_name) && entity.generates_primary_key()) && excluded_attribute != Some(attribute_name.as_str())
+        }).collect()
+    }
+
+    fn generate_create_payload_attributes(&self, entity: &Entity, excluded_attribute: Option<&str>) -> String {
         let mut attributes = String::new();
-        for (attribute_name, attribute_type) in &entity.attributes {
-            if attribute_name == &entity.primary_key {
-                continue;
-            }
-            
-            attributes.push_str(&ATTRIBUTE_TEMPLATE
+        for (attribute_name, attribute_type) in self.payload_attributes(entity, excluded_attribute) {
+            let template = match (entity.default_value(attribute_name), attribute_type) {
+                (Some(_), AttributeType::Option(_)) => CONTROLLER_CREATE_ENTITY_NULLABLE_DEFAULT_ATTRIBUTE_TEMPLATE,
+                (Some(_), _) => CONTROLLER_CREATE_ENTITY_DEFAULT_ATTRIBUTE_TEMPLATE,
+                (None, _) => ATTRIBUTE_TEMPLATE,
+            };
+            attributes.push_str(&template
                 .replace("{attribute_name}", &attribute_name)
                 .replace("{attribute_type}", &attribute_type.to_string()));
         }
+        attributes
+    }
+
+    fn generate_create_payload(&self, entity: &Entity) -> String {
         CONTROLLER_CREATE_ENTITY_PAYLOAD_TEMPLATE
             .replace("{entity_name}", &entity.name)
-            .replace("{attributes}", &attributes)
+            .replace("{attributes}", &self.generate_create_payload_attributes(entity, None))
+    }
+
+    fn generate_nested_fns(&self, entity: &Entity) -> String {
+        entity.nested_foreign_keys().iter().map(|(attribute_name, _)| {
+            let attribute_type = entity.attribute_type(attribute_name).unwrap().filter_type().to_string();
+            let filter_fn = CONTROLLER_FILTER_NESTED_ENTITIES_TEMPLATE
+                .replace("{sort_params}", CONTROLLER_SORT_PARAMS_TEMPLATE)
+                .replace("{parse_sort}", &self.generate_parse_sort(entity, "sort_params"))
+                .replace("{sort_arg}", &self.generate_sort_arg(entity, 12))
+                .replace("{paging_args}", &self.generate_paging_args(entity, "paginated_params", 12))
+                .replace("{reject_page}", &self.generate_reject_page(entity, "paginated_params"))
+                .replace("{page_variant}", &self.page_variant(entity))
+                .replace("{paging_params}", if entity.cursor_by.is_some() { "CursorParams" } else { "PaginatedParams" })
+                .replace("{attribute_type}", &attribute_type)
+                .replace("{attribute_name}", attribute_name)
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{plural_entity}", &entity.plural_name)
+                .replace("{entity_name}", &entity.name);
+            let create_fn = CONTROLLER_CREATE_NESTED_ENTITY_TEMPLATE
+                .replace("{attribute_type}", &attribute_type)
+                .replace("{pascal_attribute_name}", &to_pascal_case(attribute_name))
+                .replace("{attribute_name}", attribute_name)
+                .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{entity_name}", &entity.name);
+            filter_fn + &create_fn
+        }).collect::<Vec<String>>().join("")
+    }
+
+    /**
+     * The payload of a nested create, which converts into the regular create payload with the foreign key from the path.
+     */
+    fn generate_nested_create_payloads(&self, entity: &Entity) -> String {
+        entity.nested_foreign_keys().iter().map(|(attribute_name, _)| {
+            let attribute_type = entity.attribute_type(attribute_name).unwrap();
+            // The foreign key is wrapped once when it is nullable, and once more when it has a default in the create payload
+            let mut foreign_key_field = attribute_name.clone();
+            if let AttributeType::Option(_) = attribute_type {
+                foreign_key_field = format!("{}: Some({})", attribute_name, attribute_name);
+            }
+            if entity.default_value(attribute_name).is_some() {
+                foreign_key_field = format!("{}: Some({})", attribute_name, foreign_key_field.rsplit(": ").next().unwrap_or_default());
+            }
+            let payload_fields = self.payload_attributes(entity, Some(attribute_name)).iter().map(|(payload_attribute_name, _)| {
+                CONTROLLER_PAYLOAD_FIELD_TEMPLATE.replace("{attribute_name}", payload_attribute_name)
+            }).collect::<String>();
+            CONTROLLER_CREATE_NESTED_ENTITY_PAYLOAD_TEMPLATE
+                .replace("{attributes}", &self.generate_create_payload_attributes(entity, Some(attribute_name)))
+                .replace("{payload_fields}", &payload_fields)
+                .replace("{foreign_key_field}", &foreign_key_field)
+                .replace("{attribute_type}", &attribute_type.filter_type().to_string())
+                .replace("{pascal_attribute_name}", &to_pascal_case(attribute_name))
+                .replace("{attribute_name}", attribute_name)
+                .replace("{entity_name}", &entity.name)
+        }).collect::<Vec<String>>().join("")
     }
     
 
+    fn generate_batch_fns(&self, entity: &Entity) -> String {
+        [CONTROLLER_CREATE_ENTITY_BATCH_TEMPLATE, CONTROLLER_UPDATE_ENTITY_BATCH_TEMPLATE, CONTROLLER_DELETE_ENTITY_BATCH_TEMPLATE].iter().map(|template| {
+            template
+                .replace("{primary_key_type}", &entity.primary_key_type())
+                .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{entity_name}", &entity.name)
+        }).collect::<String>()
+    }
+
+    fn generate_update_batch_item(&self, entity: &Entity) -> String {
+        CONTROLLER_UPDATE_ENTITY_BATCH_ITEM_TEMPLATE
+            .replace("{primary_key_type}", &entity.primary_key_type())
+            .replace("{entity_name}", &entity.name)
+    }
+
     fn generate_update_payload(&self, entity: &Entity) -> String {
         let mut attributes = String::new();
         for (attribute_name, attribute_type) in &entity.attributes {
-            if attribute_name == &entity.primary_key {
+            if entity.is_primary_key(attribute_name) {
                 continue;
             }
-            let attribute_type_str = match attribute_type {
-                AttributeType::Option(t) => t.to_string(),
-                _ => attribute_type.to_string()
+            let value_type = match attribute_type {
+                AttributeType::Option(value_type) => value_type,
+                value_type => value_type,
             };
             attributes.push_str(&CONTROLLER_UPDATE_ENTITY_ATTRIBUTE_TEMPLATE
-                .replace("{attribute_name}", &attribute_name)
-                .replace("{attribute_type}", &attribute_type_str));
+                .replace("{attribute_name}", attribute_name)
+                .replace("{attribute_type}", &value_type.to_string()));
         }
         CONTROLLER_UPDATE_ENTITY_PAYLOAD_TEMPLATE
             .replace("{entity_name}", &entity.name)
             .replace("{attributes}", &attributes)
     }
 
+    fn generate_replace_payload(&self, entity: &Entity) -> String {
+        let mut attributes = String::new();
+        for (attribute_name, attribute_type) in &entity.attributes {
+            if entity.is_primary_key(attribute_name) {
+                continue;
+            }
+            let template = match attribute_type {
+                AttributeType::Option(_) => CONTROLLER_REPLACE_ENTITY_NULLABLE_ATTRIBUTE_TEMPLATE,
+                _ => ATTRIBUTE_TEMPLATE,
+            };
+            attributes.push_str(&template
+                .replace("{attribute_name}", attribute_name)
+                .replace("{attribute_type}", &attribute_type.to_string()));
+        }
+        CONTROLLER_REPLACE_ENTITY_PAYLOAD_TEMPLATE
+            .replace("{entity_name}", &entity.name)
+            .replace("{attributes}", &attributes)
+    }
+
+    /**
+     * The controller of a relation, with the routes of both of its directions.
+     * The service takes the keys in the order in which the relation declares its entities.
+     */
+    fn generate_relation_controller(&self, relation: &Relation, entities: &[Entity]) -> String {
+        let mut imports = self.generate_uuid_imports(relation.uses_uuids()) + IMPORT_PAGINATED_PARAMS_TEMPLATE;
+        let mut controller_functions = String::new();
+        for (end_index, (end, related_end)) in relation.directions().into_iter().enumerate() {
+            let Some(related_entity) = entities.iter().find(|entity| entity.name == related_end.entity_name) else {
+                continue;
+            };
+            imports.push_str(&self.generate_model_imports(related_entity));
+            let relation_args = match end_index {
+                0 => format!("&id, &{}", related_end.attribute_name),
+                _ => format!("&{}, &id", related_end.attribute_name),
+            };
+            controller_functions.push_str(&CONTROLLER_GET_RELATED_ENTITIES_TEMPLATE
+                .replace("{attribute_type}", &end.attribute_type.to_string())
+                .replace("{sc_related_plural_entity}", &to_snake_case(&related_end.plural_name))
+                .replace("{related_plural_entity}", &related_end.plural_name)
+                .replace("{related_entity_name}", &related_end.entity_name)
+                .replace("{sc_entity_name}", &to_snake_case(&end.entity_name))
+                .replace("{sc_plural_relation}", &to_snake_case(&relation.plural_name)));
+            for template in [CONTROLLER_LINK_TEMPLATE, CONTROLLER_UNLINK_TEMPLATE] {
+                controller_functions.push_str(&template
+                    .replace("{related_attribute_name}", &related_end.attribute_name)
+                    .replace("{related_attribute_type}", &related_end.attribute_type.to_string())
+                    .replace("{attribute_type}", &end.attribute_type.to_string())
+                    .replace("{relation_args}", &relation_args)
+                    .replace("{sc_related_entity_name}", &to_snake_case(&related_end.entity_name))
+                    .replace("{sc_entity_name}", &to_snake_case(&end.entity_name))
+                    .replace("{sc_plural_relation}", &to_snake_case(&relation.plural_name)));
+            }
+        }
+
+        CONTROLLER_FILE_TEMPLATE
+            .replace("{deserialize_import}", "")
+            .replace("{imports}", &imports)
+            .replace("{controller_functions}", &controller_functions)
+            .replace("{controller_payloads}", "")
+    }
+
     fn generate_controller(&self, entity: &Entity) -> String {
         let mut controller_functions = String::new();
         controller_functions.push_str(&self.generate_create_fn(&entity));
         controller_functions.push_str(&self.generate_get_fn(&entity));
         controller_functions.push_str(&self.generate_get_paginated_fn(&entity));
         controller_functions.push_str(&self.generate_update_fn(&entity));
+        controller_functions.push_str(&self.generate_replace_fn(entity));
         controller_functions.push_str(&self.generate_delete_fn(&entity));
+        controller_functions.push_str(&self.generate_nested_fns(entity));
+        controller_functions.push_str(&self.generate_batch_fns(entity));
         
 
         let mut controller_payloads = String::new();
         controller_payloads.push_str(&self.generate_create_payload(&entity));
         controller_payloads.push_str(&self.generate_update_payload(&entity));
+        controller_payloads.push_str(&self.generate_replace_payload(entity));
+        controller_payloads.push_str(&self.generate_update_batch_item(entity));
+        controller_payloads.push_str(&self.generate_nested_create_payloads(entity));
+
+        let mut imports = self.generate_uuid_imports(entity.uses_uuids()) + &self.generate_model_imports(entity) + &self.generate_custom_type_imports(entity) + IMPORT_BATCH_TEMPLATE;
+        if !entity.foreign_keys.is_empty() {
+            imports.push_str(IMPORT_EXPAND_PARAMS_TEMPLATE);
+        }
+        if !entity.nested_foreign_keys().is_empty() {
+            imports.push_str(IMPORT_SORT_PARAMS_TEMPLATE);
+        }
+        if entity.cursor_by.is_some() {
+            imports.push_str(IMPORT_CURSOR_PARAMS_TEMPLATE);
+        } else if !entity.nested_foreign_keys().is_empty() {
+            imports.push_str(IMPORT_PAGINATED_PARAMS_TEMPLATE);
+        }
+        if entity.sortable.is_empty() || entity.cursor_by.is_some() || self.rejects_combined_range_filter(entity) {
+            imports.push_str(IMPORT_ERROR_TEMPLATE);
+        }
 
         CONTROLLER_FILE_TEMPLATE
-            .replace("{imports}", &self.generate_model_imports(&entity))
+            .replace("{deserialize_import}", IMPORT_DESERIALIZE_TEMPLATE)
+            .replace("{imports}", &imports)
             .replace("{controller_functions}", &controller_functions)
             .replace("{controller_payloads}", &controller_payloads)
     }
@@ -8,6 +8,10 @@ pub static ENTITY_DOES_NOT_EXIST_ERROR_TEMPLATE: &str = r#"{entity_name}DoesNotE
 pub static ENTITY_UPDATE_ERROR_TEMPLATE: &str = r#"{entity_name}UpdateError(String)"#;
 pub static ENTITY_DELETION_ERROR_TEMPLATE: &str = r#"{entity_name}DeleteError(String)"#;
 pub static ENTITY_FETCH_ERROR_TEMPLATE: &str = r#"{entity_name}FetchError(String)"#;
+pub static ENTITY_VALIDATION_ERROR_TEMPLATE: &str = r#"{entity_name}ValidationError(String)"#;
+pub static ENTITY_INVALID_REFERENCE_ERROR_TEMPLATE: &str = r#"{entity_name}InvalidReference(String)"#;
+pub static ENTITY_STILL_REFERENCED_ERROR_TEMPLATE: &str = r#"{entity_name}StillReferenced(String)"#;
+pub static ENTITY_REFERENCE_NOT_FOUND_ERROR_TEMPLATE: &str = r#"{entity_name}ReferenceNotFound(String)"#;
 
 pub static CLIENT_ENTITY_ALREADY_EXISTS_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_ALREADY_EXISTS"#;
 pub static CLIENT_ENTITY_CREATION_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_CREATION_ERROR"#;
@@ -15,6 +19,10 @@ pub static CLIENT_ENTITY_DOES_NOT_EXIST_ERROR_TEMPLATE: &str = r#"{usc_entity_na
 pub static CLIENT_ENTITY_UPDATE_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_UPDATE_ERROR"#;
 pub static CLIENT_ENTITY_DELETION_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_DELETION_ERROR"#;
 pub static CLIENT_ENTITY_FETCH_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_FETCH_ERROR"#;
+pub static CLIENT_ENTITY_VALIDATION_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_VALIDATION_ERROR"#;
+pub static CLIENT_ENTITY_INVALID_REFERENCE_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_INVALID_REFERENCE"#;
+pub static CLIENT_ENTITY_STILL_REFERENCED_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_STILL_REFERENCED"#;
+pub static CLIENT_ENTITY_REFERENCE_NOT_FOUND_ERROR_TEMPLATE: &str = r#"{usc_entity_name}_REFERENCE_NOT_FOUND"#;
 
 pub static STATIC_ERROR_ENUMS_TEMPLATE: &str = r#"
     ConfigMissing(&'static str),
@@ -44,6 +52,16 @@ pub static ERROR_TO_CLIENT_ERROR_DELETION_TEMPLATE: &str = r#"
             Error::{entity_name}DeleteError(_) => (StatusCode::INTERNAL_SERVER_ERROR, ClientError::{usc_entity_name}_DELETION_ERROR),"#;
 pub static ERROR_TO_CLIENT_ERROR_FETCH_TEMPLATE: &str = r#"
             Error::{entity_name}FetchError(_) => (StatusCode::INTERNAL_SERVER_ERROR, ClientError::{usc_entity_name}_FETCH_ERROR),"#;
+pub static ERROR_TO_CLIENT_ERROR_VALIDATION_TEMPLATE: &str = r#"
+            Error::{entity_name}ValidationError(_) => (StatusCode::UNPROCESSABLE_ENTITY, ClientError::{usc_entity_name}_VALIDATION_ERROR),"#;
+// Foreign key violations (SQLSTATE 23503): a written reference does not exist, or a deleted entity is still referenced
+pub static ERROR_TO_CLIENT_ERROR_INVALID_REFERENCE_TEMPLATE: &str = r#"
+            Error::{entity_name}InvalidReference(_) => (StatusCode::UNPROCESSABLE_ENTITY, ClientError::{usc_entity_name}_INVALID_REFERENCE),"#;
+pub static ERROR_TO_CLIENT_ERROR_STILL_REFERENCED_TEMPLATE: &str = r#"
+            Error::{entity_name}StillReferenced(_) => (StatusCode::CONFLICT, ClientError::{usc_entity_name}_STILL_REFERENCED),"#;
+// The service looks up the referenced entities before writing, and names the attribute whose reference is missing
+pub static ERROR_TO_CLIENT_ERROR_REFERENCE_NOT_FOUND_TEMPLATE: &str = r#"
+            Error::{entity_name}ReferenceNotFound(_) => (StatusCode::UNPROCESSABLE_ENTITY, ClientError::{usc_entity_name}_REFERENCE_NOT_FOUND),"#;
 
 pub static ERROR_IMPL_TEMPLATE: &str = r#"
 impl Error {
@@ -66,6 +84,10 @@ pub trait ErrorGenerator : ModelGenerator {
             error_enums.push(ENTITY_UPDATE_ERROR_TEMPLATE.replace("{entity_name}", &entity_name));
             error_enums.push(ENTITY_DELETION_ERROR_TEMPLATE.replace("{entity_name}", &entity_name));
             error_enums.push(ENTITY_FETCH_ERROR_TEMPLATE.replace("{entity_name}", &entity_name));
+            error_enums.push(ENTITY_VALIDATION_ERROR_TEMPLATE.replace("{entity_name}", &entity_name));
+            error_enums.push(ENTITY_INVALID_REFERENCE_ERROR_TEMPLATE.replace("{entity_name}", &entity_name));
+            error_enums.push(ENTITY_STILL_REFERENCED_ERROR_TEMPLATE.replace("{entity_name}", &entity_name));
+            error_enums.push(ENTITY_REFERENCE_NOT_FOUND_ERROR_TEMPLATE.replace("{entity_name}", &entity_name));
             
         }
         error_enums.push(STATIC_ERROR_ENUMS_TEMPLATE.to_string());
@@ -82,6 +104,10 @@ pub trait ErrorGenerator : ModelGenerator {
             error_enums.push(CLIENT_ENTITY_UPDATE_ERROR_TEMPLATE.replace("{usc_entity_name}", &usc_entity_name));
             error_enums.push(CLIENT_ENTITY_DELETION_ERROR_TEMPLATE.replace("{usc_entity_name}", &usc_entity_name));
             error_enums.push(CLIENT_ENTITY_FETCH_ERROR_TEMPLATE.replace("{usc_entity_name}", &usc_entity_name));
+            error_enums.push(CLIENT_ENTITY_VALIDATION_ERROR_TEMPLATE.replace("{usc_entity_name}", &usc_entity_name));
+            error_enums.push(CLIENT_ENTITY_INVALID_REFERENCE_ERROR_TEMPLATE.replace("{usc_entity_name}", &usc_entity_name));
+            error_enums.push(CLIENT_ENTITY_STILL_REFERENCED_ERROR_TEMPLATE.replace("{usc_entity_name}", &usc_entity_name));
+            error_enums.push(CLIENT_ENTITY_REFERENCE_NOT_FOUND_ERROR_TEMPLATE.replace("{usc_entity_name}", &usc_entity_name));
         }
         error_enums.push(STATIC_CLIENT_ERROR_ENUM_TEMPLATE.to_string());
         self.generate_enum("ClientError", error_enums)
@@ -109,6 +135,18 @@ pub trait ErrorGenerator : ModelGenerator {
             error_to_client_errors.push_str(&ERROR_TO_CLIENT_ERROR_FETCH_TEMPLATE
                 .replace("{entity_name}", &entity_name)
                 .replace("{usc_entity_name}", &usc_entity_name));
+            error_to_client_errors.push_str(&ERROR_TO_CLIENT_ERROR_VALIDATION_TEMPLATE
+                .replace("{entity_name}", &entity_name)
+                .replace("{usc_entity_name}", &usc_entity_name));
+            error_to_client_errors.push_str(&ERROR_TO_CLIENT_ERROR_INVALID_REFERENCE_TEMPLATE
+                .replace("{entity_name}", &entity_name)
+                .replace("{usc_entity_name}", &usc_entity_name));
+            error_to_client_errors.push_str(&ERROR_TO_CLIENT_ERROR_STILL_REFERENCED_TEMPLATE
+                .replace("{entity_name}", &entity_name)
+                .replace("{usc_entity_name}", &usc_entity_name));
+            error_to_client_errors.push_str(&ERROR_TO_CLIENT_ERROR_REFERENCE_NOT_FOUND_TEMPLATE
+                .replace("{entity_name}", &entity_name)
+                .replace("{usc_entity_name}", &usc_entity_name));
         }
         error_to_client_errors.push_str(STATIC_ERROR_TO_CLIENT_ERROR_TEMPLATE);
         ERROR_IMPL_TEMPLATE.replace("{error_to_client_errors}", &error_to_client_errors)
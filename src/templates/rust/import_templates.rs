@@ -1,4 +1,4 @@
-use crate::{models::entity::Entity, utils::naming_convention::to_snake_case};
+use crate::{models::{entity::Entity, relation::Relation}, utils::naming_convention::to_snake_case};
 
 pub static IMPORT_TEMPLATE: &str = r#"
 use crate::{import};"#;
@@ -9,10 +9,17 @@ pub static IMPORT_SOURCE_TEMPLATE: &str = r#"sources::{sc_entity_name_plural}_ta
 
 pub static IMPORT_SERVICE_TEMPLATE: &str = r#"services::{sc_entity_name_plural}_service::{entity_plural}Service"#;
 
+pub static IMPORT_ENUMS_TEMPLATE: &str = r#"models::enums::*"#;
+
+pub static IMPORT_JSON_TYPES_TEMPLATE: &str = r#"models::json_types::*"#;
+
 pub static IMPORT_CONTROLLER_TEMPLATE: &str = r#"controllers::{sc_plural_entity}_controller::*"#;
 
 pub static ERROR_IMPORT_TEMPLATE: &str = r#"use crate::error::Error;"#;
 
+pub static UUID_IMPORT_TEMPLATE: &str = r#"
+use uuid::Uuid;"#;
+
 pub trait ImportGenerator {
     fn generate_model_imports(&self, entity: &Entity) -> String {
         let import = IMPORT_MODEL_TEMPLATE
@@ -40,6 +47,82 @@ pub trait ImportGenerator {
         IMPORT_TEMPLATE.replace("{import}", &import)
     }
 
+    /**
+     * The models of the entities referenced by the foreign keys, which are embedded when they are expanded.
+     */
+    fn generate_referenced_model_imports(&self, entity: &Entity) -> String {
+        let mut referenced_entity_names: Vec<&String> = Vec::new();
+        for (_, foreign_key) in &entity.foreign_keys {
+            if foreign_key.entity_name != entity.name && !referenced_entity_names.contains(&&foreign_key.entity_name) {
+                referenced_entity_names.push(&foreign_key.entity_name);
+            }
+        }
+        referenced_entity_names.iter().map(|entity_name| {
+            IMPORT_TEMPLATE.replace("{import}", &IMPORT_MODEL_TEMPLATE.replace("{sc_entity_name}", &to_snake_case(entity_name)))
+        }).collect::<String>()
+    }
+
+    fn generate_relation_source_imports(&self, relation: &Relation) -> String {
+        let import = IMPORT_SOURCE_TEMPLATE
+            .replace("{sc_entity_name_plural}", &to_snake_case(&relation.plural_name))
+            .replace("{entity_plural}", &relation.plural_name);
+        IMPORT_TEMPLATE.replace("{import}", &import)
+    }
+
+    fn generate_relation_controller_imports(&self, relation: &Relation) -> String {
+        let import = IMPORT_CONTROLLER_TEMPLATE
+            .replace("{sc_plural_entity}", &to_snake_case(&relation.plural_name));
+        IMPORT_TEMPLATE.replace("{import}", &import)
+    }
+
+    /**
+     * Only entities that have enum attributes import the generated enums.
+     */
+    fn generate_enum_imports(&self, entity: &Entity) -> String {
+        if entity.uses_enums() {
+            IMPORT_TEMPLATE.replace("{import}", IMPORT_ENUMS_TEMPLATE)
+        } else {
+            String::new()
+        }
+    }
+
+    fn generate_json_type_imports(&self, entity: &Entity) -> String {
+        if entity.uses_json_types() {
+            IMPORT_TEMPLATE.replace("{import}", IMPORT_JSON_TYPES_TEMPLATE)
+        } else {
+            String::new()
+        }
+    }
+
+    /**
+     * Imports of the types that are generated for the whole service instead of for a single entity.
+     */
+    fn generate_custom_type_imports(&self, entity: &Entity) -> String {
+        self.generate_enum_imports(entity) + &self.generate_json_type_imports(entity)
+    }
+
+    /**
+     * Services only name the types of the values they filter on, and JSON attributes are filtered as strings.
+     */
+    fn generate_service_custom_type_imports(&self, entity: &Entity) -> String {
+        if entity.filters_on_enums() {
+            IMPORT_TEMPLATE.replace("{import}", IMPORT_ENUMS_TEMPLATE)
+        } else {
+            String::new()
+        }
+    }
+
+    /**
+     * Only the files of entities or relations with `Uuid` attributes import it, integer keys do not need it.
+     */
+    fn generate_uuid_imports(&self, uses_uuids: bool) -> String {
+        if uses_uuids {
+            UUID_IMPORT_TEMPLATE.to_string()
+        } else {
+            String::new()
+        }
+    }
+
     fn generate_error_imports(&self) -> String {
         ERROR_IMPORT_TEMPLATE.to_string()
     }
@@ -1,4 +1,4 @@
-use crate::{models::entity::Entity, utils::naming_convention::to_snake_case};
+use crate::{models::{entity::Entity, relation::Relation}, utils::naming_convention::to_snake_case};
 
 pub static MOD_TEMPLATE: &str = r#"
 pub mod {module_name};
@@ -12,6 +12,12 @@ pub static SERVICE_MOD_TEMPLATE: &str = r#"{sc_entity_name}_service"#;
 
 pub static SOURCE_MOD_TEMPLATE: &str = r#"{sc_entity_name}_table"#;
 
+pub static ENUMS_MOD_TEMPLATE: &str = r#"enums"#;
+
+pub static JSON_TYPES_MOD_TEMPLATE: &str = r#"json_types"#;
+
+pub static INTERVAL_MOD_TEMPLATE: &str = r#"interval"#;
+
 pub trait ModGenerator {
     fn generate_controller_mod(&self, entity: &Entity) -> String {
         let module_name = CONTROLLER_MOD_TEMPLATE.replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name));
@@ -28,8 +34,38 @@ pub trait ModGenerator {
         MOD_TEMPLATE.replace("{module_name}", &module_name)
     }
 
+    fn generate_enums_mod(&self) -> String {
+        MOD_TEMPLATE.replace("{module_name}", ENUMS_MOD_TEMPLATE)
+    }
+
+    fn generate_json_types_mod(&self) -> String {
+        MOD_TEMPLATE.replace("{module_name}", JSON_TYPES_MOD_TEMPLATE)
+    }
+
+    fn generate_interval_mod(&self) -> String {
+        MOD_TEMPLATE.replace("{module_name}", INTERVAL_MOD_TEMPLATE)
+    }
+
     fn generate_source_mod(&self, entity: &Entity) -> String {
         let module_name = SOURCE_MOD_TEMPLATE.replace("{sc_entity_name}", &to_snake_case(&entity.plural_name));
         MOD_TEMPLATE.replace("{module_name}", &module_name)
     }
+
+    /**
+     * A relation has a controller, a service and a source of its own, named after its join table.
+     */
+    fn generate_relation_controller_mod(&self, relation: &Relation) -> String {
+        let module_name = CONTROLLER_MOD_TEMPLATE.replace("{sc_plural_entity}", &to_snake_case(&relation.plural_name));
+        MOD_TEMPLATE.replace("{module_name}", &module_name)
+    }
+
+    fn generate_relation_service_mod(&self, relation: &Relation) -> String {
+        let module_name = SERVICE_MOD_TEMPLATE.replace("{sc_entity_name}", &to_snake_case(&relation.plural_name));
+        MOD_TEMPLATE.replace("{module_name}", &module_name)
+    }
+
+    fn generate_relation_source_mod(&self, relation: &Relation) -> String {
+        let module_name = SOURCE_MOD_TEMPLATE.replace("{sc_entity_name}", &to_snake_case(&relation.plural_name));
+        MOD_TEMPLATE.replace("{module_name}", &module_name)
+    }
 }
\ No newline at end of file
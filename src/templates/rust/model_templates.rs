@@ -1,9 +1,9 @@
-use crate::{models::entity::{Entity, AttributeType}, utils::naming_convention::to_snake_case};
+use crate::{models::{entity::{Entity, AttributeType, JsonTypeName, PrimaryKeyGeneration}, enum_type::EnumType}, utils::naming_convention::to_snake_case};
 
 use super::import_templates::ImportGenerator;
 
 pub static STRUCT_TEMPLATE: &str = r#"
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct {struct_name} {
     {attributes}
 }
@@ -11,34 +11,87 @@ pub struct {struct_name} {
 
 pub static NEW_FROM_PAYLOAD_TEMPLATE: &str = r#"
     pub fn new(payload: Add{entity_name}Payload) -> Result<Self, Error> {
-        Ok(Self {
-            {primary_key}: Uuid::new_v4(),
+        let {sc_entity_name} = Self {{generated_primary_key}
             {new_attribute_from_payload}
-        })
+        };
+        {sc_entity_name}.validate()?;
+        Ok({sc_entity_name})
     }
 "#;
 
+pub static GENERATED_PRIMARY_KEY: &str = r#"
+            {primary_key}: Uuid::new_v4(),"#;
+
+pub static IDENTITY_PRIMARY_KEY: &str = r#"
+            // Assigned by the database when the {sc_entity_name} is inserted
+            {primary_key}: 0,"#;
+
 pub static NEW_ATTRIBUTE_FROM_PAYLOAD: &str = r#"
             {attribute_name}: payload.{attribute_name},"#;
 
+pub static NEW_ATTRIBUTE_FROM_PAYLOAD_WITH_DEFAULT: &str = r#"
+            {attribute_name}: payload.{attribute_name}.unwrap_or_else(|| {default_value}),"#;
+
 pub static UPDATE_FROM_PAYLOAD_TEMPLATE: &str = r#"
     pub fn update(self, payload: Update{entity_name}Payload) -> Result<Self, Error> {
-        Ok(Self {
-            {primary_key}: self.{primary_key},
+        let {sc_entity_name} = Self {{primary_key_from_self}
             {update_attribute_from_payload}
-        })
+        };
+        {sc_entity_name}.validate()?;
+        Ok({sc_entity_name})
     }
 "#;
 
-pub static UPDATE_ATTRIBUTE_FROM_PAYLOAD_NULLABLE: &str = r#"
-            {attribute_name}: payload.{attribute_name}.or(self.{attribute_name}),"#;
+// A replace takes every attribute from the payload, only the primary key is kept
+pub static REPLACE_FROM_PAYLOAD_TEMPLATE: &str = r#"
+    pub fn replace(self, payload: Replace{entity_name}Payload) -> Result<Self, Error> {
+        let {sc_entity_name} = Self {{primary_key_from_self}
+            {replace_attribute_from_payload}
+        };
+        {sc_entity_name}.validate()?;
+        Ok({sc_entity_name})
+    }
+"#;
+
+pub static PRIMARY_KEY_FROM_SELF: &str = r#"
+            {primary_key}: self.{primary_key},"#;
+
+// A missing nullable attribute keeps its value, and `null`, which is `Some(None)` in the payload, clears it
 pub static UPDATE_ATTRIBUTE_FROM_PAYLOAD: &str = r#"
             {attribute_name}: payload.{attribute_name}.unwrap_or(self.{attribute_name}),"#;
 
+// A required attribute cannot be cleared, `null` is a validation error
+pub static UPDATE_REQUIRED_ATTRIBUTE_FROM_PAYLOAD: &str = r#"
+            {attribute_name}: match payload.{attribute_name} {
+                Some(Some({attribute_name})) => {attribute_name},
+                Some(None) => return Err(Error::{entity_name}ValidationError("{attribute_name} cannot be null".to_string())),
+                None => self.{attribute_name},
+            },"#;
+
+// Checks the values that the database would reject, so the client gets a validation error instead
+pub static VALIDATE_TEMPLATE: &str = r#"
+    pub fn validate(&self) -> Result<(), Error> {{validations}
+        Ok(())
+    }
+"#;
+
+pub static VALIDATE_MAX_LENGTH_TEMPLATE: &str = r#"
+        if {values}.any(|value| value.chars().count() > {max_length}) {
+            return Err(Error::{entity_name}ValidationError("{attribute_name} cannot be longer than {max_length} characters".to_string()));
+        }"#;
+
+// `/v1/{plural}/batch` is routed to the batch routes, so `batch` cannot be the key of an entity
+pub static VALIDATE_BATCH_KEY_TEMPLATE: &str = r#"
+        if self.{primary_key} == "batch" {
+            return Err(Error::{entity_name}ValidationError("{primary_key} cannot be `batch`, which is the path of the batch routes".to_string()));
+        }"#;
+
 pub static ENTITY_IMPL_TEMPLATE: &str = r#"
 impl {entity_name} {
     {new_from_payload}
     {update_from_payload}
+    {replace_from_payload}
+    {validate}
 }
 "#;
 
@@ -62,6 +115,12 @@ pub static IS_FILTER_FN: &str = r#"
     }}
 "#;
 
+pub static IS_RANGE_FILTER_FN: &str = r#"
+    pub fn is_range_filter(&self) -> bool {
+       {check_if_bound_is_not_null}
+    }
+"#;
+
 pub static CHECK_IF_ATTRIBUTE_IS_NOT_NULL: &str = r#"
         self.{attribute_name}.is_some()"#;
 
@@ -77,6 +136,42 @@ pub enum {enum_name} {
 }
 "#;
 
+pub static DATABASE_ENUM_TEMPLATE: &str = r#"
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, sqlx::Type)]
+#[sqlx(type_name = "{sc_enum_name}")]
+pub enum {enum_name} {
+    {enum_values}
+}
+
+// Postgres names the array type of an enum after the enum, prefixed with an underscore
+impl sqlx::postgres::PgHasArrayType for {enum_name} {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_{sc_enum_name}")
+    }
+}
+"#;
+
+pub static DATABASE_ENUM_VALUE_TEMPLATE: &str = r#"
+    #[serde(rename = "{enum_value}")]
+    #[sqlx(rename = "{enum_value}")]
+    {enum_variant},"#;
+
+pub static ENUMS_FILE_TEMPLATE: &str = r#"
+use serde::Serialize;
+use serde::Deserialize;
+
+{enum_definitions}
+"#;
+
+// The aliases make the generated service compile, and are meant to be replaced by structs
+pub static JSON_TYPE_TEMPLATE: &str = r#"
+pub type {json_type_name} = serde_json::Value;"#;
+
+pub static JSON_TYPES_FILE_TEMPLATE: &str = r#"
+// Replace the aliases by your own structs, deriving Serialize and Deserialize, to type the JSON attributes.
+{json_type_definitions}
+"#;
+
 pub static RESPONSE_ENUM_TEMPLATE: &str = r#"
 #[derive(Deserialize, Debug, strum_macros::AsRefStr)]
 #[allow(non_camel_case_types)]
@@ -105,13 +200,127 @@ pub static ENUM_MATCH_VALUES: &str = r#"
                 {enum_name}::{entity_name}({sc_entity_name}) => {sc_entity_name}.serialize(serializer),
                 {enum_name}::Paginated{entity_plural}({sc_plural_entity}) => {sc_plural_entity}.serialize(serializer)"#;
 
+pub static CURSOR_ENUM: &str = r#"Cursor{entity_plural}(CursorPage<{entity_name}>)"#;
+pub static CURSOR_ENUM_MATCH_VALUES: &str = r#",
+                {enum_name}::Cursor{entity_plural}({sc_plural_entity}) => {sc_plural_entity}.serialize(serializer)"#;
+
+pub static EXPANDED_CURSOR_ENUM: &str = r#"CursorExpanded{entity_plural}(CursorPage<{entity_name}Expanded>)"#;
+pub static EXPANDED_CURSOR_ENUM_MATCH_VALUES: &str = r#",
+                {enum_name}::CursorExpanded{entity_plural}({sc_plural_entity}) => {sc_plural_entity}.serialize(serializer)"#;
+
+pub static EXPANDED_SINGLE_ENUM: &str = r#"Expanded{entity_name}({entity_name}Expanded)"#;
+pub static EXPANDED_PAGINATED_ENUM: &str = r#"PaginatedExpanded{entity_plural}(PaginatedResult<{entity_name}Expanded>)"#;
+pub static EXPANDED_ENUM_MATCH_VALUES: &str = r#",
+                {enum_name}::Expanded{entity_name}({sc_entity_name}) => {sc_entity_name}.serialize(serializer),
+                {enum_name}::PaginatedExpanded{entity_plural}({sc_plural_entity}) => {sc_plural_entity}.serialize(serializer)"#;
+
+/**
+ * The foreign keys of an entity can be expanded with `?expand=owner_id,dealer_id`,
+ * which embeds the referenced entities in an `expanded` object next to the attributes.
+ */
+pub static EXPAND_TEMPLATE: &str = r#"
+#[derive(Default, Debug)]
+pub struct {entity_name}Expand {
+    {expand_flags}
+}
+
+impl {entity_name}Expand {
+    pub fn parse(expand: Option<&str>) -> Result<Self, Error> {
+        let mut {sc_entity_name}_expand = Self::default();
+        for attribute_name in expand.unwrap_or_default().split(',').map(str::trim).filter(|attribute_name| !attribute_name.is_empty()) {
+            match attribute_name {
+                {expand_matches}
+                _ => return Err(Error::{entity_name}ValidationError(format!("{} cannot be expanded", attribute_name))),
+            }
+        }
+        Ok({sc_entity_name}_expand)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !({expand_any})
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct {entity_name}Expansions {
+    {expansions}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct {entity_name}Expanded {
+    #[serde(flatten)]
+    pub {sc_entity_name}: {entity_name},
+    pub expanded: {entity_name}Expansions,
+}
+"#;
+
+pub static EXPAND_FLAG_TEMPLATE: &str = r#"
+    pub {attribute_name}: bool,"#;
+
+pub static EXPAND_MATCH_TEMPLATE: &str = r#"
+                "{attribute_name}" => {sc_entity_name}_expand.{attribute_name} = true,"#;
+
+pub static EXPANSION_TEMPLATE: &str = r#"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub {attribute_name}: Option<{referenced_entity_name}>,"#;
+
+/**
+ * The list routes of a sortable entity are sorted with `?sort=-created_at`, where a leading `-` sorts in descending order.
+ * Every sort has its own query, so only the sortable attributes are accepted.
+ */
+pub static SORT_TEMPLATE: &str = r#"
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum {entity_name}Sort {
+    #[default]
+    PrimaryKey,{sort_variants}
+}
+
+impl {entity_name}Sort {
+    pub fn parse(sort: Option<&str>) -> Result<Self, Error> {
+        match sort.map(str::trim).unwrap_or_default() {
+            "" => Ok(Self::PrimaryKey),{sort_matches}
+            sort => Err(Error::{entity_name}ValidationError(format!("{} cannot be sorted on, the sortable attributes are {sortable_attributes}", sort))),
+        }
+    }
+}
+"#;
+
+pub static SORT_VARIANT_TEMPLATE: &str = r#"
+    {variant_name},"#;
+
+pub static SORT_MATCH_TEMPLATE: &str = r#"
+            "{param_value}" => Ok(Self::{variant_name}),"#;
+
+/**
+ * The keys of the last entity of a page, from which the next page of an entity listed with cursors starts.
+ */
+pub static CURSOR_TEMPLATE: &str = r#"
+#[derive(Serialize, Deserialize, Debug)]
+pub struct {entity_name}Cursor {
+    {attributes}
+}
+
+impl {entity_name}Cursor {
+    pub fn of({sc_entity_name}: &{entity_name}) -> Self {
+        Self {
+            {cursor_attributes}
+        }
+    }
+}
+"#;
+
+pub static CURSOR_ATTRIBUTE_TEMPLATE: &str = r#"
+            {attribute_name}: {sc_entity_name}.{attribute_name}.clone(),"#;
+
+pub static IMPORT_CURSOR_PAGE_TEMPLATE: &str = r#"
+use crate::models::CursorPage;"#;
+
 pub static ENUM_VALUE_TEMPLATE: &str = r#"
     {enum_value},"#;
 
 pub static MODEL_FILE_TEMPLATE: &str = r#"
 use serde::Serialize;
 use serde::Deserialize;
-use uuid::Uuid;
 use crate::error::Error;
 use crate::models::PaginatedResult;
 use serde::Serializer;
@@ -126,7 +335,7 @@ use serde::Serializer;
 {filter_impl}
 
 {response_definition}
-{response_impl}
+{response_impl}{expand_definition}{sort_definition}{cursor_definition}
 
 
 "#;
@@ -136,20 +345,26 @@ pub trait ModelGenerator: ImportGenerator {
     fn generate_model(&self, entity: &Entity) -> String {
         let model_definition = self.generate_struct(entity);
         let model_impl = self.generate_struct_impl(entity);
+        let mut imports = self.generate_uuid_imports(entity.uses_uuids()) + &self.generate_controller_imports(entity) + &self.generate_custom_type_imports(entity) + &self.generate_referenced_model_imports(entity);
+        if entity.cursor_by.is_some() {
+            imports.push_str(IMPORT_CURSOR_PAGE_TEMPLATE);
+        }
         MODEL_FILE_TEMPLATE
-            .replace("{imports}", &self.generate_controller_imports(&entity))
+            .replace("{imports}", &imports)
             .replace("{model_definition}", &model_definition)
             .replace("{model_impl}", &model_impl)
             .replace("{filter_definition}", &self.generate_filter_params(&entity))
             .replace("{filter_impl}", &self.generate_filter_params_impl(&entity))
             .replace("{response_definition}", &self.generate_response_enum(&entity))
             .replace("{response_impl}", &self.generate_response_serialize_impl(&entity))
+            .replace("{expand_definition}", &self.generate_expand(entity))
+            .replace("{sort_definition}", &self.generate_sort(entity))
+            .replace("{cursor_definition}", &self.generate_cursor(entity))
             
     }
     fn generate_struct(&self, entity: &Entity) -> String {
         let mut attributes = String::new();
-        for (key, value) in &entity.attributes {
-            let attribute_type = AttributeType::from_str(&value.to_string());
+        for (key, attribute_type) in &entity.attributes {
             attributes.push_str(&ATTRIBUTE_TEMPLATE
                 .replace("{attribute_name}", &key)
                 .replace("{attribute_type}", &attribute_type.to_string()));
@@ -162,67 +377,155 @@ pub trait ModelGenerator: ImportGenerator {
     fn generate_new_fn(&self, entity: &Entity) -> String {
         let mut new_attribute_from_payload = String::new();
         for (key, value) in &entity.attributes {
-            if key == &entity.primary_key {
+            if entity.is_primary_key(key) && entity.generates_primary_key() {
                 continue;
             }
-            new_attribute_from_payload.push_str(&NEW_ATTRIBUTE_FROM_PAYLOAD
+            let template = match entity.default_value(key) {
+                Some(default) => NEW_ATTRIBUTE_FROM_PAYLOAD_WITH_DEFAULT.replace("{default_value}", &default.rust),
+                None => NEW_ATTRIBUTE_FROM_PAYLOAD.to_string(),
+            };
+            new_attribute_from_payload.push_str(&template
                 .replace("{attribute_name}", &key)
                 .replace("{attribute_type}", &value.to_string()));
         }
+        let generated_primary_key = match entity.primary_key_generation() {
+            PrimaryKeyGeneration::Uuid => GENERATED_PRIMARY_KEY.replace("{primary_key}", &entity.primary_key[0]),
+            PrimaryKeyGeneration::Identity => IDENTITY_PRIMARY_KEY.replace("{primary_key}", &entity.primary_key[0]),
+            PrimaryKeyGeneration::Client => String::new(),
+        };
         NEW_FROM_PAYLOAD_TEMPLATE
-            .replace("{primary_key}", &entity.primary_key)
+            .replace("{generated_primary_key}", &generated_primary_key)
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
             .replace("{entity_name}", &entity.name)
             .replace("{new_attribute_from_payload}", &new_attribute_from_payload)
     }
 
     fn generate_update_fn(&self, entity: &Entity) -> String {
         let mut update_attribute_from_payload = String::new();
-        for (key, value) in &entity.attributes {
-            if key == &entity.primary_key {
+        for (key, attribute_type) in &entity.attributes {
+            if entity.is_primary_key(key) {
                 continue;
             }
-            let attribute_type = AttributeType::from_str(&value.to_string());
-            let attribute_type_str = match &attribute_type {
-                AttributeType::Option(t) => t.to_string(),
-                _ => attribute_type.to_string()
+            let template = match attribute_type {
+                AttributeType::Option(_) => UPDATE_ATTRIBUTE_FROM_PAYLOAD,
+                _ => UPDATE_REQUIRED_ATTRIBUTE_FROM_PAYLOAD,
             };
-            match attribute_type {
-                AttributeType::Option(_) => {
-                    update_attribute_from_payload.push_str(&UPDATE_ATTRIBUTE_FROM_PAYLOAD_NULLABLE
-                        .replace("{attribute_name}", &key)
-                        .replace("{attribute_type}", &attribute_type_str));
-                },
-                _ => {
-                    update_attribute_from_payload.push_str(&UPDATE_ATTRIBUTE_FROM_PAYLOAD
-                        .replace("{attribute_name}", &key)
-                        .replace("{attribute_type}", &attribute_type_str));
-                }
-            }
+            update_attribute_from_payload.push_str(&template
+                .replace("{entity_name}", &entity.name)
+                .replace("{attribute_name}", key)
+                .replace("{attribute_type}", &attribute_type.to_string()));
         }
+        let primary_key_from_self = entity.primary_key.iter().map(|primary_key| {
+            PRIMARY_KEY_FROM_SELF.replace("{primary_key}", primary_key)
+        }).collect::<String>();
         UPDATE_FROM_PAYLOAD_TEMPLATE
-            .replace("{primary_key}", &entity.primary_key)
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{primary_key_from_self}", &primary_key_from_self)
             .replace("{entity_name}", &entity.name)
             .replace("{update_attribute_from_payload}", &update_attribute_from_payload)
     }
 
+    fn generate_replace_fn(&self, entity: &Entity) -> String {
+        let replace_attribute_from_payload = entity.attributes.iter()
+            .filter(|(key, _)| !entity.is_primary_key(key))
+            .map(|(key, _)| NEW_ATTRIBUTE_FROM_PAYLOAD.replace("{attribute_name}", key))
+            .collect::<String>();
+        let primary_key_from_self = entity.primary_key.iter().map(|primary_key| {
+            PRIMARY_KEY_FROM_SELF.replace("{primary_key}", primary_key)
+        }).collect::<String>();
+        REPLACE_FROM_PAYLOAD_TEMPLATE
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{primary_key_from_self}", &primary_key_from_self)
+            .replace("{entity_name}", &entity.name)
+            .replace("{replace_attribute_from_payload}", &replace_attribute_from_payload)
+    }
+
+    fn generate_validate_fn(&self, entity: &Entity) -> String {
+        let validations = entity.attributes.iter().filter_map(|(attribute_name, attribute_type)| {
+            let max_length = attribute_type.max_length()?;
+            // Iterate over the strings, whether the attribute is a string, an option or an array of strings
+            let values = match attribute_type {
+                AttributeType::Option(inner_type) if inner_type.is_array() => format!("self.{}.iter().flatten()", attribute_name),
+                AttributeType::Option(_) | AttributeType::Vec(_) => format!("self.{}.iter()", attribute_name),
+                _ => format!("std::iter::once(&self.{})", attribute_name),
+            };
+            Some(VALIDATE_MAX_LENGTH_TEMPLATE
+                .replace("{values}", &values)
+                .replace("{max_length}", &max_length.to_string())
+                .replace("{entity_name}", &entity.name)
+                .replace("{attribute_name}", attribute_name))
+        }).collect::<Vec<String>>().join("");
+        let batch_key_validation = match entity.primary_key_attributes().as_slice() {
+            [(primary_key, attribute_type)] if attribute_type.is_string() => VALIDATE_BATCH_KEY_TEMPLATE
+                .replace("{entity_name}", &entity.name)
+                .replace("{primary_key}", primary_key),
+            _ => String::new(),
+        };
+        VALIDATE_TEMPLATE.replace("{validations}", &(batch_key_validation + &validations))
+    }
+
     fn generate_struct_impl(&self, entity: &Entity) -> String {
         let new_from_payload = self.generate_new_fn(entity);
         let update_from_payload = self.generate_update_fn(entity);
+        let replace_from_payload = self.generate_replace_fn(entity);
+        let validate = self.generate_validate_fn(entity);
         ENTITY_IMPL_TEMPLATE
             .replace("{entity_name}", &entity.name)
             .replace("{new_from_payload}", &new_from_payload)
             .replace("{update_from_payload}", &update_from_payload)
+            .replace("{replace_from_payload}", &replace_from_payload)
+            .replace("{validate}", &validate)
     }
 
     // accessors for all the fields
     fn generate_entity_value_accessors(&self, entity: &Entity) -> String {
         let mut entity_values = String::new();
-        for (field_name, _) in &entity.attributes {
-            entity_values.push_str(&format!("{}.{}, ", to_snake_case(&entity.name), field_name));
+        for (field_name, attribute_type) in entity.attributes.iter().filter(|(field_name, _)| !entity.is_database_generated(field_name)) {
+            let accessor = format!("{}.{}", to_snake_case(&entity.name), field_name);
+            entity_values.push_str(&format!("{}, ", self.generate_bind_value(&accessor, attribute_type)));
         }
         entity_values
     }
 
+    /**
+     * sqlx cannot type check bind parameters of custom types such as enums.
+     * `as _` tells query_as! to skip the check for these parameters.
+     * Arrays are bound as slices, which is the type sqlx expects for them.
+     */
+    fn generate_bind_value(&self, value: &str, attribute_type: &AttributeType) -> String {
+        let value = match attribute_type {
+            AttributeType::Vec(_) => format!("{}.as_slice()", value),
+            AttributeType::Option(inner_type) if inner_type.is_array() => format!("{}.as_deref()", value),
+            _ => value.to_string(),
+        };
+        match attribute_type.sqlx_type_override() {
+            Some(_) => format!("{} as _", value),
+            None => value,
+        }
+    }
+
+    fn generate_database_enums(&self, enums: &[EnumType]) -> String {
+        let enum_definitions = enums.iter().map(|enum_type| {
+            let enum_values = enum_type.values.iter().map(|enum_value| {
+                DATABASE_ENUM_VALUE_TEMPLATE
+                    .replace("{enum_value}", enum_value)
+                    .replace("{enum_variant}", &EnumType::variant_name(enum_value))
+            }).collect::<Vec<String>>().join("");
+            DATABASE_ENUM_TEMPLATE
+                .replace("{sc_enum_name}", &to_snake_case(&enum_type.name))
+                .replace("{enum_name}", &enum_type.name)
+                .replace("{enum_values}", &enum_values)
+        }).collect::<Vec<String>>().join("\n");
+        ENUMS_FILE_TEMPLATE.replace("{enum_definitions}", &enum_definitions)
+    }
+
+    fn generate_json_types(&self, json_type_names: &[JsonTypeName]) -> String {
+        let json_type_definitions = json_type_names.iter().map(|json_type_name| {
+            JSON_TYPE_TEMPLATE.replace("{json_type_name}", json_type_name)
+        }).collect::<Vec<String>>().join("");
+        JSON_TYPES_FILE_TEMPLATE.replace("{json_type_definitions}", &json_type_definitions)
+    }
+
     fn generate_enum(&self, name: &str, enum_values: Vec<String>) -> String {
         let mut values = String::new();
         for value in enum_values {
@@ -233,13 +536,98 @@ pub trait ModelGenerator: ImportGenerator {
             .replace("{enum_values}", &values)
     }
 
+    fn generate_expand(&self, entity: &Entity) -> String {
+        if entity.foreign_keys.is_empty() {
+            return String::new();
+        }
+        let sc_entity_name = to_snake_case(&entity.name);
+        let mut expand_flags = String::new();
+        let mut expand_matches = String::new();
+        let mut expansions = String::new();
+        for (attribute_name, foreign_key) in &entity.foreign_keys {
+            expand_flags.push_str(&EXPAND_FLAG_TEMPLATE.replace("{attribute_name}", attribute_name));
+            expand_matches.push_str(&EXPAND_MATCH_TEMPLATE
+                .replace("{attribute_name}", attribute_name)
+                .replace("{sc_entity_name}", &sc_entity_name));
+            expansions.push_str(&EXPANSION_TEMPLATE
+                .replace("{attribute_name}", attribute_name)
+                .replace("{referenced_entity_name}", &foreign_key.entity_name));
+        }
+        let expand_any = entity.foreign_keys.iter().map(|(attribute_name, _)| format!("self.{}", attribute_name)).collect::<Vec<String>>().join(" || ");
+        EXPAND_TEMPLATE
+            .replace("{expand_flags}", &expand_flags)
+            .replace("{expand_matches}", &expand_matches)
+            .replace("{expand_any}", &expand_any)
+            .replace("{expansions}", &expansions)
+            .replace("{sc_entity_name}", &sc_entity_name)
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_sort(&self, entity: &Entity) -> String {
+        if entity.sortable.is_empty() {
+            return String::new();
+        }
+        let sort_keys = entity.sort_keys();
+        let sort_variants = sort_keys.iter().map(|sort_key| {
+            SORT_VARIANT_TEMPLATE.replace("{variant_name}", &sort_key.variant_name())
+        }).collect::<String>();
+        let sort_matches = sort_keys.iter().map(|sort_key| {
+            SORT_MATCH_TEMPLATE
+                .replace("{param_value}", &sort_key.param_value())
+                .replace("{variant_name}", &sort_key.variant_name())
+        }).collect::<String>();
+        SORT_TEMPLATE
+            .replace("{sort_variants}", &sort_variants)
+            .replace("{sort_matches}", &sort_matches)
+            .replace("{sortable_attributes}", &entity.sortable.join(", "))
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_cursor(&self, entity: &Entity) -> String {
+        if entity.cursor_by.is_none() {
+            return String::new();
+        }
+        let sc_entity_name = to_snake_case(&entity.name);
+        let mut attributes = String::new();
+        let mut cursor_attributes = String::new();
+        for (attribute_name, attribute_type) in entity.cursor_attributes() {
+            attributes.push_str(&ATTRIBUTE_TEMPLATE
+                .replace("{attribute_name}", attribute_name)
+                .replace("{attribute_type}", &attribute_type.to_string()));
+            cursor_attributes.push_str(&CURSOR_ATTRIBUTE_TEMPLATE
+                .replace("{attribute_name}", attribute_name)
+                .replace("{sc_entity_name}", &sc_entity_name));
+        }
+        CURSOR_TEMPLATE
+            .replace("{attributes}", &attributes)
+            .replace("{cursor_attributes}", &cursor_attributes)
+            .replace("{sc_entity_name}", &sc_entity_name)
+            .replace("{entity_name}", &entity.name)
+    }
+
     fn generate_response_enum(&self, entity: &Entity) -> String {
-        let enum_values = vec![
+        let mut enum_values = vec![
             SINGLE_ENUM
             .replace("{entity_name}", &entity.name), 
             PAGINATED_ENUM
             .replace("{entity_name}", &entity.name)
             .replace("{entity_plural}", &&entity.plural_name)];
+        if !entity.foreign_keys.is_empty() {
+            enum_values.push(EXPANDED_SINGLE_ENUM.replace("{entity_name}", &entity.name));
+            enum_values.push(EXPANDED_PAGINATED_ENUM
+                .replace("{entity_name}", &entity.name)
+                .replace("{entity_plural}", &entity.plural_name));
+        }
+        if entity.cursor_by.is_some() {
+            enum_values.push(CURSOR_ENUM
+                .replace("{entity_name}", &entity.name)
+                .replace("{entity_plural}", &entity.plural_name));
+            if !entity.foreign_keys.is_empty() {
+                enum_values.push(EXPANDED_CURSOR_ENUM
+                    .replace("{entity_name}", &entity.name)
+                    .replace("{entity_plural}", &entity.plural_name));
+            }
+        }
         let enum_name = format!("{}Response", &entity.name);
         RESPONSE_ENUM_TEMPLATE
             .replace("{enum_name}", &enum_name)
@@ -249,7 +637,17 @@ pub trait ModelGenerator: ImportGenerator {
     fn generate_response_serialize_impl(&self, entity: &Entity) -> String {
 
 
-        let enum_match_values = ENUM_MATCH_VALUES
+        let mut enum_match_values = ENUM_MATCH_VALUES.to_string();
+        if !entity.foreign_keys.is_empty() {
+            enum_match_values.push_str(EXPANDED_ENUM_MATCH_VALUES);
+        }
+        if entity.cursor_by.is_some() {
+            enum_match_values.push_str(CURSOR_ENUM_MATCH_VALUES);
+            if !entity.foreign_keys.is_empty() {
+                enum_match_values.push_str(EXPANDED_CURSOR_ENUM_MATCH_VALUES);
+            }
+        }
+        let enum_match_values = enum_match_values
             .replace("{enum_name}", &format!("{}Response", &entity.name))
             .replace("{sc_entity_name}", &to_snake_case(&entity.name))
             .replace("{entity_name}", &entity.name)
@@ -268,7 +666,7 @@ pub trait ModelGenerator: ImportGenerator {
      * In the controllers: Check which filter you have, starting from the most specific (ie the longest filter_by vector)
      */
     fn generate_filter_params(&self, entity: &Entity) -> String {
-        let filter_attributes = entity.filter_by.iter().fold(entity.filter_by.first().unwrap().to_owned(), |attributes, filter_by| {
+        let mut filter_attributes = entity.filters().iter().fold(Vec::new(), |attributes, filter_by| {
             let mut attributes = attributes.clone();
             for attribute in filter_by {
                 if !attributes.contains(attribute) {
@@ -278,16 +676,7 @@ pub trait ModelGenerator: ImportGenerator {
             attributes
         }).iter().map(|attribute_name| {
             let attribute_type = &entity.attributes.iter().find(|(key, _)| key == attribute_name).expect(format!("attribute name: {attribute_name} not found. Entity: {entity}").as_str()).1;
-            let attribute_type_str = 
-            "Option<".to_string()
-            +
-            match attribute_type {
-                AttributeType::Option(t) => t.to_string(),
-                _ => attribute_type.to_string()
-            }
-            .as_str()
-            +
-            ">";
+            let attribute_type_str = format!("Option<{}>", attribute_type.filter_type());
             ATTRIBUTE_TEMPLATE
             .replace("{attribute_name}", &attribute_name)
             .replace("{attribute_type}", &attribute_type_str)
@@ -295,6 +684,37 @@ pub trait ModelGenerator: ImportGenerator {
             .collect::<Vec<String>>()
             .join("");
 
+        for match_filter in &entity.match_filters {
+            filter_attributes.push_str(&ATTRIBUTE_TEMPLATE
+                .replace("{attribute_name}", &match_filter.param_name())
+                .replace("{attribute_type}", "Option<String>"));
+        }
+
+        for (attribute_name, attribute_type) in entity.range_filter_attributes() {
+            for bound in ["min", "max"] {
+                filter_attributes.push_str(&ATTRIBUTE_TEMPLATE
+                    .replace("{attribute_name}", &format!("{bound}_{attribute_name}"))
+                    .replace("{attribute_type}", &format!("Option<{}>", attribute_type.filter_type())));
+            }
+        }
+
+        if !entity.searchable.is_empty() {
+            filter_attributes.push_str(&ATTRIBUTE_TEMPLATE
+                .replace("{attribute_name}", "q")
+                .replace("{attribute_type}", "Option<String>"));
+        }
+
+        // Entities that are not sortable take the sort parameter as well, to reject it
+        filter_attributes.push_str(&ATTRIBUTE_TEMPLATE
+            .replace("{attribute_name}", "sort")
+            .replace("{attribute_type}", "Option<String>"));
+
+        if !entity.foreign_keys.is_empty() {
+            filter_attributes.push_str(&ATTRIBUTE_TEMPLATE
+                .replace("{attribute_name}", "expand")
+                .replace("{attribute_type}", "Option<String>"));
+        }
+
         FILTER_PARAMS_TEMPLATE
             .replace("{entity_name}", &entity.name)
             .replace("{attributes}", filter_attributes.as_str())
@@ -302,7 +722,7 @@ pub trait ModelGenerator: ImportGenerator {
 
     fn generate_filter_params_impl(&self, entity: &Entity) -> String {
         let mut is_filter_functions = String::new();
-        for filter_by in &entity.filter_by {
+        for filter_by in &entity.filters() {
             let most_specific_attribute = filter_by.last().unwrap();
             let check_if_attributes_are_not_null = filter_by
                 .iter()
@@ -314,10 +734,79 @@ pub trait ModelGenerator: ImportGenerator {
                 .replace("{attribute_name}", &most_specific_attribute)
                 .replace("{check_if_attribute_is_not_null}", &check_if_attributes_are_not_null));
         }
+        for match_filter in &entity.match_filters {
+            let param_name = match_filter.param_name();
+            is_filter_functions.push_str(&IS_FILTER_FN
+                .replace("{attribute_name}", &param_name)
+                .replace("{check_if_attribute_is_not_null}", &CHECK_IF_ATTRIBUTE_IS_NOT_NULL.replace("{attribute_name}", &param_name)));
+        }
+        let range_attributes = entity.range_filter_attributes();
+        if !range_attributes.is_empty() {
+            let check_if_bounds_are_not_null = range_attributes
+                .iter()
+                .flat_map(|(attribute_name, _)| ["min", "max"].map(|bound| CHECK_IF_ATTRIBUTE_IS_NOT_NULL
+                    .replace("{attribute_name}", &format!("{bound}_{attribute_name}"))))
+                .collect::<Vec<String>>()
+                .join(" || ");
+            is_filter_functions.push_str(&IS_RANGE_FILTER_FN
+                .replace("{check_if_bound_is_not_null}", &check_if_bounds_are_not_null));
+        }
         FILTER_IMPL_TEMPLATE
             .replace("{entity_name}", &entity.name)
             .replace("{is_filter_functions}", &is_filter_functions)
     }
 
     
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{models::{ddr_format::DdrFormat, ddr_req::DomainDrivenRequest}, templates::rust::{controller_templates::ControllerGenerator, error_templates::ErrorGenerator}};
+
+    use super::ModelGenerator;
+
+    fn normalize(code: &str) -> String {
+        code.split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    #[test]
+    fn rejects_null_for_a_required_attribute_and_clears_a_nullable_one() {
+        let ddr = DomainDrivenRequest::try_from(DdrFormat::Yaml.parse("
+service_name: shop
+entities:
+  - User:
+      id: Uuid
+      name: String
+      nickname: Option<String>
+").unwrap()).unwrap();
+        let user = &ddr.entities[0];
+
+        let payload = normalize(&ddr.generate_update_payload(user));
+        assert!(payload.contains(r#"#[serde(default, with = "::serde_with::rust::double_option")] pub name: Option<Option<String>>,"#));
+        assert!(payload.contains(r#"#[serde(default, with = "::serde_with::rust::double_option")] pub nickname: Option<Option<String>>,"#));
+
+        let update_fn = normalize(&ModelGenerator::generate_update_fn(&ddr, user));
+        assert!(update_fn.contains(r#"Some(None) => return Err(Error::UserValidationError("name cannot be null".to_string())),"#));
+        assert!(update_fn.contains("nickname: payload.nickname.unwrap_or(self.nickname),"));
+        assert!(normalize(&ddr.generate_error_impl(vec!["User".to_string()])).contains("Error::UserValidationError(_) => (StatusCode::UNPROCESSABLE_ENTITY,"));
+    }
+
+    #[test]
+    fn counts_the_characters_of_a_bounded_string() {
+        let ddr = DomainDrivenRequest::try_from(DdrFormat::Yaml.parse("
+service_name: shop
+entities:
+  - Country:
+      id: Uuid
+      code: String(2)
+      aliases: Vec<String(3)>
+      motto: Option<String(40)>
+      name: Text
+").unwrap()).unwrap();
+        let validate_fn = normalize(&ddr.generate_validate_fn(&ddr.entities[0]));
+        assert!(validate_fn.contains(r#"if std::iter::once(&self.code).any(|value| value.chars().count() > 2) { return Err(Error::CountryValidationError("code cannot be longer than 2 characters".to_string())); }"#));
+        assert!(validate_fn.contains("if self.aliases.iter().any(|value| value.chars().count() > 3) {"));
+        assert!(validate_fn.contains("if self.motto.iter().any(|value| value.chars().count() > 40) {"));
+        assert!(!validate_fn.contains("self.name"));
+    }
+}
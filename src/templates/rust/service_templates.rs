@@ -1,6 +1,6 @@
-use crate::{utils::naming_convention::to_snake_case, models::entity::Entity};
+use crate::{utils::naming_convention::to_snake_case, models::{entity::{AttributeType, Entity, EntityPluralName, PrimaryKeyGeneration}, relation::Relation}};
 
-use super::{import_templates::ImportGenerator, model_templates::ATTRIBUTE_TEMPLATE};
+use super::{import_templates::ImportGenerator, model_templates::ATTRIBUTE_TEMPLATE, source_templates::{RANGE_FIELD, RANGE_VALUES, SORT_FIELD}};
 
 pub static VERIFY_ENTITY_CREATION_FN: &str = r##"
     pub async fn verify_{sc_entity_name}_creation_constraints(
@@ -27,6 +27,15 @@ pub static UNIQUESS_CONSTRAINT: &str = r##"
         };
 "##;
 
+// A primary key provided by the client, such as a natural or a composite key, must not be taken yet
+pub static PRIMARY_KEY_UNIQUENESS_CONSTRAINT: &str = r##"
+        match self.{sc_plural_entity}_table.get_{sc_entity_name}({primary_key_values}).await {
+            Ok(_) => return Err(Error::{entity_name}AlreadyExists),
+            Err(sqlx::Error::RowNotFound) => (),
+            Err(e) => return Err(Error::{entity_name}FetchError(e.to_string()))
+        };
+"##;
+
 pub static EXISTENCE_CONSTRAINT: &str = r##"
         match self.get_{sc_entity_name}({primary_key}).await {
             Ok(_) => (),
@@ -34,10 +43,27 @@ pub static EXISTENCE_CONSTRAINT: &str = r##"
         };
 "##;
 
+/**
+ * A referenced entity is looked up through its own table, by the primary key or the unique attribute that is referenced.
+ */
+pub static REFERENCE_CONSTRAINT: &str = r##"
+        match self.{sc_referenced_plural_entity}_table.{referenced_getter}({reference}).await {
+            Ok(_) => (),
+            Err(sqlx::Error::RowNotFound) => return Err(Error::{entity_name}ReferenceNotFound("{attribute_name}".to_string())),
+            Err(e) => return Err(Error::{referenced_entity_name}FetchError(e.to_string()))
+        };
+"##;
+
+pub static NULLABLE_REFERENCE_CONSTRAINT: &str = r##"
+        if let Some({attribute_name}) = &{sc_entity_name}.{attribute_name} {
+            {reference_constraint}
+        }
+"##;
+
 pub static VERIFY_ENTITY_DELETE_FN: &str = r##"
     pub async fn verify_{sc_entity_name}_delete_constraints(
         &self,
-        {sc_entity_name}_id: &Uuid
+        {sc_entity_name}_id: &{primary_key_type}
     ) -> Result<(), Error> {
         {verify_constraints}
     }
@@ -53,6 +79,8 @@ pub static CREATE_ENTITY_FN: &str = r##"
 
         match self.{sc_plural_entity}_table.create_{sc_entity_name}(&{sc_entity_name}).await {
             Ok({sc_entity_name}) => Ok({sc_entity_name}),
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Err(Error::{entity_name}InvalidReference(e.to_string())),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(Error::{entity_name}AlreadyExists),
             Err(e) => Err(Error::{entity_name}CreationError(e.to_string()))
         }
     }
@@ -61,9 +89,9 @@ pub static CREATE_ENTITY_FN: &str = r##"
 pub static GET_ENTITY_FN: &str = r##"
     pub async fn get_{sc_entity_name}(
         &self,
-        {sc_entity_name}_id: &Uuid
+        {sc_entity_name}_id: &{primary_key_type}
     ) -> Result<{entity_name}, Error> {
-        match self.{sc_plural_entity}_table.get_{sc_entity_name}(&{sc_entity_name}_id).await {
+        match self.{sc_plural_entity}_table.get_{sc_entity_name}({primary_key_args}).await {
             Ok({sc_entity_name}) => Ok({sc_entity_name}),
             Err(e) => Err(Error::{entity_name}FetchError(e.to_string()))
         }
@@ -72,11 +100,11 @@ pub static GET_ENTITY_FN: &str = r##"
 
 pub static GET_PAGINATED_ENTITY_FN: &str = r##"
     pub async fn get_paginated_{sc_plural_entity}(
-        &self,
+        &self,{sort_field}
         page: i64,
         limit: i64,
     ) -> Result<PaginatedResult<{entity_name}>, Error> {
-        let {sc_plural_entity} = self.{sc_plural_entity}_table.get_paginated_{sc_plural_entity}(page, limit).await;
+        let {sc_plural_entity} = self.{sc_plural_entity}_table.get_paginated_{sc_plural_entity}({sort_arg}page, limit).await;
         match {sc_plural_entity} {
             Ok({sc_plural_entity}) => {
                 let total = self
@@ -99,6 +127,41 @@ pub static GET_PAGINATED_ENTITY_FN: &str = r##"
 
 "##;
 
+/**
+ * A page of an entity listed with cursors, unfiltered or among the entities that match a filter.
+ */
+pub static GET_AFTER_CURSOR_FN: &str = r##"
+    pub async fn {fn_name}(
+        &self,{filter_by_fields}
+        cursor: Option<&str>,
+        limit: i64,
+    ) -> Result<CursorPage<{entity_name}>, Error> {
+        let cursor = match cursor {
+            Some(cursor) => Some(decode_cursor::<{entity_name}Cursor>(cursor).ok_or_else(|| {
+                Error::{entity_name}ValidationError("The cursor is not valid".to_string())
+            })?),
+            None => None,
+        };
+        // One more {sc_entity_name} than requested is fetched, to know whether there is a next page
+        let mut {sc_plural_entity} = self
+            .{sc_plural_entity}_table
+            .{fn_name}({filter_by_args}cursor.as_ref(), limit + 1)
+            .await
+            .map_err(|e| Error::{entity_name}FetchError(e.to_string()))?;
+        let next_cursor = if {sc_plural_entity}.len() as i64 > limit {
+            {sc_plural_entity}.truncate(limit as usize);
+            {sc_plural_entity}.last().map(|{sc_entity_name}| encode_cursor(&{entity_name}Cursor::of({sc_entity_name})))
+        } else {
+            None
+        };
+        Ok(CursorPage {
+            results: {sc_plural_entity},
+            next_cursor,
+            page_size: limit,
+        })
+    }
+"##;
+
 pub static FILTER_BY_FN: &str = r##"
     pub async fn get_{sc_plural_entity}_by_{most_specific_attribute}(
         &self,
@@ -117,11 +180,11 @@ pub static FILTER_BY_FN: &str = r##"
 pub static FILTER_BY_PAGINATED_FN: &str = r##"
     pub async fn filter_{sc_plural_entity}_by_{most_specific_attribute}(
         &self,
-        {filter_by_fields},
+        {filter_by_fields},{sort_field}
         page: i64,
         limit: i64,
     ) -> Result<PaginatedResult<{entity_name}>, Error> {
-        let {sc_plural_entity} = self.{sc_plural_entity}_table.filter_{sc_plural_entity}_by_{most_specific_attribute}({filter_by_args}, page, limit).await;
+        let {sc_plural_entity} = self.{sc_plural_entity}_table.filter_{sc_plural_entity}_by_{most_specific_attribute}({filter_by_args}, {sort_arg}page, limit).await;
         match {sc_plural_entity} {
             Ok({sc_plural_entity}) => {
                 let total = self
@@ -143,12 +206,70 @@ pub static FILTER_BY_PAGINATED_FN: &str = r##"
     }
 "##;
 
+pub static FILTER_BY_RANGE_PAGINATED_FN: &str = r##"
+    pub async fn filter_{sc_plural_entity}_by_range(
+        &self,
+        {range_fields},{sort_field}
+        page: i64,
+        limit: i64,
+    ) -> Result<PaginatedResult<{entity_name}>, Error> {
+        let {sc_plural_entity} = self.{sc_plural_entity}_table.filter_{sc_plural_entity}_by_range({range_values}, {sort_arg}page, limit).await;
+        match {sc_plural_entity} {
+            Ok({sc_plural_entity}) => {
+                let total = self
+                    .{sc_plural_entity}_table
+                    .filter_{sc_plural_entity}_by_range_count({range_values})
+                    .await
+                    .map_err(|_| {
+                        Error::{entity_name}FetchError("Could not fetch the total number of {sc_plural_entity}".to_string())
+                    })?;
+                Ok(PaginatedResult {
+                    results: {sc_plural_entity},
+                    total: total,
+                    page: page,
+                    page_size: limit,
+                })
+            }
+            Err(e) => Err(Error::{entity_name}FetchError(e.to_string())),
+        }
+    }
+"##;
+
+pub static SEARCH_PAGINATED_FN: &str = r##"
+    pub async fn search_{sc_plural_entity}(
+        &self,
+        q: &str,
+        page: i64,
+        limit: i64,
+    ) -> Result<PaginatedResult<{entity_name}>, Error> {
+        let {sc_plural_entity} = self.{sc_plural_entity}_table.search_{sc_plural_entity}(q, page, limit).await;
+        match {sc_plural_entity} {
+            Ok({sc_plural_entity}) => {
+                let total = self
+                    .{sc_plural_entity}_table
+                    .search_{sc_plural_entity}_count(q)
+                    .await
+                    .map_err(|_| {
+                        Error::{entity_name}FetchError("Could not fetch the total number of {sc_plural_entity}".to_string())
+                    })?;
+                Ok(PaginatedResult {
+                    results: {sc_plural_entity},
+                    total: total,
+                    page: page,
+                    page_size: limit,
+                })
+            }
+            Err(e) => Err(Error::{entity_name}FetchError(e.to_string())),
+        }
+    }
+"##;
+
 pub static FILTER_BY_FIELD: &str = r#"{attribute_name}: &{attribute_type}"#;
 
 pub static UPDATE_ENTITY_FN: &str = r##"
     pub async fn update_{sc_entity_name}(
         &self,
-        {sc_entity_name}_id: &Uuid,
+        {sc_entity_name}_id: &{primary_key_type},
         {sc_entity_name}_payload: Update{entity_name}Payload
     ) -> Result<{entity_name}, Error> {
         let {sc_entity_name} = self.get_{sc_entity_name}({sc_entity_name}_id).await?.update({sc_entity_name}_payload)?;
@@ -156,6 +277,26 @@ pub static UPDATE_ENTITY_FN: &str = r##"
 
         match self.{sc_plural_entity}_table.update_{sc_entity_name}(&{sc_entity_name}).await {
             Ok({sc_entity_name}) => Ok({sc_entity_name}),
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Err(Error::{entity_name}InvalidReference(e.to_string())),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(Error::{entity_name}AlreadyExists),
+            Err(e) => Err(Error::{entity_name}UpdateError(e.to_string()))
+        }
+    }
+"##;
+
+pub static REPLACE_ENTITY_FN: &str = r##"
+    pub async fn replace_{sc_entity_name}(
+        &self,
+        {sc_entity_name}_id: &{primary_key_type},
+        {sc_entity_name}_payload: Replace{entity_name}Payload
+    ) -> Result<{entity_name}, Error> {
+        let {sc_entity_name} = self.get_{sc_entity_name}({sc_entity_name}_id).await?.replace({sc_entity_name}_payload)?;
+        self.verify_{sc_entity_name}_update_constraints(&{sc_entity_name}).await?;
+
+        match self.{sc_plural_entity}_table.update_{sc_entity_name}(&{sc_entity_name}).await {
+            Ok({sc_entity_name}) => Ok({sc_entity_name}),
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Err(Error::{entity_name}InvalidReference(e.to_string())),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(Error::{entity_name}AlreadyExists),
             Err(e) => Err(Error::{entity_name}UpdateError(e.to_string()))
         }
     }
@@ -164,40 +305,282 @@ pub static UPDATE_ENTITY_FN: &str = r##"
 pub static DELETE_ENTITY_FN: &str = r##"
     pub async fn delete_{sc_entity_name}(
         &self,
-        {sc_entity_name}_id: &Uuid
+        {sc_entity_name}_id: &{primary_key_type}
     ) -> Result<(), Error> {
         self.verify_{sc_entity_name}_delete_constraints(&{sc_entity_name}_id).await?;
 
-        match self.{sc_plural_entity}_table.delete_{sc_entity_name}(&{sc_entity_name}_id).await {
+        match self.{sc_plural_entity}_table.delete_{sc_entity_name}({primary_key_args}).await {
             Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Err(Error::{entity_name}StillReferenced(e.to_string())),
             Err(e) => Err(Error::{entity_name}DeleteError(e.to_string()))
         }
     }
 "##;
 
+pub static LINK_FN: &str = r##"
+    pub async fn link(
+        &self,
+        {relation_fields}
+    ) -> Result<(), Error> {
+        match self.{sc_plural_relation}_table.link({relation_args}).await {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Err(Error::{relation_name}InvalidReference(e.to_string())),
+            Err(e) => Err(Error::{relation_name}CreationError(e.to_string()))
+        }
+    }
+"##;
+
+pub static UNLINK_FN: &str = r##"
+    pub async fn unlink(
+        &self,
+        {relation_fields}
+    ) -> Result<(), Error> {
+        match self.{sc_plural_relation}_table.unlink({relation_args}).await {
+            Ok(0) => Err(Error::{relation_name}DoesNotExist),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::{relation_name}DeleteError(e.to_string()))
+        }
+    }
+"##;
+
+pub static GET_RELATED_FN: &str = r##"
+    pub async fn get_{sc_related_plural_entity}_of_{sc_entity_name}(
+        &self,
+        {attribute_field},
+        page: i64,
+        limit: i64,
+    ) -> Result<PaginatedResult<{related_entity_name}>, Error> {
+        let {sc_related_plural_entity} = self.{sc_plural_relation}_table.get_{sc_related_plural_entity}_of_{sc_entity_name}({attribute_name}, page, limit).await;
+        match {sc_related_plural_entity} {
+            Ok({sc_related_plural_entity}) => {
+                let total = self
+                    .{sc_plural_relation}_table
+                    .count_{sc_related_plural_entity}_of_{sc_entity_name}({attribute_name})
+                    .await
+                    .map_err(|_| {
+                        Error::{relation_name}FetchError("Could not fetch the total number of {sc_related_plural_entity}".to_string())
+                    })?;
+                Ok(PaginatedResult {
+                    results: {sc_related_plural_entity},
+                    total: total,
+                    page: page,
+                    page_size: limit,
+                })
+            }
+            Err(e) => Err(Error::{relation_name}FetchError(e.to_string())),
+        }
+    }
+"##;
+
+/**
+ * The referenced entities of a whole page are fetched with one query per expanded foreign key.
+ */
+pub static EXPAND_ENTITIES_FN: &str = r##"
+    pub async fn expand_{sc_plural_entity}(
+        &self,
+        {sc_plural_entity}: Vec<{entity_name}>,
+        {sc_entity_name}_expand: &{entity_name}Expand
+    ) -> Result<Vec<{entity_name}Expanded>, Error> {
+        let mut expanded_{sc_plural_entity} = {sc_plural_entity}
+            .into_iter()
+            .map(|{sc_entity_name}| {entity_name}Expanded { {sc_entity_name}, expanded: {entity_name}Expansions::default() })
+            .collect::<Vec<{entity_name}Expanded>>();
+        {expand_foreign_keys}
+        Ok(expanded_{sc_plural_entity})
+    }
+"##;
+
+pub static EXPAND_FOREIGN_KEY: &str = r#"
+        if {sc_entity_name}_expand.{attribute_name} {
+            let {attribute_name}_values = expanded_{sc_plural_entity}
+                .iter()
+                .{collect_values}(|expanded_{sc_entity_name}| expanded_{sc_entity_name}.{sc_entity_name}.{attribute_name}.clone())
+                .collect::<Vec<{attribute_type}>>();
+            let {sc_referenced_plural_entity} = self
+                .{sc_referenced_plural_entity}_table
+                .get_{sc_referenced_plural_entity}_by_{referenced_attribute_name}_in(&{attribute_name}_values)
+                .await
+                .map_err(|e| Error::{referenced_entity_name}FetchError(e.to_string()))?;
+            for expanded_{sc_entity_name} in expanded_{sc_plural_entity}.iter_mut() {
+                expanded_{sc_entity_name}.expanded.{attribute_name} = {sc_referenced_plural_entity}
+                    .iter()
+                    .find(|{sc_referenced_entity_name}| {is_referenced})
+                    .cloned();
+            }
+        }"#;
+
+/**
+ * The controllers expand a response once it is fetched, whether it holds a single entity or a page.
+ */
+pub static RESPONSE_EXPANSION_TEMPLATE: &str = r#"
+impl {entity_name}Response {
+    pub async fn expand(
+        self,
+        {sc_plural_entity}_service: &{entity_plural}Service,
+        {sc_entity_name}_expand: &{entity_name}Expand
+    ) -> Result<Self, Error> {
+        if {sc_entity_name}_expand.is_empty() {
+            return Ok(self);
+        }
+        match self {
+            {entity_name}Response::{entity_name}({sc_entity_name}) => {
+                let mut expanded_{sc_plural_entity} = {sc_plural_entity}_service.expand_{sc_plural_entity}(vec![{sc_entity_name}], {sc_entity_name}_expand).await?;
+                Ok({entity_name}Response::Expanded{entity_name}(expanded_{sc_plural_entity}.remove(0)))
+            }
+            {entity_name}Response::Paginated{entity_plural}({sc_plural_entity}) => {
+                Ok({entity_name}Response::PaginatedExpanded{entity_plural}(PaginatedResult {
+                    results: {sc_plural_entity}_service.expand_{sc_plural_entity}({sc_plural_entity}.results, {sc_entity_name}_expand).await?,
+                    total: {sc_plural_entity}.total,
+                    page: {sc_plural_entity}.page,
+                    page_size: {sc_plural_entity}.page_size,
+                }))
+            }{cursor_expansion}
+            expanded_response => Ok(expanded_response),
+        }
+    }
+}
+"#;
+
+pub static CURSOR_EXPANSION_TEMPLATE: &str = r#"
+            {entity_name}Response::Cursor{entity_plural}({sc_plural_entity}) => {
+                Ok({entity_name}Response::CursorExpanded{entity_plural}(CursorPage {
+                    results: {sc_plural_entity}_service.expand_{sc_plural_entity}({sc_plural_entity}.results, {sc_entity_name}_expand).await?,
+                    next_cursor: {sc_plural_entity}.next_cursor,
+                    page_size: {sc_plural_entity}.page_size,
+                }))
+            }"#;
+
+pub static IMPORT_PAGINATED_RESULT_TEMPLATE: &str = r#"
+use crate::models::PaginatedResult;"#;
+
+pub static IMPORT_CURSOR_TEMPLATE: &str = r#"
+use crate::models::{CursorPage, encode_cursor, decode_cursor};"#;
+
+/**
+ * Every entity of a batch is verified like a single one. An atomic batch fails with the first error and writes nothing,
+ * any other batch writes the entities that were verified and returns the outcome of every entity, in the order of the batch.
+ */
+pub static CREATE_ENTITY_BATCH_FN: &str = r##"
+    pub async fn create_{sc_entity_name}_batch(
+        &self,
+        {sc_entity_name}_payloads: Vec<Add{entity_name}Payload>,
+        atomic: bool
+    ) -> Result<Vec<Result<{entity_name}, Error>>, Error> {
+        let mut verified = Vec::with_capacity({sc_entity_name}_payloads.len());
+        for {sc_entity_name}_payload in {sc_entity_name}_payloads {
+            let verified_{sc_entity_name} = async {
+                let {sc_entity_name} = {entity_name}::new({sc_entity_name}_payload)?;
+                self.verify_{sc_entity_name}_creation_constraints(&{sc_entity_name}).await?;
+                Ok::<{entity_name}, Error>({sc_entity_name})
+            }.await;
+            match verified_{sc_entity_name} {
+                Err(e) if atomic => return Err(e),
+                verified_{sc_entity_name} => verified.push(verified_{sc_entity_name}),
+            }
+        }
+
+        // The verification runs before the batch, the constraints of the table still catch the conflicts within the batch
+        // and the changes made in the meantime
+        let creation_error = |e: sqlx::Error| match e {
+            sqlx::Error::Database(e) if e.is_unique_violation() => Error::{entity_name}AlreadyExists,
+            sqlx::Error::Database(e) if e.is_foreign_key_violation() => Error::{entity_name}InvalidReference(e.to_string()),
+            e => Error::{entity_name}CreationError(e.to_string())
+        };
+        let {sc_entity_name}_batch = verified.iter().filter_map(|verified_{sc_entity_name}| verified_{sc_entity_name}.as_ref().ok()).collect::<Vec<&{entity_name}>>();
+        let mut created_{sc_plural_entity} = self.{sc_plural_entity}_table.create_{sc_entity_name}_batch(&{sc_entity_name}_batch, atomic).await.map_err(creation_error)?.into_iter();
+        Ok(verified.into_iter().map(|verified_{sc_entity_name}| {
+            verified_{sc_entity_name}.and_then(|_| created_{sc_plural_entity}.next().unwrap().map_err(creation_error))
+        }).collect())
+    }
+"##;
+
+pub static UPDATE_ENTITY_BATCH_FN: &str = r##"
+    pub async fn update_{sc_entity_name}_batch(
+        &self,
+        {sc_entity_name}_items: Vec<Update{entity_name}BatchItem>,
+        atomic: bool
+    ) -> Result<Vec<Result<{entity_name}, Error>>, Error> {
+        let mut verified = Vec::with_capacity({sc_entity_name}_items.len());
+        for {sc_entity_name}_item in {sc_entity_name}_items {
+            let verified_{sc_entity_name} = async {
+                let {sc_entity_name}_id = {sc_entity_name}_item.id;
+                // An unknown key is reported as in a delete batch, instead of as a failed fetch
+                let {sc_entity_name} = match self.{sc_plural_entity}_table.get_{sc_entity_name}({primary_key_args}).await {
+                    Ok({sc_entity_name}) => {sc_entity_name}.update({sc_entity_name}_item.payload)?,
+                    Err(sqlx::Error::RowNotFound) => return Err(Error::{entity_name}DoesNotExist),
+                    Err(e) => return Err(Error::{entity_name}FetchError(e.to_string())),
+                };
+                self.verify_{sc_entity_name}_update_constraints(&{sc_entity_name}).await?;
+                Ok::<{entity_name}, Error>({sc_entity_name})
+            }.await;
+            match verified_{sc_entity_name} {
+                Err(e) if atomic => return Err(e),
+                verified_{sc_entity_name} => verified.push(verified_{sc_entity_name}),
+            }
+        }
+
+        let update_error = |e: sqlx::Error| match e {
+            sqlx::Error::Database(e) if e.is_unique_violation() => Error::{entity_name}AlreadyExists,
+            sqlx::Error::Database(e) if e.is_foreign_key_violation() => Error::{entity_name}InvalidReference(e.to_string()),
+            e => Error::{entity_name}UpdateError(e.to_string())
+        };
+        let {sc_entity_name}_batch = verified.iter().filter_map(|verified_{sc_entity_name}| verified_{sc_entity_name}.as_ref().ok()).collect::<Vec<&{entity_name}>>();
+        let mut updated_{sc_plural_entity} = self.{sc_plural_entity}_table.update_{sc_entity_name}_batch(&{sc_entity_name}_batch, atomic).await.map_err(update_error)?.into_iter();
+        Ok(verified.into_iter().map(|verified_{sc_entity_name}| {
+            verified_{sc_entity_name}.and_then(|_| updated_{sc_plural_entity}.next().unwrap().map_err(update_error))
+        }).collect())
+    }
+"##;
+
+// The outcome of a deleted entity is its key
+pub static DELETE_ENTITY_BATCH_FN: &str = r##"
+    pub async fn delete_{sc_entity_name}_batch(
+        &self,
+        {sc_entity_name}_ids: Vec<{primary_key_type}>,
+        atomic: bool
+    ) -> Result<Vec<Result<{primary_key_type}, Error>>, Error> {
+        let mut verified = Vec::with_capacity({sc_entity_name}_ids.len());
+        for {sc_entity_name}_id in {sc_entity_name}_ids {
+            let verified_id = self.verify_{sc_entity_name}_delete_constraints(&{sc_entity_name}_id).await.map(|_| {sc_entity_name}_id);
+            match verified_id {
+                Err(e) if atomic => return Err(e),
+                verified_id => verified.push(verified_id),
+            }
+        }
+
+        let deletion_error = |e: sqlx::Error| match e {
+            sqlx::Error::Database(e) if e.is_foreign_key_violation() => Error::{entity_name}StillReferenced(e.to_string()),
+            e => Error::{entity_name}DeleteError(e.to_string())
+        };
+        let {sc_entity_name}_keys = verified.iter().filter_map(|verified_id| verified_id.as_ref().ok()).collect::<Vec<&{primary_key_type}>>();
+        let mut deleted_{sc_plural_entity} = self.{sc_plural_entity}_table.delete_{sc_entity_name}_batch(&{sc_entity_name}_keys, atomic).await.map_err(deletion_error)?.into_iter();
+        Ok(verified.into_iter().map(|verified_id| {
+            verified_id.and_then(|{sc_entity_name}_id| deleted_{sc_plural_entity}.next().unwrap().map(|_| {sc_entity_name}_id).map_err(deletion_error))
+        }).collect())
+    }
+"##;
+
 pub static SERVICE_FILE_TEMPLATE: &str = r#"
 use std::sync::Arc;
 
 use sqlx::{Pool, Postgres};
 {entity_imports}
 use crate::error::Error;
-use uuid::Uuid;
-use crate::models::PaginatedResult;
 
 pub struct {entity_plural}Service {
-    {sc_plural_entity}_table: {entity_plural}Table,
+    {sc_plural_entity}_table: {entity_plural}Table,{referenced_table_fields}
 }
 
 impl {entity_plural}Service {
     pub fn new(db_pool: &Arc<Pool<Postgres>>) -> Self {
         Self {
-            {sc_plural_entity}_table: {entity_plural}Table::new(db_pool.clone()),
+            {sc_plural_entity}_table: {entity_plural}Table::new(db_pool.clone()),{referenced_table_definitions}
         }
     }
 
     {service_functions}
 }
-"#;
+{response_expansion}"#;
 
 pub static SERVICES_STATE_TEMPLATE: &str = r#"
 pub struct ServicesState {
@@ -218,17 +601,91 @@ pub async fn create_services(
 }
 "#;
 
+pub static REFERENCED_TABLE_FIELD: &str = r#"
+    {sc_referenced_plural_entity}_table: {referenced_entity_plural}Table,"#;
+
+pub static REFERENCED_TABLE_DEFINITION: &str = r#"
+            {sc_referenced_plural_entity}_table: {referenced_entity_plural}Table::new(db_pool.clone()),"#;
+
 pub static SERVICE_DEFINITION: &str = r#"
 let {sc_plural_entity}_service = {sc_plural_entity}_service::{entity_plural}Service::new(&arc_pool);
 "#;
 
 
 pub trait ServiceGenerator: ImportGenerator {
+    /**
+     * Services take the primary key as a single value, a tuple for composite primary keys.
+     * The tables take every attribute of the primary key as a separate parameter.
+     */
+    fn generate_primary_key_args(&self, entity: &Entity) -> String {
+        let sc_entity_name = to_snake_case(&entity.name);
+        if !entity.has_composite_primary_key() {
+            return format!("&{}_id", sc_entity_name);
+        }
+        (0..entity.primary_key.len()).map(|index| format!("&{}_id.{}", sc_entity_name, index)).collect::<Vec<String>>().join(", ")
+    }
 
-    fn generate_verify_entity_creation_constraints_fn(&self, entity: &Entity) -> String {
+    /**
+     * The primary key of an entity in the service, as it is passed to `get_{entity}`.
+     */
+    fn generate_primary_key_of(&self, entity: &Entity) -> String {
         let sc_entity_name = to_snake_case(&entity.name);
-        // TODO: Generate constraints
-        let verify_constraints = "Ok(())".to_string();
+        if !entity.has_composite_primary_key() {
+            return format!("&{}.{}", sc_entity_name, entity.primary_key[0]);
+        }
+        let primary_key = entity.primary_key.iter().map(|primary_key| format!("{}.{}.clone()", sc_entity_name, primary_key)).collect::<Vec<String>>().join(", ");
+        format!("&({})", primary_key)
+    }
+
+    /**
+     * The entities referenced by the foreign keys of an entity, other than the entity itself.
+     * Their tables are part of the service, so that the references can be verified before writing.
+     */
+    fn referenced_entities<'a>(&self, entity: &Entity, entities: &'a [Entity]) -> Vec<&'a Entity> {
+        let mut referenced_entities: Vec<&Entity> = Vec::new();
+        for (_, foreign_key) in &entity.foreign_keys {
+            let referenced_entity = entities.iter().find(|referenced_entity| referenced_entity.name == foreign_key.entity_name);
+            if let Some(referenced_entity) = referenced_entity {
+                if referenced_entity.name != entity.name && !referenced_entities.iter().any(|known| known.name == referenced_entity.name) {
+                    referenced_entities.push(referenced_entity);
+                }
+            }
+        }
+        referenced_entities
+    }
+
+    fn generate_reference_constraints(&self, entity: &Entity, entities: &[Entity]) -> String {
+        let sc_entity_name = to_snake_case(&entity.name);
+        entity.foreign_keys.iter().filter_map(|(attribute_name, foreign_key)| {
+            let referenced_entity = entities.iter().find(|referenced_entity| referenced_entity.name == foreign_key.entity_name)?;
+            // Foreign keys reference either the primary key or a unique attribute, which has its own getter
+            let referenced_getter = if referenced_entity.is_primary_key(&foreign_key.attribute_name) {
+                format!("get_{}", to_snake_case(&referenced_entity.name))
+            } else {
+                format!("get_{}_by_{}", to_snake_case(&referenced_entity.plural_name), foreign_key.attribute_name)
+            };
+            let nullable = matches!(entity.attribute_type(attribute_name), Some(AttributeType::Option(_)));
+            let reference = if nullable { attribute_name.clone() } else { format!("&{}.{}", sc_entity_name, attribute_name) };
+            let reference_constraint = REFERENCE_CONSTRAINT
+                .replace("{sc_referenced_plural_entity}", &to_snake_case(&referenced_entity.plural_name))
+                .replace("{referenced_getter}", &referenced_getter)
+                .replace("{reference}", &reference)
+                .replace("{referenced_entity_name}", &referenced_entity.name)
+                .replace("{entity_name}", &entity.name)
+                .replace("{attribute_name}", attribute_name);
+            if !nullable {
+                return Some(reference_constraint);
+            }
+            Some(NULLABLE_REFERENCE_CONSTRAINT
+                .replace("{reference_constraint}", &reference_constraint.trim().replace('\n', "\n    "))
+                .replace("{sc_entity_name}", &sc_entity_name)
+                .replace("{attribute_name}", attribute_name))
+        }).collect::<Vec<String>>().join("")
+    }
+
+    fn generate_verify_entity_creation_constraints_fn(&self, entity: &Entity, entities: &[Entity]) -> String {
+        let sc_entity_name = to_snake_case(&entity.name);
+        let verify_constraints = self.generate_reference_constraints(entity, entities) + "Ok(())";
         let uniqueness_constraints = entity.unique_attributes.iter().map(|uniqe_attributes| {
             let most_specific_attribute = uniqe_attributes.last().unwrap();
             let attribute_names = uniqe_attributes.iter().map(|attribute_name| format!("&{}.{}", to_snake_case(&entity.name), attribute_name)).collect::<Vec<String>>().join(",");
@@ -239,27 +696,36 @@ pub trait ServiceGenerator: ImportGenerator {
                 .replace("{attribute_names}", &attribute_names)
                 .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
         }).collect::<Vec<String>>().join("\n");
+        let primary_key_uniqueness_constraint = match entity.primary_key_generation() {
+            PrimaryKeyGeneration::Client => PRIMARY_KEY_UNIQUENESS_CONSTRAINT
+                .replace("{primary_key_values}", &entity.primary_key.iter().map(|primary_key| format!("&{}.{}", sc_entity_name, primary_key)).collect::<Vec<String>>().join(", "))
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{sc_entity_name}", &sc_entity_name)
+                .replace("{entity_name}", &entity.name),
+            PrimaryKeyGeneration::Uuid | PrimaryKeyGeneration::Identity => String::new(),
+        };
         VERIFY_ENTITY_CREATION_FN
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity.name)
-            .replace("{verify_constraints}", (uniqueness_constraints + verify_constraints.as_str()).as_str())
+            .replace("{verify_constraints}", (primary_key_uniqueness_constraint + &uniqueness_constraints + verify_constraints.as_str()).as_str())
     }
 
-    fn generate_verify_entity_update_constraints_fn(&self, entity: &Entity) -> String {
+    fn generate_verify_entity_update_constraints_fn(&self, entity: &Entity, entities: &[Entity]) -> String {
         let sc_entity_name = to_snake_case(&entity.name);
-        let verify_constraints = "Ok(())".to_string();
+        let verify_constraints = self.generate_reference_constraints(entity, entities) + "Ok(())";
         let exitence_constraint  = 
             EXISTENCE_CONSTRAINT
                 .replace("{sc_entity_name}", &sc_entity_name)
                 .replace("{entity_name}", &entity.name)
-                .replace("{primary_key}", &format!("&{}.{}", to_snake_case(&entity.name), &entity.primary_key));
+                .replace("{primary_key}", &self.generate_primary_key_of(entity));
         VERIFY_ENTITY_UPDATE_FN
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity.name)
             .replace("{verify_constraints}", (exitence_constraint + verify_constraints.as_str()).as_str())
     }
 
-    fn generate_verify_entity_delete_constraints_fn(&self, entity_name: &str) -> String {
+    fn generate_verify_entity_delete_constraints_fn(&self, entity: &Entity) -> String {
+        let entity_name = &entity.name;
         let sc_entity_name = to_snake_case(entity_name);
         let verify_constraints = "Ok(())".to_string();
         let exitence_constraint  = 
@@ -268,6 +734,7 @@ pub trait ServiceGenerator: ImportGenerator {
                 .replace("{entity_name}", &entity_name)
                 .replace("{primary_key}", &format!("&{}_{}", to_snake_case(entity_name), &"id"));
         VERIFY_ENTITY_DELETE_FN
+            .replace("{primary_key_type}", &entity.primary_key_type())
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity_name)
             .replace("{verify_constraints}", (exitence_constraint + verify_constraints.as_str()).as_str())
@@ -288,18 +755,20 @@ pub trait ServiceGenerator: ImportGenerator {
         let sc_plural_entity = to_snake_case(entity.plural_name.as_str());
         
         GET_ENTITY_FN
+            .replace("{primary_key_type}", &entity.primary_key_type())
+            .replace("{primary_key_args}", &self.generate_primary_key_args(entity))
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity.name)
             .replace("{sc_plural_entity}", &sc_plural_entity)
     }
 
     fn generate_filter_by_fn(&self, entity: &Entity) -> String {
-        entity.filter_by.iter().map(|filter_by| {
+        entity.filters().iter().map(|filter_by| {
             let most_specific_attribute = filter_by.last().unwrap();
             let filter_by_fields = filter_by.iter().map(|field_name| {
                 FILTER_BY_FIELD
                     .replace("{attribute_name}", &field_name)
-                    .replace("{attribute_type}", &entity.attributes.iter().find(|(attribute_name, _)| attribute_name == field_name).unwrap().1.to_string())
+                    .replace("{attribute_type}", &entity.attributes.iter().find(|(attribute_name, _)| attribute_name == field_name).unwrap().1.filter_type().to_string())
             }).collect::<Vec<String>>().join(",\n");
             let filter_by_args = filter_by.iter().map(|field_name| "&".to_string() + field_name).collect::<Vec<String>>().join(",");
             if filter_by.iter().filter(|field_name| entity.is_unique(&field_name)).count() > 0 {
@@ -310,8 +779,13 @@ pub trait ServiceGenerator: ImportGenerator {
                 .replace("{most_specific_attribute}", &most_specific_attribute)
                 .replace("{filter_by_fields}", &filter_by_fields)
                 .replace("{filter_by_args}", &filter_by_args)
+            } else if entity.cursor_by.is_some() {
+                let fn_name = format!("filter_{}_by_{}", to_snake_case(&entity.plural_name), most_specific_attribute);
+                self.generate_get_after_cursor_fn(entity, &fn_name, &filter_by_fields, &filter_by_args)
             } else {
                 FILTER_BY_PAGINATED_FN
+                .replace("{sort_field}", self.generate_sort_field(entity))
+                .replace("{sort_arg}", self.generate_sort_arg(entity))
                 .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
                 .replace("{entity_name}", &entity.name)
                 .replace("{sc_entity_name}", &to_snake_case(&entity.name))
@@ -324,11 +798,94 @@ pub trait ServiceGenerator: ImportGenerator {
         
     }
 
+    fn generate_sort_field(&self, entity: &Entity) -> &'static str {
+        if entity.sortable.is_empty() { "" } else { SORT_FIELD }
+    }
+
+    fn generate_sort_arg(&self, entity: &Entity) -> &'static str {
+        if entity.sortable.is_empty() { "" } else { "sort, " }
+    }
+
+    fn generate_match_filter_fns(&self, entity: &Entity) -> String {
+        entity.match_filters.iter().map(|match_filter| {
+            let param_name = match_filter.param_name();
+            let filter_by_fields = FILTER_BY_FIELD.replace("{attribute_name}", &param_name).replace("{attribute_type}", "String");
+            if entity.cursor_by.is_some() {
+                let fn_name = format!("filter_{}_by_{}", to_snake_case(&entity.plural_name), param_name);
+                return self.generate_get_after_cursor_fn(entity, &fn_name, &filter_by_fields, &param_name);
+            }
+            FILTER_BY_PAGINATED_FN
+                .replace("{sort_field}", self.generate_sort_field(entity))
+                .replace("{sort_arg}", self.generate_sort_arg(entity))
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{entity_name}", &entity.name)
+                .replace("{most_specific_attribute}", &param_name)
+                .replace("{filter_by_fields}", &filter_by_fields)
+                .replace("{filter_by_args}", &param_name)
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    fn generate_filter_by_range_fn(&self, entity: &Entity) -> String {
+        let range_attributes = entity.range_filter_attributes();
+        if range_attributes.is_empty() {
+            return String::new();
+        }
+        let range_fields = range_attributes.iter().map(|(attribute_name, attribute_type)| {
+            RANGE_FIELD
+                .replace("{attribute_name}", attribute_name)
+                .replace("{attribute_type}", &attribute_type.filter_type().to_string())
+        }).collect::<Vec<String>>().join(",\n        ");
+        let range_values = range_attributes.iter().map(|(attribute_name, _)| {
+            RANGE_VALUES.replace("{attribute_name}", attribute_name)
+        }).collect::<Vec<String>>().join(", ");
+        if entity.cursor_by.is_some() {
+            let fn_name = format!("filter_{}_by_range", to_snake_case(&entity.plural_name));
+            return self.generate_get_after_cursor_fn(entity, &fn_name, &range_fields, &range_values);
+        }
+        FILTER_BY_RANGE_PAGINATED_FN
+            .replace("{sort_field}", self.generate_sort_field(entity))
+            .replace("{sort_arg}", self.generate_sort_arg(entity))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{entity_name}", &entity.name)
+            .replace("{range_fields}", &range_fields)
+            .replace("{range_values}", &range_values)
+    }
+
+    fn generate_search_fn(&self, entity: &Entity) -> String {
+        if entity.searchable.is_empty() {
+            return String::new();
+        }
+        SEARCH_PAGINATED_FN
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_get_entities_page_fn(&self, entity: &Entity) -> String {
+        if entity.cursor_by.is_none() {
+            return self.generate_get_entities_paginated_fn(entity);
+        }
+        self.generate_get_after_cursor_fn(entity, &format!("get_{}_after", to_snake_case(&entity.plural_name)), "", "")
+    }
+
+    fn generate_get_after_cursor_fn(&self, entity: &Entity, fn_name: &str, filter_by_fields: &str, filter_by_args: &str) -> String {
+        let filter_by_fields = if filter_by_fields.is_empty() { String::new() } else { format!("\n        {},", filter_by_fields) };
+        let filter_by_args = if filter_by_args.is_empty() { String::new() } else { format!("{}, ", filter_by_args) };
+        GET_AFTER_CURSOR_FN
+            .replace("{fn_name}", fn_name)
+            .replace("{filter_by_fields}", &filter_by_fields)
+            .replace("{filter_by_args}", &filter_by_args)
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{entity_name}", &entity.name)
+    }
+
     fn generate_get_entities_paginated_fn(&self, entity: &Entity) -> String {
         let sc_entity_name = to_snake_case(&entity.name.as_str());
         let sc_plural_entity = to_snake_case(&entity.plural_name.as_str());
         
         GET_PAGINATED_ENTITY_FN
+            .replace("{sort_field}", self.generate_sort_field(entity))
+            .replace("{sort_arg}", self.generate_sort_arg(entity))
             .replace("{sc_plural_entity}", &sc_plural_entity)
             .replace("{entity_name}", &entity.name)
             .replace("{sc_entity_name}", &sc_entity_name)
@@ -339,6 +896,18 @@ pub trait ServiceGenerator: ImportGenerator {
         let sc_plural_entity = to_snake_case(&entity.plural_name.as_str());
         
         UPDATE_ENTITY_FN
+            .replace("{primary_key_type}", &entity.primary_key_type())
+            .replace("{sc_entity_name}", &sc_entity_name)
+            .replace("{entity_name}", &entity.name)
+            .replace("{sc_plural_entity}", &sc_plural_entity)
+    }
+
+    fn generate_replace_entity_fn(&self, entity: &Entity) -> String {
+        let sc_entity_name = to_snake_case(&entity.name);
+        let sc_plural_entity = to_snake_case(&entity.plural_name);
+
+        REPLACE_ENTITY_FN
+            .replace("{primary_key_type}", &entity.primary_key_type())
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity.name)
             .replace("{sc_plural_entity}", &sc_plural_entity)
@@ -349,42 +918,191 @@ pub trait ServiceGenerator: ImportGenerator {
         let sc_plural_entity = to_snake_case(&entity.plural_name.as_str());
         
         DELETE_ENTITY_FN
+            .replace("{primary_key_type}", &entity.primary_key_type())
+            .replace("{primary_key_args}", &self.generate_primary_key_args(entity))
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity.name)
             .replace("{sc_plural_entity}", &sc_plural_entity)
     }
 
-    fn generate_service(&self, entity: &Entity) -> String {
-        let mut entity_imports = String::new();
+    fn generate_expand_entities_fn(&self, entity: &Entity, entities: &[Entity]) -> String {
+        if entity.foreign_keys.is_empty() {
+            return String::new();
+        }
+        let sc_entity_name = to_snake_case(&entity.name);
+        let expand_foreign_keys = entity.foreign_keys.iter().filter_map(|(attribute_name, foreign_key)| {
+            let referenced_entity = entities.iter().find(|referenced_entity| referenced_entity.name == foreign_key.entity_name)?;
+            let attribute_type = entity.attribute_type(attribute_name)?;
+            let referenced_attribute_type = referenced_entity.attribute_type(&foreign_key.attribute_name)?;
+            let sc_referenced_entity_name = to_snake_case(&referenced_entity.name);
+            // Only the keys that are set are fetched, so a nullable key is compared to the referenced attribute as an option
+            let nullable = matches!(attribute_type, AttributeType::Option(_));
+            let is_referenced = if nullable && !matches!(referenced_attribute_type, AttributeType::Option(_)) {
+                format!("Some(&{}.{}) == expanded_{}.{}.{}.as_ref()", sc_referenced_entity_name, foreign_key.attribute_name, sc_entity_name, sc_entity_name, attribute_name)
+            } else {
+                format!("{}.{} == expanded_{}.{}.{}", sc_referenced_entity_name, foreign_key.attribute_name, sc_entity_name, sc_entity_name, attribute_name)
+            };
+            Some(EXPAND_FOREIGN_KEY
+                .replace("{collect_values}", if nullable { "filter_map" } else { "map" })
+                .replace("{is_referenced}", &is_referenced)
+                .replace("{attribute_type}", &attribute_type.filter_type().to_string())
+                .replace("{sc_referenced_plural_entity}", &to_snake_case(&referenced_entity.plural_name))
+                .replace("{sc_referenced_entity_name}", &sc_referenced_entity_name)
+                .replace("{referenced_attribute_name}", &foreign_key.attribute_name)
+                .replace("{referenced_entity_name}", &referenced_entity.name)
+                .replace("{attribute_name}", attribute_name)
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{sc_entity_name}", &sc_entity_name))
+        }).collect::<Vec<String>>().join("");
+        EXPAND_ENTITIES_FN
+            .replace("{expand_foreign_keys}", &expand_foreign_keys)
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{sc_entity_name}", &sc_entity_name)
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_response_expansion(&self, entity: &Entity) -> String {
+        if entity.foreign_keys.is_empty() {
+            return String::new();
+        }
+        let cursor_expansion = if entity.cursor_by.is_some() { CURSOR_EXPANSION_TEMPLATE } else { "" };
+        RESPONSE_EXPANSION_TEMPLATE
+            .replace("{cursor_expansion}", cursor_expansion)
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{entity_plural}", &entity.plural_name)
+            .replace("{entity_name}", &entity.name)
+    }
+
+    fn generate_batch_fns(&self, entity: &Entity) -> String {
+        [CREATE_ENTITY_BATCH_FN, UPDATE_ENTITY_BATCH_FN, DELETE_ENTITY_BATCH_FN].iter().map(|template| {
+            template
+                .replace("{primary_key_args}", &self.generate_primary_key_args(entity))
+                .replace("{primary_key_type}", &entity.primary_key_type())
+                .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{entity_name}", &entity.name)
+        }).collect::<String>()
+    }
+
+    fn generate_service(&self, entity: &Entity, entities: &[Entity]) -> String {
+        let referenced_entities = self.referenced_entities(entity, entities);
+        let mut entity_imports = self.generate_uuid_imports(entity.uses_uuids());
         entity_imports.push_str(&self.generate_model_imports(&entity));
         entity_imports.push_str(&self.generate_source_imports(&entity));
         entity_imports.push_str(&self.generate_controller_imports(&entity));
+        entity_imports.push_str(&self.generate_service_custom_type_imports(entity));
+        // Entities listed with cursors only use a `PaginatedResult` in the expansion of their responses
+        if entity.cursor_by.is_none() || !entity.foreign_keys.is_empty() {
+            entity_imports.push_str(IMPORT_PAGINATED_RESULT_TEMPLATE);
+        }
+        if entity.cursor_by.is_some() {
+            entity_imports.push_str(IMPORT_CURSOR_TEMPLATE);
+        }
+        for referenced_entity in &referenced_entities {
+            entity_imports.push_str(&self.generate_source_imports(referenced_entity));
+        }
+
+        let mut referenced_table_fields = String::new();
+        let mut referenced_table_definitions = String::new();
+        for referenced_entity in &referenced_entities {
+            let sc_referenced_plural_entity = to_snake_case(&referenced_entity.plural_name);
+            referenced_table_fields.push_str(&REFERENCED_TABLE_FIELD
+                .replace("{sc_referenced_plural_entity}", &sc_referenced_plural_entity)
+                .replace("{referenced_entity_plural}", &referenced_entity.plural_name));
+            referenced_table_definitions.push_str(&REFERENCED_TABLE_DEFINITION
+                .replace("{sc_referenced_plural_entity}", &sc_referenced_plural_entity)
+                .replace("{referenced_entity_plural}", &referenced_entity.plural_name));
+        }
 
         let mut service_functions = String::new();
-        service_functions.push_str(&self.generate_verify_entity_creation_constraints_fn(&entity));
-        service_functions.push_str(&self.generate_verify_entity_update_constraints_fn(&entity));
-        service_functions.push_str(&self.generate_verify_entity_delete_constraints_fn(entity.name.as_str()));
+        service_functions.push_str(&self.generate_verify_entity_creation_constraints_fn(entity, entities));
+        service_functions.push_str(&self.generate_verify_entity_update_constraints_fn(entity, entities));
+        service_functions.push_str(&self.generate_verify_entity_delete_constraints_fn(entity));
 
         service_functions.push_str(&self.generate_create_entity_fn(&entity));
         service_functions.push_str(&self.generate_get_entity_fn(&entity));
-        service_functions.push_str(&self.generate_get_entities_paginated_fn(&entity));
+        service_functions.push_str(&self.generate_get_entities_page_fn(entity));
         service_functions.push_str(&self.generate_filter_by_fn(entity));
+        service_functions.push_str(&self.generate_match_filter_fns(entity));
+        service_functions.push_str(&self.generate_filter_by_range_fn(entity));
+        service_functions.push_str(&self.generate_search_fn(entity));
         service_functions.push_str(&self.generate_update_entity_fn(&entity));
+        service_functions.push_str(&self.generate_replace_entity_fn(entity));
         service_functions.push_str(&self.generate_delete_entity_fn(&entity));
+        service_functions.push_str(&self.generate_batch_fns(entity));
+        service_functions.push_str(&self.generate_expand_entities_fn(entity, entities));
 
         SERVICE_FILE_TEMPLATE
             .replace("{entity_imports}", &entity_imports)
+            .replace("{response_expansion}", &self.generate_response_expansion(entity))
+            .replace("{referenced_table_fields}", &referenced_table_fields)
+            .replace("{referenced_table_definitions}", &referenced_table_definitions)
             .replace("{entity_plural}", &entity.plural_name)
             .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
             .replace("{service_functions}", &service_functions)
     }
 
-    fn generate_services_state(&self, entities: Vec<&Entity>) -> String {
-        let services_as_fields = entities
+    /**
+     * The service of a join table: linking, unlinking and listing the entities related to either side.
+     */
+    fn generate_relation_service(&self, relation: &Relation, entities: &[Entity]) -> String {
+        let sc_plural_relation = to_snake_case(&relation.plural_name);
+        let relation_fields = relation.ends.iter().map(|end| {
+            FILTER_BY_FIELD
+                .replace("{attribute_name}", &end.attribute_name)
+                .replace("{attribute_type}", &end.attribute_type.to_string())
+        }).collect::<Vec<String>>().join(",\n        ");
+        let relation_args = relation.ends.iter().map(|end| end.attribute_name.clone()).collect::<Vec<String>>().join(", ");
+
+        let mut entity_imports = self.generate_uuid_imports(relation.uses_uuids()) + &self.generate_relation_source_imports(relation) + IMPORT_PAGINATED_RESULT_TEMPLATE;
+        let mut service_functions = String::new();
+        service_functions.push_str(&LINK_FN
+            .replace("{relation_fields}", &relation_fields)
+            .replace("{relation_args}", &relation_args));
+        service_functions.push_str(&UNLINK_FN
+            .replace("{relation_fields}", &relation_fields)
+            .replace("{relation_args}", &relation_args));
+        for (end, related_end) in relation.directions() {
+            let Some(related_entity) = entities.iter().find(|entity| entity.name == related_end.entity_name) else {
+                continue;
+            };
+            entity_imports.push_str(&self.generate_model_imports(related_entity));
+            service_functions.push_str(&GET_RELATED_FN
+                .replace("{attribute_field}", &FILTER_BY_FIELD
+                    .replace("{attribute_name}", &end.attribute_name)
+                    .replace("{attribute_type}", &end.attribute_type.to_string()))
+                .replace("{attribute_name}", &end.attribute_name)
+                .replace("{sc_related_plural_entity}", &to_snake_case(&related_end.plural_name))
+                .replace("{related_entity_name}", &related_end.entity_name)
+                .replace("{sc_entity_name}", &to_snake_case(&end.entity_name)));
+        }
+
+        SERVICE_FILE_TEMPLATE
+            .replace("{entity_imports}", &entity_imports)
+            .replace("{response_expansion}", "")
+            .replace("{referenced_table_fields}", "")
+            .replace("{referenced_table_definitions}", "")
+            .replace("{entity_plural}", &relation.plural_name)
+            .replace("{sc_plural_entity}", &sc_plural_relation)
+            .replace("{service_functions}", &service_functions)
+            .replace("{sc_plural_relation}", &sc_plural_relation)
+            .replace("{relation_name}", &relation.name)
+    }
+
+    /**
+     * Every entity and every relation has a service.
+     */
+    fn service_plural_names<'a>(&self, entities: Vec<&'a Entity>, relations: &'a [Relation]) -> Vec<&'a EntityPluralName> {
+        entities.into_iter().map(|entity| &entity.plural_name).chain(relations.iter().map(|relation| &relation.plural_name)).collect()
+    }
+
+    fn generate_services_state(&self, entities: Vec<&Entity>, relations: &[Relation]) -> String {
+        let services_as_fields = self.service_plural_names(entities, relations)
             .iter()
-            .map(|entity| {
-                let service_key = to_snake_case(&entity.plural_name) + "_service";
-                let service_value = to_snake_case(&entity.plural_name) + "_service::" + &entity.plural_name + "Service";
+            .map(|plural_name| {
+                let service_key = to_snake_case(plural_name) + "_service";
+                let service_value = to_snake_case(plural_name) + "_service::" + plural_name + "Service";
                 ATTRIBUTE_TEMPLATE
                     .replace("{attribute_name}", &service_key)
                     .replace("{attribute_type}", &service_value)
@@ -396,24 +1114,24 @@ pub trait ServiceGenerator: ImportGenerator {
             .replace("{services_as_fields}", &services_as_fields)
     }
 
-    fn generate_service_definition(&self, entity: &Entity) -> String {
-        let sc_plural_entity = to_snake_case(&entity.plural_name);
-        let entity_plural = &entity.plural_name;
+    fn generate_service_definition(&self, entity_plural: &str) -> String {
+        let sc_plural_entity = to_snake_case(entity_plural);
         SERVICE_DEFINITION
             .replace("{sc_plural_entity}", &sc_plural_entity)
-            .replace("{entity_plural}", &entity_plural)
+            .replace("{entity_plural}", entity_plural)
     }
 
-    fn generate_create_services_fn(&self, entities: Vec<&Entity>) -> String {
-        let service_definitions = entities
+    fn generate_create_services_fn(&self, entities: Vec<&Entity>, relations: &[Relation]) -> String {
+        let plural_names = self.service_plural_names(entities, relations);
+        let service_definitions = plural_names
             .iter()
-            .map(|entity| self.generate_service_definition(entity))
+            .map(|plural_name| self.generate_service_definition(plural_name))
             .collect::<Vec<String>>()
             .join("\n");
 
-        let service_names = entities
+        let service_names = plural_names
             .iter()
-            .map(|entity| to_snake_case(&entity.plural_name) + "_service")
+            .map(|plural_name| to_snake_case(plural_name) + "_service")
             .collect::<Vec<String>>()
             .join(",\n");
 
@@ -422,4 +1140,98 @@ pub trait ServiceGenerator: ImportGenerator {
             .replace("{services_as_fields}", &service_names)
 
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{models::{ddr_format::DdrFormat, ddr_req::DomainDrivenRequest}, templates::rust::{controller_templates::ControllerGenerator, error_templates::ErrorGenerator}};
+
+    use super::ServiceGenerator;
+
+    fn request(ddr: &str) -> DomainDrivenRequest {
+        DomainDrivenRequest::try_from(DdrFormat::Yaml.parse(ddr).unwrap()).unwrap()
+    }
+
+    fn normalize(code: &str) -> String {
+        code.split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    #[test]
+    fn looks_up_the_referenced_entities_before_writing() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Car:
+      id: Uuid
+      owned_by: User.id
+      sold_by: Option<User.email>
+  - User:
+      id: Uuid
+      email: String
+      unique_attributes: [[email]]
+      filter_by: [email]
+");
+        let car = &ddr.entities[0];
+        let creation_constraints = normalize(&ddr.generate_verify_entity_creation_constraints_fn(car, &ddr.entities));
+        let owned_by = r#"match self.users_table.get_user(&car.owned_by).await { Ok(_) => (), Err(sqlx::Error::RowNotFound) => return Err(Error::CarReferenceNotFound("owned_by".to_string())), Err(e) => return Err(Error::UserFetchError(e.to_string())) };"#;
+        let sold_by = r#"if let Some(sold_by) = &car.sold_by { match self.users_table.get_users_by_email(sold_by).await { Ok(_) => (), Err(sqlx::Error::RowNotFound) => return Err(Error::CarReferenceNotFound("sold_by".to_string())), Err(e) => return Err(Error::UserFetchError(e.to_string())) }; }"#;
+        assert!(creation_constraints.contains(owned_by));
+        assert!(creation_constraints.contains(sold_by));
+        let update_constraints = normalize(&ddr.generate_verify_entity_update_constraints_fn(car, &ddr.entities));
+        assert!(update_constraints.contains(owned_by));
+        assert!(update_constraints.contains(sold_by));
+        assert!(normalize(&ddr.generate_error_impl(vec!["Car".to_string(), "User".to_string()])).contains("Error::CarReferenceNotFound(_) => (StatusCode::UNPROCESSABLE_ENTITY, ClientError::CAR_REFERENCE_NOT_FOUND),"));
+    }
+
+    #[test]
+    fn expands_a_page_with_one_query_per_foreign_key() {
+        let ddr = request("
+service_name: shop
+entities:
+  - Car:
+      id: Uuid
+      owned_by: User.id
+      sold_by: Option<User.email>
+  - User:
+      id: Uuid
+      email: String
+      unique_attributes: [[email]]
+      filter_by: [email]
+");
+        let expand_fn = normalize(&ddr.generate_expand_entities_fn(&ddr.entities[0], &ddr.entities));
+        assert!(expand_fn.contains("if car_expand.owned_by { let owned_by_values = expanded_cars .iter() .map(|expanded_car| expanded_car.car.owned_by.clone()) .collect::<Vec<Uuid>>(); let users = self .users_table .get_users_by_id_in(&owned_by_values)"));
+        assert!(expand_fn.contains(".find(|user| user.id == expanded_car.car.owned_by)"));
+        assert!(expand_fn.contains(".filter_map(|expanded_car| expanded_car.car.sold_by.clone()) .collect::<Vec<String>>(); let users = self .users_table .get_users_by_email_in(&sold_by_values)"));
+        assert!(expand_fn.contains(".find(|user| Some(&user.email) == expanded_car.car.sold_by.as_ref())"));
+        assert_eq!(ddr.generate_expand_entities_fn(&ddr.entities[1], &ddr.entities), "");
+    }
+
+    #[test]
+    fn imports_the_custom_types_only_where_they_are_named() {
+        let ddr = request("
+service_name: fleet
+enums:
+  - CarStatus: [available, sold]
+entities:
+  - Garage:
+      id: Uuid
+      address: Json<Address>
+  - Car:
+      id: Uuid
+      status: CarStatus
+      filter_by: [status]
+relations:
+  - Parking: [Garage, Car]
+");
+        let [garage, car] = [&ddr.entities[0], &ddr.entities[1]];
+        assert!(!ddr.generate_service(garage, &ddr.entities).contains("json_types"));
+        assert!(ddr.generate_service(car, &ddr.entities).contains("use crate::models::enums::*;"));
+        assert!(ddr.generate_controller(garage).contains("use crate::models::json_types::*;"));
+        let relation_service = ddr.generate_relation_service(&ddr.relations[0], &ddr.entities);
+        let relation_controller = ddr.generate_relation_controller(&ddr.relations[0], &ddr.entities);
+        for relation_file in [relation_service, relation_controller.clone()] {
+            assert!(!relation_file.contains("enums") && !relation_file.contains("json_types"));
+        }
+        assert!(!relation_controller.contains("serde::Deserialize"));
+    }
+}
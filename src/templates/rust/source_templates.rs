@@ -1,4 +1,4 @@
-use crate::{utils::naming_convention::to_snake_case, templates::postgres::crud_query_templates::CrudQueryGenerator, models::entity::Entity};
+use crate::{utils::naming_convention::to_snake_case, templates::postgres::crud_query_templates::CrudQueryGenerator, models::{entity::{Entity, SortKey}, relation::Relation}};
 
 use super::{model_templates::ModelGenerator, import_templates::ImportGenerator};
 
@@ -24,13 +24,13 @@ pub static CREATE_ENTITY_FN: &str = r##"
 pub static GET_ENTITY_FN: &str = r##"
     pub async fn get_{sc_entity_name}(
         &self,
-        {primary_key}: &{primary_key_type}
+        {primary_key_fields}
     ) -> Result<{entity_name}, sqlx::Error> {
         let {sc_entity_name} = sqlx::query_as!(
             {entity_name},
             r#"{get_query}
             "#,
-            {primary_key}
+            {primary_key_values}
         )
         .fetch_one(self.pool.as_ref())
         .await?;
@@ -38,18 +38,19 @@ pub static GET_ENTITY_FN: &str = r##"
     }
 "##;
 
-pub static GET_PAGINATED_FN: &str = r##"
-    pub async fn get_paginated_{sc_plural_entity}(
+/**
+ * Fetches the entities referenced by a page of other entities in a single query, to expand them.
+ */
+pub static GET_BY_ATTRIBUTE_IN_FN: &str = r##"
+    pub async fn get_{sc_plural_entity}_by_{attribute_name}_in(
         &self,
-        page: i64,
-        limit: i64
+        {attribute_name}_values: &[{attribute_type}]
     ) -> Result<Vec<{entity_name}>, sqlx::Error> {
         let {sc_plural_entity} = sqlx::query_as!(
             {entity_name},
-            r#"{get_paginated_query}
+            r#"{get_by_attribute_in_query}
             "#,
-            limit,
-            (page - 1) * limit
+            {attribute_values}
         )
         .fetch_all(self.pool.as_ref())
         .await?;
@@ -57,6 +58,27 @@ pub static GET_PAGINATED_FN: &str = r##"
     }
 "##;
 
+pub static GET_PAGINATED_FN: &str = r##"
+    pub async fn get_paginated_{sc_plural_entity}(
+        &self,{sort_field}
+        {paging_fields}
+    ) -> Result<Vec<{entity_name}>, sqlx::Error> {
+        let {sc_plural_entity} = {fetch_page};
+        Ok({sc_plural_entity})
+    }
+"##;
+
+pub static GET_AFTER_CURSOR_FN: &str = r##"
+    pub async fn get_{sc_plural_entity}_after(
+        &self,
+        cursor: Option<&{entity_name}Cursor>,
+        limit: i64
+    ) -> Result<Vec<{entity_name}>, sqlx::Error> {
+        let {sc_plural_entity} = {fetch_page};
+        Ok({sc_plural_entity})
+    }
+"##;
+
 pub static FILTER_BY_FN: &str = r##"
     pub async fn get_{sc_plural_entity}_by_{most_specific_attribute}(
         &self,
@@ -77,15 +99,75 @@ pub static FILTER_BY_FN: &str = r##"
 pub static FILTER_BY_PAGINATED_FN: &str = r##"
     pub async fn filter_{sc_plural_entity}_by_{most_specific_attribute}(
         &self,
-        {filter_by_fields},
+        {filter_by_fields},{sort_field}
+        {paging_fields}
+    ) -> Result<Vec<{entity_name}>, sqlx::Error> {
+        let {sc_plural_entity} = {fetch_page};
+        Ok({sc_plural_entity})
+    }
+"##;
+
+pub static FILTER_BY_PAGINATED_COUNT_FN: &str = r##"
+    pub async fn filter_{sc_plural_entity}_by_{most_specific_attribute}_count(
+        &self,
+        {filter_by_fields}
+    ) -> Result<i64, sqlx::Error> {
+        let {sc_plural_entity}_count = sqlx::query!(
+            r#"{filter_by_paginated_count_query}
+            "#,
+            {filter_by_values}
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+        Ok({sc_plural_entity}_count.count.unwrap())
+    }
+"##;
+
+/**
+ * Bounds that are `None` are left open.
+ */
+pub static FILTER_BY_RANGE_PAGINATED_FN: &str = r##"
+    pub async fn filter_{sc_plural_entity}_by_range(
+        &self,
+        {range_fields},{sort_field}
+        {paging_fields}
+    ) -> Result<Vec<{entity_name}>, sqlx::Error> {
+        let {sc_plural_entity} = {fetch_page};
+        Ok({sc_plural_entity})
+    }
+"##;
+
+pub static FILTER_BY_RANGE_COUNT_FN: &str = r##"
+    pub async fn filter_{sc_plural_entity}_by_range_count(
+        &self,
+        {range_fields}
+    ) -> Result<i64, sqlx::Error> {
+        let {sc_plural_entity}_count = sqlx::query!(
+            r#"{filter_by_range_count_query}
+            "#,
+            {range_values}
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+        Ok({sc_plural_entity}_count.count.unwrap())
+    }
+"##;
+
+/**
+ * The best matches of the search terms come first.
+ */
+pub static SEARCH_PAGINATED_FN: &str = r##"
+    pub async fn search_{sc_plural_entity}(
+        &self,
+        q: &str,
         page: i64,
         limit: i64
     ) -> Result<Vec<{entity_name}>, sqlx::Error> {
         let {sc_plural_entity} = sqlx::query_as!(
             {entity_name},
-            r#"{filter_by_paginated_query}
+            r#"{search_paginated_query}
             "#,
-            {filter_by_values},
+            q,
             limit,
             (page - 1) * limit
         )
@@ -93,17 +175,15 @@ pub static FILTER_BY_PAGINATED_FN: &str = r##"
         .await?;
         Ok({sc_plural_entity})
     }
-"##;
 
-pub static FILTER_BY_PAGINATED_COUNT_FN: &str = r##"
-    pub async fn filter_{sc_plural_entity}_by_{most_specific_attribute}_count(
+    pub async fn search_{sc_plural_entity}_count(
         &self,
-        {filter_by_fields}
+        q: &str
     ) -> Result<i64, sqlx::Error> {
         let {sc_plural_entity}_count = sqlx::query!(
-            r#"{filter_by_paginated_count_query}
+            r#"{search_count_query}
             "#,
-            {filter_by_values}
+            q
         )
         .fetch_one(self.pool.as_ref())
         .await?;
@@ -113,6 +193,65 @@ pub static FILTER_BY_PAGINATED_COUNT_FN: &str = r##"
 
 pub static FILTER_BY_FIELD: &str = r#"{attribute_name}: &{attribute_type}"#;
 
+pub static RANGE_FIELD: &str = r#"min_{attribute_name}: Option<{attribute_type}>, max_{attribute_name}: Option<{attribute_type}>"#;
+
+pub static RANGE_VALUES: &str = r#"min_{attribute_name}, max_{attribute_name}"#;
+
+pub static SORT_FIELD: &str = r#"
+        sort: {entity_name}Sort,"#;
+
+/**
+ * Fetches a page of entities, after the values of the filter, if any.
+ */
+pub static FETCH_PAGE: &str = r##"sqlx::query_as!(
+            {entity_name},
+            r#"{query}
+            "#,{values}
+            limit,
+            (page - 1) * limit
+        )
+        .fetch_all(self.pool.as_ref())
+        .await?"##;
+
+pub static PAGING_FIELDS: &str = r#"page: i64,
+        limit: i64"#;
+
+// Entities listed with cursors are paged after the cursor of the last entity of the previous page
+pub static CURSOR_PAGING_FIELDS: &str = r#"cursor: Option<&{entity_name}Cursor>,
+        limit: i64"#;
+
+/**
+ * The first page of an entity listed with cursors has its own query, the next pages bind the cursor after the values of the filter.
+ */
+pub static FETCH_CURSOR_PAGE: &str = r##"match cursor {
+            None => sqlx::query_as!(
+                {entity_name},
+                r#"{first_page_query}
+                "#,{values}
+                limit
+            )
+            .fetch_all(self.pool.as_ref())
+            .await?,
+            Some(cursor) => sqlx::query_as!(
+                {entity_name},
+                r#"{after_cursor_query}
+                "#,{values}
+                {cursor_values},
+                limit
+            )
+            .fetch_all(self.pool.as_ref())
+            .await?,
+        }"##;
+
+/**
+ * Every sort of a sortable entity has its own query, so that the query is checked at compile time and can use an index.
+ */
+pub static FETCH_SORTED_PAGE: &str = r#"match sort {{fetch_sorts}
+        }"#;
+
+pub static FETCH_SORT: &str = r#"
+            {entity_name}Sort::{variant_name} => {fetch_page},"#;
+
 pub static GET_COUNT_FN: &str = r##"
     pub async fn get_{sc_plural_entity}_count(
         &self
@@ -149,14 +288,14 @@ pub static UPDATE_ENTITY_FN: &str = r##"
 pub static DELETE_ENTITY_FN: &str = r##"
     pub async fn delete_{sc_entity_name}(
         &self,
-        {primary_key}: &{primary_key_type}
+        {primary_key_fields}
     ) -> Result<(), sqlx::Error> {
         let mut transaction = self.pool.begin().await?;
         sqlx::query_as!(
             {entity_name},
             r#"{delete_query}
             "#,
-            {primary_key}
+            {primary_key_values}
         )
         .execute(transaction.as_mut())
         .await?;
@@ -165,9 +304,185 @@ pub static DELETE_ENTITY_FN: &str = r##"
     }
 "##;
 
+pub static LINK_FN: &str = r##"
+    pub async fn link(
+        &self,
+        {relation_fields}
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"{link_query}
+            "#,
+            {relation_values}
+        )
+        .execute(self.pool.as_ref())
+        .await?;
+        Ok(())
+    }
+"##;
+
+pub static UNLINK_FN: &str = r##"
+    pub async fn unlink(
+        &self,
+        {relation_fields}
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"{unlink_query}
+            "#,
+            {relation_values}
+        )
+        .execute(self.pool.as_ref())
+        .await?;
+        Ok(result.rows_affected())
+    }
+"##;
+
+pub static GET_RELATED_FN: &str = r##"
+    pub async fn get_{sc_related_plural_entity}_of_{sc_entity_name}(
+        &self,
+        {attribute_field},
+        page: i64,
+        limit: i64
+    ) -> Result<Vec<{related_entity_name}>, sqlx::Error> {
+        let {sc_related_plural_entity} = sqlx::query_as!(
+            {related_entity_name},
+            r#"{get_related_paginated_query}
+            "#,
+            {attribute_value},
+            limit,
+            (page - 1) * limit
+        )
+        .fetch_all(self.pool.as_ref())
+        .await?;
+        Ok({sc_related_plural_entity})
+    }
+
+    pub async fn count_{sc_related_plural_entity}_of_{sc_entity_name}(
+        &self,
+        {attribute_field}
+    ) -> Result<i64, sqlx::Error> {
+        let {sc_related_plural_entity}_count = sqlx::query!(
+            r#"{count_related_query}
+            "#,
+            {attribute_value}
+        )
+        .fetch_one(self.pool.as_ref())
+        .await?;
+        Ok({sc_related_plural_entity}_count.count.unwrap())
+    }
+"##;
+
+/**
+ * Batches are written in a single transaction, every entity under its own savepoint.
+ * An atomic batch stops at the first failed write and rolls back the whole transaction,
+ * any other batch only rolls back the failed entity and returns the outcome of every entity.
+ */
+pub static CREATE_ENTITY_BATCH_FN: &str = r##"
+    pub async fn create_{sc_entity_name}_batch(
+        &self,
+        {sc_plural_entity}: &[&{entity_name}],
+        atomic: bool
+    ) -> Result<Vec<Result<{entity_name}, sqlx::Error>>, sqlx::Error> {
+        let mut transaction = self.pool.begin().await?;
+        let mut results = Vec::with_capacity({sc_plural_entity}.len());
+        for {sc_entity_name} in {sc_plural_entity} {
+            let mut savepoint = sqlx::Acquire::begin(&mut transaction).await?;
+            let new_{sc_entity_name} = sqlx::query_as!(
+                {entity_name},
+                r#"{create_query}
+                "#,
+                {entity_values}
+            )
+            .fetch_one(savepoint.as_mut())
+            .await;
+            match new_{sc_entity_name} {
+                Ok(new_{sc_entity_name}) => {
+                    savepoint.commit().await?;
+                    results.push(Ok(new_{sc_entity_name}));
+                }
+                Err(e) if atomic => return Err(e),
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    results.push(Err(e));
+                }
+            }
+        }
+        transaction.commit().await?;
+        Ok(results)
+    }
+"##;
+
+pub static UPDATE_ENTITY_BATCH_FN: &str = r##"
+    pub async fn update_{sc_entity_name}_batch(
+        &self,
+        {sc_plural_entity}: &[&{entity_name}],
+        atomic: bool
+    ) -> Result<Vec<Result<{entity_name}, sqlx::Error>>, sqlx::Error> {
+        let mut transaction = self.pool.begin().await?;
+        let mut results = Vec::with_capacity({sc_plural_entity}.len());
+        for {sc_entity_name} in {sc_plural_entity} {
+            let mut savepoint = sqlx::Acquire::begin(&mut transaction).await?;
+            let updated_{sc_entity_name} = sqlx::query_as!(
+                {entity_name},
+                r#"{update_query}
+                "#,
+                {entity_values}
+            )
+            .fetch_one(savepoint.as_mut())
+            .await;
+            match updated_{sc_entity_name} {
+                Ok(updated_{sc_entity_name}) => {
+                    savepoint.commit().await?;
+                    results.push(Ok(updated_{sc_entity_name}));
+                }
+                Err(e) if atomic => return Err(e),
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    results.push(Err(e));
+                }
+            }
+        }
+        transaction.commit().await?;
+        Ok(results)
+    }
+"##;
+
+pub static DELETE_ENTITY_BATCH_FN: &str = r##"
+    pub async fn delete_{sc_entity_name}_batch(
+        &self,
+        {sc_entity_name}_keys: &[&{primary_key_type}],
+        atomic: bool
+    ) -> Result<Vec<Result<(), sqlx::Error>>, sqlx::Error> {
+        let mut transaction = self.pool.begin().await?;
+        let mut results = Vec::with_capacity({sc_entity_name}_keys.len());
+        for {sc_entity_name}_key in {sc_entity_name}_keys {
+            let {primary_key_pattern} = *{sc_entity_name}_key;
+            let mut savepoint = sqlx::Acquire::begin(&mut transaction).await?;
+            let deleted_{sc_entity_name} = sqlx::query!(
+                r#"{delete_query}
+                "#,
+                {primary_key_values}
+            )
+            .execute(savepoint.as_mut())
+            .await;
+            match deleted_{sc_entity_name} {
+                Ok(_) => {
+                    savepoint.commit().await?;
+                    results.push(Ok(()));
+                }
+                Err(e) if atomic => return Err(e),
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    results.push(Err(e));
+                }
+            }
+        }
+        transaction.commit().await?;
+        Ok(results)
+    }
+"##;
+
 pub static SOURCE_FILE_TEMPLATE: &str = r#"
 use std::sync::Arc;
-use uuid::Uuid;
 use sqlx::{Pool, Postgres};
 
 {entity_imports}
@@ -188,13 +503,76 @@ impl {entity_plural}Table {
 
 
 pub trait SourceGenerator : CrudQueryGenerator + ModelGenerator + ImportGenerator {
+    /**
+     * Every attribute of the primary key is a separate parameter: `id: &Uuid` or `user_id: &Uuid, role_id: &Uuid`.
+     */
+    fn generate_primary_key_fields(&self, entity: &Entity) -> String {
+        entity.primary_key_attributes().iter().map(|(primary_key, attribute_type)| {
+            FILTER_BY_FIELD
+                .replace("{attribute_name}", primary_key)
+                .replace("{attribute_type}", &attribute_type.to_string())
+        }).collect::<Vec<String>>().join(", ")
+    }
+
+    fn generate_primary_key_values(&self, entity: &Entity) -> String {
+        entity.primary_key_attributes().iter().map(|(primary_key, attribute_type)| {
+            self.generate_bind_value(primary_key, attribute_type)
+        }).collect::<Vec<String>>().join(", ")
+    }
+
+    fn generate_sort_field(&self, entity: &Entity) -> &'static str {
+        if entity.sortable.is_empty() { "" } else { SORT_FIELD }
+    }
+
+    fn generate_paging_fields(&self, entity: &Entity) -> &'static str {
+        if entity.cursor_by.is_some() { CURSOR_PAGING_FIELDS } else { PAGING_FIELDS }
+    }
+
+    /**
+     * A page of the entities that match `filter_by_fields`, if any, whose `filter_value_count` values are `filter_values`.
+     * Entities listed with cursors are paged after a cursor, the others by page number in the order of their sort.
+     */
+    fn generate_fetch_any_page(&self, entity: &Entity, filter_values: &str, filter_by_fields: &str, filter_value_count: usize, generate_query: impl Fn(Option<&SortKey>) -> String) -> String {
+        if entity.cursor_by.is_none() {
+            return self.generate_fetch_page(entity, filter_values, generate_query);
+        }
+        let values = if filter_values.is_empty() { String::new() } else { format!("\n                {},", filter_values) };
+        let cursor_values = entity.cursor_attributes().iter().map(|(attribute_name, attribute_type)| {
+            self.generate_bind_value(&format!("&cursor.{attribute_name}"), attribute_type)
+        }).collect::<Vec<String>>().join(", ");
+        FETCH_CURSOR_PAGE
+            .replace("{first_page_query}", &self.generate_cursor_page_query(entity, filter_by_fields, filter_value_count, false))
+            .replace("{after_cursor_query}", &self.generate_cursor_page_query(entity, filter_by_fields, filter_value_count, true))
+            .replace("{cursor_values}", &cursor_values)
+            .replace("{values}", &values)
+    }
+
+    /**
+     * The query of each sort is generated by `generate_query`, the values of the filter are bound before the limit and the offset.
+     */
+    fn generate_fetch_page(&self, entity: &Entity, filter_values: &str, generate_query: impl Fn(Option<&SortKey>) -> String) -> String {
+        let values = if filter_values.is_empty() { String::new() } else { format!("\n            {},", filter_values) };
+        let fetch_page = |sort_key: Option<&SortKey>| FETCH_PAGE
+            .replace("{query}", &generate_query(sort_key))
+            .replace("{values}", &values);
+        if entity.sortable.is_empty() {
+            return fetch_page(None);
+        }
+        let fetch_sorts = std::iter::once(("PrimaryKey".to_string(), fetch_page(None)))
+            .chain(entity.sort_keys().iter().map(|sort_key| (sort_key.variant_name(), fetch_page(Some(sort_key)))))
+            .map(|(variant_name, fetch_page)| {
+                FETCH_SORT
+                    .replace("{variant_name}", &variant_name)
+                    .replace("{fetch_page}", &fetch_page.replace('\n', "\n    "))
+            }).collect::<String>();
+        FETCH_SORTED_PAGE.replace("{fetch_sorts}", &fetch_sorts)
+    }
+
     fn generate_create_fn(&self, entity: &Entity) -> String {
         let sc_entity_name = to_snake_case(&entity.name);
         let create_query = self.generate_create_query(&entity);
         let entity_values = self.generate_entity_value_accessors(&entity);
         CREATE_ENTITY_FN
-            .replace("{primary_key}", &entity.primary_key)
-            .replace("{primary_key_type}", &entity.primary_key_type().to_string())
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity.name)
             .replace("{create_query}", &create_query)
@@ -205,33 +583,61 @@ pub trait SourceGenerator : CrudQueryGenerator + ModelGenerator + ImportGenerato
         let sc_entity_name = to_snake_case(&entity.name);
         let get_query = self.generate_get_query(&entity);
         GET_ENTITY_FN
-            .replace("{primary_key}", &entity.primary_key)
-            .replace("{primary_key_type}", &entity.primary_key_type().to_string())
+            .replace("{primary_key_fields}", &self.generate_primary_key_fields(entity))
+            .replace("{primary_key_values}", &self.generate_primary_key_values(entity))
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity.name)
             .replace("{get_query}", &get_query)
     }
 
+    fn generate_get_by_attribute_in_fns(&self, entity: &Entity, entities: &[Entity]) -> String {
+        entity.referenced_attributes(entities).into_iter().filter_map(|attribute_name| {
+            let attribute_type = entity.attribute_type(attribute_name)?.filter_type();
+            Some(GET_BY_ATTRIBUTE_IN_FN
+                .replace("{attribute_values}", &self.generate_bind_value(&format!("{}_values", attribute_name), attribute_type))
+                .replace("{get_by_attribute_in_query}", &self.generate_get_by_attribute_in_query(entity, attribute_name))
+                .replace("{attribute_type}", &attribute_type.to_string())
+                .replace("{attribute_name}", attribute_name)
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{entity_name}", &entity.name))
+        }).collect::<Vec<String>>().join("")
+    }
+
     fn generate_get_paginated_fn(&self, entity: &Entity) -> String {
-        let get_paginated_query = self.generate_get_paginated_query(&entity);
         GET_PAGINATED_FN
+            .replace("{sort_field}", self.generate_sort_field(entity))
+            .replace("{paging_fields}", self.generate_paging_fields(entity))
+            .replace("{fetch_page}", &self.generate_fetch_page(entity, "", |sort_key| self.generate_get_paginated_query(entity, sort_key)))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{entity_name}", &entity.name)
+    }
+
+    /**
+     * Entities listed with cursors are paged after a cursor instead of by page number.
+     */
+    fn generate_get_page_fn(&self, entity: &Entity) -> String {
+        if entity.cursor_by.is_none() {
+            return SourceGenerator::generate_get_paginated_fn(self, entity);
+        }
+        GET_AFTER_CURSOR_FN
+            .replace("{fetch_page}", &self.generate_fetch_any_page(entity, "", "", 0, |_| String::new()))
             .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
             .replace("{entity_name}", &entity.name)
-            .replace("{get_paginated_query}", &get_paginated_query)
     }
 
     fn generate_filter_by_fn(&self, entity: &Entity) -> String {
-        entity.filter_by.iter().map(|filter_by| {
+        entity.filters().iter().map(|filter_by| {
           
             let filter_by_values = filter_by.iter().map(|field_name| {
-                format!("&{}", field_name)
+                let attribute_type = &entity.attributes.iter().find(|(attribute_name, _)| attribute_name == field_name).unwrap().1;
+                self.generate_bind_value(&format!("&{}", field_name), attribute_type.filter_type())
             }).collect::<Vec<String>>().join(", ");
             let filter_by_fields = filter_by.iter().map(|field_name| {
                 FILTER_BY_FIELD
                 .replace("{attribute_name}", &field_name)
-                .replace("{attribute_type}", &entity.attributes.iter().find(|(attribute_name, _)| attribute_name == field_name).unwrap().1.to_string())
+                .replace("{attribute_type}", &entity.attributes.iter().find(|(attribute_name, _)| attribute_name == field_name).unwrap().1.filter_type().to_string())
             }).collect::<Vec<String>>().join("\n, ");
-            if filter_by.iter().filter(|field_name| entity.is_unique(&field_name)).count() > 0 {
+            if filter_by.iter().filter(|field_name| entity.is_unique(field_name)).count() > 0 {
                 let filter_by_query = self.generate_filter_by_query(&entity, &filter_by);
                 FILTER_BY_FN
                 .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
@@ -243,11 +649,15 @@ pub trait SourceGenerator : CrudQueryGenerator + ModelGenerator + ImportGenerato
 
 
             } else {
-                let filter_by_paginated_query = self.generate_filter_by_paginated_query(&entity, &filter_by);
+                let fetch_page = self.generate_fetch_any_page(entity, &filter_by_values, &self.generate_filter_by_fields(entity, filter_by), filter_by.len(), |sort_key| {
+                    self.generate_filter_by_paginated_query(entity, filter_by, sort_key)
+                });
                 FILTER_BY_PAGINATED_FN
+                .replace("{sort_field}", self.generate_sort_field(entity))
+                .replace("{paging_fields}", self.generate_paging_fields(entity))
+                .replace("{fetch_page}", &fetch_page)
                 .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
                 .replace("{entity_name}", &entity.name)
-                .replace("{filter_by_paginated_query}", &filter_by_paginated_query)
                 .replace("{filter_by_values}", &filter_by_values)
                 .replace("{most_specific_attribute}", &filter_by.last().unwrap())
                 .replace("{filter_by_fields}", &filter_by_fields)
@@ -259,14 +669,18 @@ pub trait SourceGenerator : CrudQueryGenerator + ModelGenerator + ImportGenerato
     }
 
     fn generate_filter_by_paginated_count_fn(&self, entity: &Entity) -> String {
-        entity.filter_by.iter().filter(|filter_by| filter_by.iter().filter(|field_name| entity.is_unique(&field_name)).count() == 0).map(|filter_by| {
+        if entity.cursor_by.is_some() {
+            return String::new();
+        }
+        entity.filters().iter().filter(|filter_by| filter_by.iter().filter(|field_name| entity.is_unique(field_name)).count() == 0).map(|filter_by| {
             let filter_by_values = filter_by.iter().map(|field_name| {
-                format!("&{}", field_name)
+                let attribute_type = &entity.attributes.iter().find(|(attribute_name, _)| attribute_name == field_name).unwrap().1;
+                self.generate_bind_value(&format!("&{}", field_name), attribute_type.filter_type())
             }).collect::<Vec<String>>().join(", ");
             let filter_by_fields = filter_by.iter().map(|field_name| {
                 FILTER_BY_FIELD
                 .replace("{attribute_name}", &field_name)
-                .replace("{attribute_type}", &entity.attributes.iter().find(|(attribute_name, _)| attribute_name == field_name).unwrap().1.to_string())
+                .replace("{attribute_type}", &entity.attributes.iter().find(|(attribute_name, _)| attribute_name == field_name).unwrap().1.filter_type().to_string())
             }).collect::<Vec<String>>().join("\n, ");
             let filter_by_paginated_count_query = self.generate_filter_by_paginated_count_query(&entity, &filter_by);
             FILTER_BY_PAGINATED_COUNT_FN
@@ -279,7 +693,81 @@ pub trait SourceGenerator : CrudQueryGenerator + ModelGenerator + ImportGenerato
         }).collect::<Vec<String>>().join("\n")
     }
 
+    /**
+     * Match filters are paginated like the other filters, the value of the filter is bound as a pattern.
+     */
+    fn generate_match_filter_fns(&self, entity: &Entity) -> String {
+        entity.match_filters.iter().map(|match_filter| {
+            let param_name = match_filter.param_name();
+            let filter_by_fields = FILTER_BY_FIELD
+                .replace("{attribute_name}", &param_name)
+                .replace("{attribute_type}", "String");
+            let fetch_page = self.generate_fetch_any_page(entity, &param_name, &self.generate_match_filter_field(match_filter), 1, |sort_key| {
+                self.generate_match_filter_paginated_query(entity, match_filter, sort_key)
+            });
+            let paginated_fn = FILTER_BY_PAGINATED_FN
+                .replace("{sort_field}", self.generate_sort_field(entity))
+                .replace("{paging_fields}", self.generate_paging_fields(entity))
+                .replace("{fetch_page}", &fetch_page);
+            // Entities listed with cursors are not counted
+            let count_fn = match entity.cursor_by {
+                Some(_) => String::new(),
+                None => FILTER_BY_PAGINATED_COUNT_FN
+                    .replace("{filter_by_paginated_count_query}", &self.generate_match_filter_count_query(entity, match_filter)),
+            };
+            (paginated_fn + &count_fn)
+                .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+                .replace("{entity_name}", &entity.name)
+                .replace("{filter_by_values}", &param_name)
+                .replace("{most_specific_attribute}", &param_name)
+                .replace("{filter_by_fields}", &filter_by_fields)
+        }).collect::<Vec<String>>().join("\n")
+    }
+
+    fn generate_filter_by_range_fn(&self, entity: &Entity) -> String {
+        let range_attributes = entity.range_filter_attributes();
+        if range_attributes.is_empty() {
+            return String::new();
+        }
+        let range_fields = range_attributes.iter().map(|(attribute_name, attribute_type)| {
+            RANGE_FIELD
+                .replace("{attribute_name}", attribute_name)
+                .replace("{attribute_type}", &attribute_type.filter_type().to_string())
+        }).collect::<Vec<String>>().join(",\n        ");
+        let range_values = range_attributes.iter().map(|(attribute_name, _)| {
+            RANGE_VALUES.replace("{attribute_name}", attribute_name)
+        }).collect::<Vec<String>>().join(", ");
+        let fetch_page = self.generate_fetch_any_page(entity, &range_values, &self.generate_filter_by_range_fields(entity), 2 * range_attributes.len(), |sort_key| {
+            self.generate_filter_by_range_paginated_query(entity, sort_key)
+        });
+        let count_fn = if entity.cursor_by.is_some() { "" } else { FILTER_BY_RANGE_COUNT_FN };
+        (FILTER_BY_RANGE_PAGINATED_FN.to_string() + count_fn)
+            .replace("{sort_field}", self.generate_sort_field(entity))
+            .replace("{paging_fields}", self.generate_paging_fields(entity))
+            .replace("{fetch_page}", &fetch_page)
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{entity_name}", &entity.name)
+            .replace("{filter_by_range_count_query}", &self.generate_filter_by_range_count_query(entity))
+            .replace("{range_fields}", &range_fields)
+            .replace("{range_values}", &range_values)
+    }
+
+    fn generate_search_fn(&self, entity: &Entity) -> String {
+        if entity.searchable.is_empty() {
+            return String::new();
+        }
+        SEARCH_PAGINATED_FN
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{entity_name}", &entity.name)
+            .replace("{search_paginated_query}", &self.generate_search_paginated_query(entity))
+            .replace("{search_count_query}", &self.generate_search_count_query(entity))
+    }
+
     fn generate_get_count_fn(&self, entity: &Entity) -> String {
+        // Entities listed with cursors are not counted
+        if entity.cursor_by.is_some() {
+            return String::new();
+        }
         let count_query = self.generate_count_query(&entity);
         GET_COUNT_FN
             .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
@@ -290,7 +778,10 @@ pub trait SourceGenerator : CrudQueryGenerator + ModelGenerator + ImportGenerato
     fn generate_update_fn(&self, entity: &Entity) -> String {
         let sc_entity_name = to_snake_case(&entity.name);
         let update_query = self.generate_update_query(entity);
-        let entity_values = self.generate_entity_value_accessors(entity) + &format!("{}.{}, ", to_snake_case(&entity.name), entity.primary_key);
+        let primary_key_values = entity.primary_key_attributes().iter().map(|(primary_key, attribute_type)| {
+            format!("{}, ", self.generate_bind_value(&format!("{}.{}", to_snake_case(&entity.name), primary_key), attribute_type))
+        }).collect::<String>();
+        let entity_values = self.generate_entity_value_accessors(entity) + &primary_key_values;
         
         UPDATE_ENTITY_FN
             .replace("{sc_entity_name}", &sc_entity_name)
@@ -303,30 +794,120 @@ pub trait SourceGenerator : CrudQueryGenerator + ModelGenerator + ImportGenerato
         let sc_entity_name = to_snake_case(&entity.name);
         let delete_query = self.generate_delete_query(&entity);
         DELETE_ENTITY_FN
-            .replace("{primary_key}", &entity.primary_key)
-            .replace("{primary_key_type}", &entity.primary_key_type().to_string())
+            .replace("{primary_key_fields}", &self.generate_primary_key_fields(entity))
+            .replace("{primary_key_values}", &self.generate_primary_key_values(entity))
             .replace("{sc_entity_name}", &sc_entity_name)
             .replace("{entity_name}", &entity.name)
             .replace("{delete_query}", &delete_query)
     }
 
-    fn generate_source(&self, entity: &Entity) -> String {
-        let entity_imports = self.generate_model_imports(&entity);
+    fn generate_create_batch_fn(&self, entity: &Entity) -> String {
+        CREATE_ENTITY_BATCH_FN
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{entity_name}", &entity.name)
+            .replace("{create_query}", &self.generate_create_query(entity))
+            .replace("{entity_values}", &self.generate_entity_value_accessors(entity))
+    }
+
+    fn generate_update_batch_fn(&self, entity: &Entity) -> String {
+        let primary_key_values = entity.primary_key_attributes().iter().map(|(primary_key, attribute_type)| {
+            format!("{}, ", self.generate_bind_value(&format!("{}.{}", to_snake_case(&entity.name), primary_key), attribute_type))
+        }).collect::<String>();
+        UPDATE_ENTITY_BATCH_FN
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{sc_plural_entity}", &to_snake_case(&entity.plural_name))
+            .replace("{entity_name}", &entity.name)
+            .replace("{update_query}", &self.generate_update_query(entity))
+            .replace("{entity_values}", &(self.generate_entity_value_accessors(entity) + &primary_key_values))
+    }
+
+    /**
+     * The key of every entity is destructured into its attributes, which are bound like in `delete_{entity}`.
+     */
+    fn generate_delete_batch_fn(&self, entity: &Entity) -> String {
+        let primary_key_pattern = if entity.has_composite_primary_key() {
+            format!("({})", entity.primary_key.join(", "))
+        } else {
+            entity.primary_key.join("")
+        };
+        DELETE_ENTITY_BATCH_FN
+            .replace("{sc_entity_name}", &to_snake_case(&entity.name))
+            .replace("{primary_key_type}", &entity.primary_key_type())
+            .replace("{primary_key_pattern}", &primary_key_pattern)
+            .replace("{primary_key_values}", &self.generate_primary_key_values(entity))
+            .replace("{delete_query}", &self.generate_delete_query(entity))
+    }
+
+    fn generate_source(&self, entity: &Entity, entities: &[Entity]) -> String {
+        let entity_imports = self.generate_uuid_imports(entity.uses_uuids()) + &self.generate_model_imports(entity) + &self.generate_custom_type_imports(entity);
 
         let mut source_functions = String::new();
         source_functions.push_str(SourceGenerator::generate_create_fn(self, &entity).as_str());
         source_functions.push_str(SourceGenerator::generate_get_fn(self, &entity).as_str());
-        source_functions.push_str(SourceGenerator::generate_get_paginated_fn(self, &entity).as_str());
+        source_functions.push_str(&self.generate_get_by_attribute_in_fns(entity, entities));
+        source_functions.push_str(&self.generate_get_page_fn(entity));
         source_functions.push_str(SourceGenerator::generate_filter_by_fn(self, &entity).as_str());
         source_functions.push_str(SourceGenerator::generate_filter_by_paginated_count_fn(self, &entity).as_str());
+        source_functions.push_str(&self.generate_match_filter_fns(entity));
+        source_functions.push_str(&self.generate_filter_by_range_fn(entity));
+        source_functions.push_str(&self.generate_search_fn(entity));
         source_functions.push_str(SourceGenerator::generate_get_count_fn(self, &entity).as_str());
         source_functions.push_str(SourceGenerator::generate_update_fn(self, &entity).as_str());
         source_functions.push_str(SourceGenerator::generate_delete_fn(self, &entity).as_str());
+        source_functions.push_str(&self.generate_create_batch_fn(entity));
+        source_functions.push_str(&self.generate_update_batch_fn(entity));
+        source_functions.push_str(&self.generate_delete_batch_fn(entity));
 
         SOURCE_FILE_TEMPLATE
             .replace("{entity_imports}", &entity_imports)
             .replace("{entity_plural}", &entity.plural_name)
             .replace("{source_functions}", &source_functions)
     }
-    
-}
\ No newline at end of file
+
+    /**
+     * The source of a join table: linking, unlinking and listing the entities related to either side.
+     */
+    fn generate_relation_source(&self, relation: &Relation, entities: &[Entity]) -> String {
+        let relation_fields = relation.ends.iter().map(|end| {
+            FILTER_BY_FIELD
+                .replace("{attribute_name}", &end.attribute_name)
+                .replace("{attribute_type}", &end.attribute_type.to_string())
+        }).collect::<Vec<String>>().join(",\n        ");
+        let relation_values = relation.ends.iter().map(|end| {
+            self.generate_bind_value(&end.attribute_name, &end.attribute_type)
+        }).collect::<Vec<String>>().join(", ");
+
+        let mut entity_imports = self.generate_uuid_imports(relation.uses_uuids());
+        let mut source_functions = String::new();
+        source_functions.push_str(&LINK_FN
+            .replace("{relation_fields}", &relation_fields)
+            .replace("{relation_values}", &relation_values)
+            .replace("{link_query}", &self.generate_link_query(relation)));
+        source_functions.push_str(&UNLINK_FN
+            .replace("{relation_fields}", &relation_fields)
+            .replace("{relation_values}", &relation_values)
+            .replace("{unlink_query}", &self.generate_unlink_query(relation)));
+        for (end, related_end) in relation.directions() {
+            let Some(related_entity) = entities.iter().find(|entity| entity.name == related_end.entity_name) else {
+                continue;
+            };
+            entity_imports.push_str(&(self.generate_model_imports(related_entity) + &self.generate_custom_type_imports(related_entity)));
+            source_functions.push_str(&GET_RELATED_FN
+                .replace("{attribute_field}", &FILTER_BY_FIELD
+                    .replace("{attribute_name}", &end.attribute_name)
+                    .replace("{attribute_type}", &end.attribute_type.to_string()))
+                .replace("{attribute_value}", &self.generate_bind_value(&end.attribute_name, &end.attribute_type))
+                .replace("{get_related_paginated_query}", &self.generate_get_related_paginated_query(relation, end, related_end, related_entity))
+                .replace("{count_related_query}", &self.generate_count_related_query(relation, end))
+                .replace("{sc_related_plural_entity}", &to_snake_case(&related_end.plural_name))
+                .replace("{related_entity_name}", &related_end.entity_name)
+                .replace("{sc_entity_name}", &to_snake_case(&end.entity_name)));
+        }
+
+        SOURCE_FILE_TEMPLATE
+            .replace("{entity_imports}", &entity_imports)
+            .replace("{entity_plural}", &relation.plural_name)
+            .replace("{source_functions}", &source_functions)
+    }
+}
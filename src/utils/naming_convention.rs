@@ -16,6 +16,20 @@ pub fn to_camel_case(snake_case: &str) -> String {
     camel_case
 }
 
+/**
+ * Converts a value such as `in_progress` or `in-progress` to `InProgress`.
+ */
+pub fn to_pascal_case(value: &str) -> String {
+    value
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect::<String>()
+}
+
 pub fn to_snake_case(camel_case: &str) -> String {
     let mut snake_case = String::new();
     let mut camel_case_chars = camel_case.chars();
@@ -32,6 +46,65 @@ pub fn to_snake_case(camel_case: &str) -> String {
     snake_case
 }
 
+/**
+ * Singular and plural forms of nouns that do not follow the regular rules, in lowercase.
+ */
+static IRREGULAR_PLURALS: [(&str, &str); 23] = [
+    ("person", "people"),
+    ("man", "men"),
+    ("woman", "women"),
+    ("child", "children"),
+    ("foot", "feet"),
+    ("tooth", "teeth"),
+    ("goose", "geese"),
+    ("mouse", "mice"),
+    ("ox", "oxen"),
+    ("leaf", "leaves"),
+    ("knife", "knives"),
+    ("life", "lives"),
+    ("wife", "wives"),
+    ("half", "halves"),
+    ("shelf", "shelves"),
+    ("wolf", "wolves"),
+    ("quiz", "quizzes"),
+    ("sheep", "sheep"),
+    ("fish", "fish"),
+    ("deer", "deer"),
+    ("series", "series"),
+    ("species", "species"),
+    ("news", "news"),
+];
+
+/**
+ * Converts a singular name such as `Category` or `OrderItem` to its English plural: `Categories`, `OrderItems`.
+ * Only the last word of a PascalCase or snake_case name is pluralized.
+ */
+pub fn to_plural(singular: &str) -> String {
+    let last_word_start = singular
+        .char_indices()
+        .rev()
+        .find(|(index, c)| c.is_ascii_uppercase() || (*c == '_' && index + 1 < singular.len()))
+        .map(|(index, c)| if c == '_' { index + 1 } else { index })
+        .unwrap_or(0);
+    let (prefix, last_word) = singular.split_at(last_word_start);
+    let lowercase_last_word = last_word.to_ascii_lowercase();
+
+    if let Some((_, plural)) = IRREGULAR_PLURALS.iter().find(|(irregular, _)| *irregular == lowercase_last_word) {
+        let capitalized = last_word.starts_with(|c: char| c.is_ascii_uppercase());
+        let plural = if capitalized { to_pascal_case(plural) } else { plural.to_string() };
+        return format!("{}{}", prefix, plural);
+    }
+    let ends_with_consonant_y = lowercase_last_word.ends_with('y')
+        && !lowercase_last_word[..lowercase_last_word.len() - 1].ends_with(['a', 'e', 'i', 'o', 'u']);
+    if ends_with_consonant_y {
+        format!("{}ies", &singular[..singular.len() - 1])
+    } else if ["s", "x", "z", "ch", "sh"].iter().any(|suffix| lowercase_last_word.ends_with(suffix)) {
+        format!("{}es", singular)
+    } else {
+        format!("{}s", singular)
+    }
+}
+
 pub fn to_upper_snake_case(camel_case: &str) -> String {
     to_snake_case(camel_case).to_ascii_uppercase()
 }
@@ -41,4 +114,36 @@ pub fn to_upper_snake_case(camel_case: &str) -> String {
 
 pub fn kebab_to_snake_case(kebab_case: &str) -> String {
     kebab_case.replace("-", "_")
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_plural;
+
+    #[test]
+    fn pluralizes_regular_nouns() {
+        assert_eq!(to_plural("Key"), "Keys");
+        assert_eq!(to_plural("Category"), "Categories");
+        assert_eq!(to_plural("Box"), "Boxes");
+        assert_eq!(to_plural("Match"), "Matches");
+        assert_eq!(to_plural("Address"), "Addresses");
+    }
+
+    #[test]
+    fn pluralizes_the_last_word_of_compound_names() {
+        assert_eq!(to_plural("OrderItem"), "OrderItems");
+        assert_eq!(to_plural("ProductCategory"), "ProductCategories");
+        assert_eq!(to_plural("order_item"), "order_items");
+        assert_eq!(to_plural("sales_person"), "sales_people");
+    }
+
+    #[test]
+    fn pluralizes_irregular_nouns() {
+        assert_eq!(to_plural("Person"), "People");
+        assert_eq!(to_plural("child"), "children");
+        assert_eq!(to_plural("SalesPerson"), "SalesPeople");
+        assert_eq!(to_plural("Human"), "Humans");
+        assert_eq!(to_plural("Quiz"), "Quizzes");
+        assert_eq!(to_plural("PopQuiz"), "PopQuizzes");
+    }
+}
@@ -0,0 +1,128 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::postgres::types::PgInterval;
+
+const MICROSECONDS_PER_SECOND: i64 = 1_000_000;
+const MICROSECONDS_PER_MINUTE: i64 = 60 * MICROSECONDS_PER_SECOND;
+const MICROSECONDS_PER_HOUR: i64 = 60 * MICROSECONDS_PER_MINUTE;
+
+/**
+ * A Postgres INTERVAL, (de)serialized as an ISO 8601 duration such as `P1Y2M3DT4H5M6.5S`.
+ * sqlx only decodes intervals into `PgInterval`, which cannot be serialized.
+ */
+#[derive(Debug, Clone, PartialEq, sqlx::Type)]
+#[sqlx(transparent)]
+pub struct Interval(pub PgInterval);
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let PgInterval { months, days, microseconds } = self.0;
+        if months == 0 && days == 0 && microseconds == 0 {
+            return write!(f, "PT0S");
+        }
+        write!(f, "P")?;
+        if months / 12 != 0 {
+            write!(f, "{}Y", months / 12)?;
+        }
+        if months % 12 != 0 {
+            write!(f, "{}M", months % 12)?;
+        }
+        if days != 0 {
+            write!(f, "{}D", days)?;
+        }
+        if microseconds != 0 {
+            write!(f, "T")?;
+            let hours = microseconds / MICROSECONDS_PER_HOUR;
+            let minutes = microseconds % MICROSECONDS_PER_HOUR / MICROSECONDS_PER_MINUTE;
+            let seconds = microseconds % MICROSECONDS_PER_MINUTE;
+            if hours != 0 {
+                write!(f, "{}H", hours)?;
+            }
+            if minutes != 0 {
+                write!(f, "{}M", minutes)?;
+            }
+            if seconds != 0 {
+                let sign = if seconds < 0 { "-" } else { "" };
+                let (whole, fraction) = (seconds.abs() / MICROSECONDS_PER_SECOND, seconds.abs() % MICROSECONDS_PER_SECOND);
+                if fraction == 0 {
+                    write!(f, "{}{}S", sign, whole)?;
+                } else {
+                    write!(f, "{}{}.{}S", sign, whole, format!("{:06}", fraction).trim_end_matches('0'))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("{} is not an ISO 8601 duration, such as P1DT2H30M", s);
+        let duration = s.strip_prefix('P').filter(|duration| !duration.is_empty()).ok_or_else(invalid)?;
+        let (date, time) = match duration.split_once('T') {
+            Some((_, "")) => return Err(invalid()),
+            Some((date, time)) => (date, time),
+            None => (duration, ""),
+        };
+
+        let mut interval = PgInterval { months: 0, days: 0, microseconds: 0 };
+        for (value, unit) in components(date).ok_or_else(invalid)? {
+            let value = value.parse::<i32>().map_err(|_| invalid())?;
+            match unit {
+                'Y' => interval.months += value * 12,
+                'M' => interval.months += value,
+                'W' => interval.days += value * 7,
+                'D' => interval.days += value,
+                _ => return Err(invalid()),
+            }
+        }
+        for (value, unit) in components(time).ok_or_else(invalid)? {
+            match unit {
+                'H' => interval.microseconds += value.parse::<i64>().map_err(|_| invalid())? * MICROSECONDS_PER_HOUR,
+                'M' => interval.microseconds += value.parse::<i64>().map_err(|_| invalid())? * MICROSECONDS_PER_MINUTE,
+                'S' => interval.microseconds += (value.parse::<f64>().map_err(|_| invalid())? * MICROSECONDS_PER_SECOND as f64).round() as i64,
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(Interval(interval))
+    }
+}
+
+/**
+ * Splits `1Y2M` into `[("1", 'Y'), ("2", 'M')]`.
+ */
+fn components(s: &str) -> Option<Vec<(&str, char)>> {
+    let mut components = Vec::new();
+    let mut start = 0;
+    for (index, c) in s.char_indices() {
+        if c.is_ascii_alphabetic() {
+            if index == start {
+                return None;
+            }
+            components.push((&s[start..index], c));
+            start = index + 1;
+        }
+    }
+    if start == s.len() { Some(components) } else { None }
+}
+
+impl Serialize for Interval {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interval {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
@@ -1,4 +1,10 @@
-use serde::{Deserialize, Serialize};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error::Error;
 
 #[derive(Deserialize)]
 pub struct PaginatedParams {
@@ -6,6 +12,26 @@ pub struct PaginatedParams {
     pub page_size: Option<i64>,
 }
 
+/**
+ * The paging of an entity listed with cursors. `page` is only read to reject it, since it has no effect on a cursor.
+ */
+#[derive(Deserialize)]
+pub struct CursorParams {
+    pub cursor: Option<String>,
+    pub page_size: Option<i64>,
+    pub page: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct ExpandParams {
+    pub expand: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SortParams {
+    pub sort: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PaginatedResult<T: Serialize> {
     pub results: Vec<T>,
@@ -14,4 +40,64 @@ pub struct PaginatedResult<T: Serialize> {
     pub page_size: i64,
 }
 
+/**
+ * A page of an entity listed with cursors. `next_cursor` is passed as `cursor` to get the next page,
+ * and is missing on the last page.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CursorPage<T: Serialize> {
+    pub results: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub page_size: i64,
+}
+
+/**
+ * Cursors are opaque to the clients: the keys of the last entity of a page, as URL safe base64 of their JSON.
+ */
+pub fn encode_cursor<T: Serialize>(keys: &T) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(keys).unwrap_or_default())
+}
+
+pub fn decode_cursor<T: DeserializeOwned>(cursor: &str) -> Option<T> {
+    let keys = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    serde_json::from_slice(&keys).ok()
+}
+
+#[derive(Deserialize)]
+pub struct BatchParams {
+    pub atomic: Option<bool>,
+}
+
+/**
+ * The outcome of an entity of a batch that is not atomic: the entity, or the client error
+ * that the route of a single entity would have returned.
+ */
+#[derive(Serialize, Debug)]
+pub struct BatchResult<T: Serialize> {
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/**
+ * An atomic batch only succeeds when every entity does, it returns the entities with `status`.
+ * Any other batch returns the outcome of every entity with 207 Multi-Status.
+ */
+pub fn batch_response<T: Serialize>(results: Vec<Result<T, Error>>, atomic: bool, status: StatusCode) -> Response {
+    if atomic {
+        let results = results.into_iter().filter_map(Result::ok).collect::<Vec<T>>();
+        return (status, Json(results)).into_response();
+    }
+    let results = results.into_iter().map(|result| match result {
+        Ok(result) => BatchResult { status: status.as_u16(), result: Some(result), error: None },
+        Err(e) => {
+            let (error_status, client_error) = e.client_status_and_error();
+            BatchResult { status: error_status.as_u16(), result: None, error: Some(client_error.as_ref().to_string()) }
+        }
+    }).collect::<Vec<BatchResult<T>>>();
+    (StatusCode::MULTI_STATUS, Json(results)).into_response()
+}
+
 pub mod config;
@@ -0,0 +1,54 @@
+use std::{path::PathBuf, process::Command};
+
+use sqlx::{Connection, Executor, PgConnection};
+
+static SERVICE_DATABASE: &str = "lightspeed_shop_service";
+
+/**
+ * Generates a service from a request that uses every feature of the generator, and builds it.
+ * sqlx checks the queries of the service at compile time, so the migrations are first applied to a fresh database
+ * on the Postgres server of `DATABASE_URL`:
+ * `DATABASE_URL=postgres://postgres@localhost/postgres cargo test -- --ignored`
+ */
+#[tokio::test]
+#[ignore = "needs a Postgres server at DATABASE_URL"]
+async fn generated_service_builds() {
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must point to a Postgres server");
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/shop.yaml");
+    let output = std::env::temp_dir().join(SERVICE_DATABASE);
+    let _ = std::fs::remove_dir_all(&output);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_lightspeed"))
+        .arg("-i").arg(&fixture)
+        .arg("-o").arg(&output)
+        .status()
+        .expect("Could not run the generator");
+    assert!(status.success(), "The generation failed");
+
+    let mut connection = PgConnection::connect(&database_url).await.expect("Could not connect to DATABASE_URL");
+    connection.execute(format!("DROP DATABASE IF EXISTS {SERVICE_DATABASE}").as_str()).await.unwrap();
+    connection.execute(format!("CREATE DATABASE {SERVICE_DATABASE}").as_str()).await.unwrap();
+    let (server_url, _) = database_url.rsplit_once('/').expect("DATABASE_URL must end with a database name");
+    let service_database_url = format!("{server_url}/{SERVICE_DATABASE}");
+
+    // The migrations are prefixed with their timestamp, and contain several statements each
+    let mut service_connection = PgConnection::connect(&service_database_url).await.unwrap();
+    let mut migrations = std::fs::read_dir(output.join("migrations")).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<PathBuf>>();
+    migrations.sort();
+    for migration in migrations {
+        let sql = std::fs::read_to_string(&migration).unwrap();
+        if let Err(e) = service_connection.execute(sql.as_str()).await {
+            panic!("Could not apply the migration {}: {e}", migration.display());
+        }
+    }
+
+    let status = Command::new(env!("CARGO"))
+        .arg("build")
+        .current_dir(&output)
+        .env("DATABASE_URL", &service_database_url)
+        .status()
+        .expect("Could not run cargo");
+    assert!(status.success(), "The generated service does not build");
+}